@@ -0,0 +1,166 @@
+//! Sampling nibbles from an arbitrary distribution with Vose's alias
+//! method: one uniform column, one biased coin, constant time.
+//!
+//! ```
+//! use u4::{alias::AliasTable, U4};
+//!
+//! let mut weights = [0; 16];
+//! weights[3] = 1;
+//! weights[7] = 3;
+//! let table = AliasTable::from_weights(weights).unwrap();
+//! assert_eq!(table.probability(U4::n(7)), 0.75);
+//! // Any 64 random bits give a sample; with the rand feature the table is
+//! // also a `Distribution<U4>`.
+//! assert!([U4::n(3), U4::n(7)].contains(&table.sample_bits(0x1234_5678_9abc_def0)));
+//! ```
+
+use core::fmt::{self, Display, Formatter};
+
+use crate::U4;
+
+/// One in fixed point, the coin threshold of a column that never aliases.
+const ONE: u64 = 1 << 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightError {
+    /// A weight is negative, infinite or NaN.
+    Invalid,
+    /// Every weight is zero.
+    AllZero,
+}
+
+impl Display for WeightError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid => write!(f, "weights must be finite and non-negative"),
+            Self::AllZero => write!(f, "at least one weight must be positive"),
+        }
+    }
+}
+
+impl core::error::Error for WeightError {}
+
+/// A precomputed sampler for a distribution over the 16 nibbles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AliasTable {
+    /// Chance, out of [`ONE`], of keeping a column rather than taking its
+    /// alias.
+    threshold: [u64; 16],
+    alias: [U4; 16],
+}
+
+impl AliasTable {
+    /// A table drawing `n` with probability proportional to `weights[n]`.
+    pub fn from_weights(weights: [u64; 16]) -> Result<Self, WeightError> {
+        let total: u128 = weights.iter().map(|&w| w as u128).sum();
+        if total == 0 {
+            return Err(WeightError::AllZero);
+        }
+        // Each column holds an average of `total`; scale so that's 16 * total.
+        let mut scaled = weights.map(|w| w as u128 * 16);
+        let mut threshold = [ONE; 16];
+        let mut alias: [U4; 16] = core::array::from_fn(|i| U4::from_u8(i as u8));
+        let (mut small, mut large) = ([0; 16], [0; 16]);
+        let (mut ns, mut nl) = (0, 0);
+        for (i, &s) in scaled.iter().enumerate() {
+            if s < total {
+                small[ns] = i;
+                ns += 1;
+            } else {
+                large[nl] = i;
+                nl += 1;
+            }
+        }
+        while ns > 0 && nl > 0 {
+            ns -= 1;
+            let (s, l) = (small[ns], large[nl - 1]);
+            threshold[s] = (scaled[s] * ONE as u128 / total) as u64;
+            alias[s] = U4::from_u8(l as u8);
+            scaled[l] -= total - scaled[s];
+            if scaled[l] < total {
+                nl -= 1;
+                small[ns] = l;
+                ns += 1;
+            }
+        }
+        Ok(Self { threshold, alias })
+    }
+
+    /// A table drawing `n` with probability proportional to `weights[n]`.
+    /// Weights are rounded to 52 bits of precision relative to their sum.
+    pub fn from_f64(weights: [f64; 16]) -> Result<Self, WeightError> {
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err(WeightError::Invalid);
+        }
+        let total: f64 = weights.iter().sum();
+        if total == 0.0 {
+            return Err(WeightError::AllZero);
+        }
+        Self::from_weights(weights.map(|w| (w / total * (1_u64 << 52) as f64) as u64))
+    }
+
+    /// The sample for 64 uniformly random bits: the low four pick a column
+    /// and the high 32 toss its coin.
+    pub fn sample_bits(&self, bits: u64) -> U4 {
+        let column = (bits & 0xf) as usize;
+        if bits >> 32 < self.threshold[column] {
+            U4::from_u8(column as u8)
+        } else {
+            self.alias[column]
+        }
+    }
+
+    /// The exact probability of drawing `n`, including the rounding of the
+    /// coin thresholds to 32 bits.
+    pub fn probability(&self, n: U4) -> f64 {
+        let kept = self.threshold[n.to_u8() as usize];
+        let aliased: u64 = (0..16)
+            .filter(|&c| self.alias[c] == n && c != n.to_u8() as usize)
+            .map(|c| ONE - self.threshold[c])
+            .sum();
+        (kept + aliased) as f64 / (16 * ONE) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_probabilities() {
+        let weights = [5, 0, 1, 9, 0, 0, 2, 2, 7, 1, 1, 0, 30, 3, 0, 4];
+        let total: u64 = weights.iter().sum();
+        let table = AliasTable::from_weights(weights).unwrap();
+        for n in (0..16).map(U4::n) {
+            let expected = weights[n.to_u8() as usize] as f64 / total as f64;
+            assert!((table.probability(n) - expected).abs() < 1e-9, "{n}");
+        }
+
+        let uniform = AliasTable::from_weights([1; 16]).unwrap();
+        for bits in [0, u64::MAX, 0xffff_ffff_0000_0007] {
+            assert_eq!(uniform.sample_bits(bits), U4::from_u8(bits as u8));
+        }
+    }
+
+    #[test]
+    fn point_mass() {
+        let mut weights = [0.0; 16];
+        weights[9] = 0.5;
+        let table = AliasTable::from_f64(weights).unwrap();
+        for bits in (0..64).map(|i| 0x9e37_79b9_7f4a_7c15_u64.rotate_left(i)) {
+            assert_eq!(table.sample_bits(bits), U4::n(9));
+        }
+        assert_eq!(table.probability(U4::n(9)), 1.0);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(AliasTable::from_weights([0; 16]), Err(WeightError::AllZero));
+        assert_eq!(AliasTable::from_f64([0.0; 16]), Err(WeightError::AllZero));
+        let mut w = [1.0; 16];
+        w[2] = -1.0;
+        assert_eq!(AliasTable::from_f64(w), Err(WeightError::Invalid));
+        w[2] = f64::NAN;
+        assert_eq!(AliasTable::from_f64(w), Err(WeightError::Invalid));
+    }
+}
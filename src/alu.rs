@@ -0,0 +1,174 @@
+use crate::U4;
+
+/// Status flags produced by [`Alu4`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Flags {
+    /// Carry out of bit 3. Set on borrow for subtraction, and to the bit
+    /// shifted or rotated out for shifts and rotates.
+    pub carry: bool,
+    pub zero: bool,
+    /// Two's complement overflow.
+    pub overflow: bool,
+    /// Bit 3 of the result.
+    pub negative: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AluOp {
+    Add,
+    /// Add with carry in.
+    Adc,
+    Sub,
+    /// Subtract with borrow in.
+    Sbc,
+    And,
+    Or,
+    Xor,
+    Shl,
+    Shr,
+    Rol,
+    Ror,
+}
+
+/// A 4-bit ALU. The carry flag of the previous operation is the carry in of
+/// [`AluOp::Adc`] and [`AluOp::Sbc`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Alu4 {
+    pub flags: Flags,
+}
+
+impl Alu4 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `op` and updates the flags. Unary operations ignore `b`.
+    pub fn execute(&mut self, op: AluOp, a: U4, b: U4) -> U4 {
+        let (x, y) = (a.to_u8(), b.to_u8());
+        let carry_in = self.flags.carry as u8;
+        let (r, carry, overflow) = match op {
+            AluOp::Add | AluOp::Adc => {
+                let c = if op == AluOp::Adc { carry_in } else { 0 };
+                let r = x + y + c;
+                (r & 0xf, r > 0xf, (x ^ r) & (y ^ r) & 0x8 != 0)
+            }
+            AluOp::Sub | AluOp::Sbc => {
+                let c = if op == AluOp::Sbc { carry_in } else { 0 };
+                let r = x.wrapping_sub(y).wrapping_sub(c) & 0xf;
+                (r, y + c > x, (x ^ y) & (x ^ r) & 0x8 != 0)
+            }
+            AluOp::And => (x & y, false, false),
+            AluOp::Or => (x | y, false, false),
+            AluOp::Xor => (x ^ y, false, false),
+            AluOp::Shl => {
+                let r = (x << 1) & 0xf;
+                (r, x & 0x8 != 0, (x ^ r) & 0x8 != 0)
+            }
+            AluOp::Shr => (x >> 1, x & 1 != 0, false),
+            AluOp::Rol => (((x << 1) | (x >> 3)) & 0xf, x & 0x8 != 0, false),
+            AluOp::Ror => ((x >> 1) | ((x & 1) << 3), x & 1 != 0, false),
+        };
+        self.flags = Flags {
+            carry,
+            zero: r == 0,
+            overflow,
+            negative: r & 0x8 != 0,
+        };
+        U4::from_u8(r)
+    }
+
+    pub fn add(&mut self, a: U4, b: U4) -> U4 {
+        self.execute(AluOp::Add, a, b)
+    }
+
+    pub fn adc(&mut self, a: U4, b: U4) -> U4 {
+        self.execute(AluOp::Adc, a, b)
+    }
+
+    pub fn sub(&mut self, a: U4, b: U4) -> U4 {
+        self.execute(AluOp::Sub, a, b)
+    }
+
+    pub fn sbc(&mut self, a: U4, b: U4) -> U4 {
+        self.execute(AluOp::Sbc, a, b)
+    }
+
+    pub fn and(&mut self, a: U4, b: U4) -> U4 {
+        self.execute(AluOp::And, a, b)
+    }
+
+    pub fn or(&mut self, a: U4, b: U4) -> U4 {
+        self.execute(AluOp::Or, a, b)
+    }
+
+    pub fn xor(&mut self, a: U4, b: U4) -> U4 {
+        self.execute(AluOp::Xor, a, b)
+    }
+
+    pub fn shl(&mut self, a: U4) -> U4 {
+        self.execute(AluOp::Shl, a, U4::MIN)
+    }
+
+    pub fn shr(&mut self, a: U4) -> U4 {
+        self.execute(AluOp::Shr, a, U4::MIN)
+    }
+
+    pub fn rol(&mut self, a: U4) -> U4 {
+        self.execute(AluOp::Rol, a, U4::MIN)
+    }
+
+    pub fn ror(&mut self, a: U4) -> U4 {
+        self.execute(AluOp::Ror, a, U4::MIN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_flags() {
+        let mut alu = Alu4::new();
+        assert_eq!(alu.add(U4::n(9), U4::n(8)), U4::n(1));
+        assert!(alu.flags.carry);
+        assert!(alu.flags.overflow);
+
+        assert_eq!(alu.adc(U4::n(1), U4::n(1)), U4::n(3));
+        assert!(!alu.flags.carry);
+
+        assert_eq!(alu.add(U4::n(4), U4::n(4)), U4::n(8));
+        assert!(alu.flags.overflow && alu.flags.negative && !alu.flags.carry);
+
+        assert_eq!(alu.add(U4::n(8), U4::n(8)), U4::n(0));
+        assert!(alu.flags.zero && alu.flags.carry);
+    }
+
+    #[test]
+    fn sub_flags() {
+        let mut alu = Alu4::new();
+        assert_eq!(alu.sub(U4::n(2), U4::n(3)), U4::n(15));
+        assert!(alu.flags.carry && alu.flags.negative && !alu.flags.overflow);
+
+        assert_eq!(alu.sbc(U4::n(5), U4::n(1)), U4::n(3));
+        assert!(!alu.flags.carry);
+
+        assert_eq!(alu.sub(U4::n(8), U4::n(1)), U4::n(7));
+        assert!(alu.flags.overflow);
+    }
+
+    #[test]
+    fn logic_and_shifts() {
+        let mut alu = Alu4::new();
+        assert_eq!(alu.and(U4::n(0b1100), U4::n(0b1010)), U4::n(0b1000));
+        assert_eq!(alu.or(U4::n(0b1100), U4::n(0b1010)), U4::n(0b1110));
+        assert_eq!(alu.xor(U4::n(0b1100), U4::n(0b1100)), U4::n(0));
+        assert!(alu.flags.zero);
+
+        assert_eq!(alu.shl(U4::n(0b1001)), U4::n(0b0010));
+        assert!(alu.flags.carry);
+        assert_eq!(alu.shr(U4::n(0b1001)), U4::n(0b0100));
+        assert!(alu.flags.carry);
+        assert_eq!(alu.rol(U4::n(0b1001)), U4::n(0b0011));
+        assert_eq!(alu.ror(U4::n(0b1001)), U4::n(0b1100));
+    }
+}
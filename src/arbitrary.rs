@@ -0,0 +1,80 @@
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{bcd::BcdDigit, perm::Perm16, sbox::SBox4, U4};
+
+/// Takes the low nibble of one byte.
+impl<'a> Arbitrary<'a> for U4 {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::from_u8(u8::arbitrary(u)?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u8::size_hint(depth)
+    }
+}
+
+impl<'a> Arbitrary<'a> for BcdDigit {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::from_u8(u.int_in_range(0..=9)?).unwrap())
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u8::size_hint(depth)
+    }
+}
+
+/// Fisher-Yates shuffle driven by the input, so every permutation is
+/// reachable.
+fn shuffled_table(u: &mut Unstructured<'_>) -> Result<[U4; 16]> {
+    let mut table: [U4; 16] = core::array::from_fn(|i| U4::from_u8(i as u8));
+    for i in (1..16).rev() {
+        let j = u.int_in_range(0..=i)?;
+        table.swap(i, j);
+    }
+    Ok(table)
+}
+
+impl<'a> Arbitrary<'a> for Perm16 {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::new(shuffled_table(u)?).unwrap())
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, Some(15))
+    }
+}
+
+impl<'a> Arbitrary<'a> for SBox4 {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::new(shuffled_table(u)?).unwrap())
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, Some(15))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nibbles() {
+        let mut u = Unstructured::new(&[0x3a, 0xff, 0x00]);
+        assert_eq!(U4::arbitrary(&mut u).unwrap(), U4::n(0xa));
+        assert_eq!(U4::arbitrary(&mut u).unwrap(), U4::MAX);
+        assert!(BcdDigit::arbitrary(&mut u).unwrap().to_u8() <= 9);
+    }
+
+    #[test]
+    fn structured() {
+        let data: Vec<u8> = (0..64).collect();
+        let block = <[U4; 4]>::arbitrary(&mut Unstructured::new(&data[14..])).unwrap();
+        assert_eq!(block, [14, 15, 0, 1].map(U4::n));
+        let sbox = SBox4::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert_eq!(sbox.compose(&sbox.inverse()), SBox4::IDENTITY);
+        // Exhausted input still yields a valid permutation.
+        let p = Perm16::arbitrary(&mut Unstructured::new(&[])).unwrap();
+        assert_eq!(p.then(&p.inverse()), Perm16::IDENTITY);
+    }
+}
@@ -0,0 +1,127 @@
+//! Reverse DNS names for IPv6 addresses.
+//!
+//! The `ip6.arpa` name of an address lists its 32 nibbles as hex labels,
+//! least significant first.
+//!
+//! ```
+//! use core::net::Ipv6Addr;
+//!
+//! use u4::arpa;
+//!
+//! let addr: Ipv6Addr = "2001:db8::567:89ab".parse().unwrap();
+//! let name = arpa::reverse_name(addr).to_string();
+//! assert_eq!(
+//!     name,
+//!     "b.a.9.8.7.6.5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+//! );
+//! assert_eq!(arpa::parse_reverse_name(&name), Ok(addr));
+//! ```
+
+use core::{
+    fmt::{self, Display, Formatter},
+    net::Ipv6Addr,
+};
+
+use crate::U4;
+
+const SUFFIX: &str = "ip6.arpa";
+
+/// The name is not a full 32-label `ip6.arpa` name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidReverseName;
+
+impl Display for InvalidReverseName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("not a 32-nibble ip6.arpa name")
+    }
+}
+
+impl core::error::Error for InvalidReverseName {}
+
+/// The nibbles of `addr`, least significant first, in `ip6.arpa` label
+/// order.
+pub fn reversed_nibbles(addr: impl Into<u128>) -> [U4; 32] {
+    let bits = addr.into();
+    core::array::from_fn(|i| U4::from_u8((bits >> (4 * i)) as u8))
+}
+
+/// Formats as the `ip6.arpa` name of an address, without a trailing dot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReverseName(u128);
+
+/// The `ip6.arpa` name of `addr`, which may be an [`Ipv6Addr`] or a `u128`.
+pub fn reverse_name(addr: impl Into<u128>) -> ReverseName {
+    ReverseName(addr.into())
+}
+
+impl Display for ReverseName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for n in reversed_nibbles(self.0) {
+            write!(f, "{:x}.", n.to_u8())?;
+        }
+        f.write_str(SUFFIX)
+    }
+}
+
+/// Parses an `ip6.arpa` name, ignoring case and an optional trailing dot.
+/// Shorter names delegating a prefix are rejected.
+pub fn parse_reverse_name(name: &str) -> Result<Ipv6Addr, InvalidReverseName> {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    let split = name
+        .len()
+        .checked_sub(SUFFIX.len())
+        .ok_or(InvalidReverseName)?;
+    if !name.is_char_boundary(split) || !name[split..].eq_ignore_ascii_case(SUFFIX) {
+        return Err(InvalidReverseName);
+    }
+    let labels = &name.as_bytes()[..split];
+    if labels.len() != 64 {
+        return Err(InvalidReverseName);
+    }
+    let mut bits = 0_u128;
+    for (i, pair) in labels.chunks(2).enumerate() {
+        let n = U4::from_hex_digit(pair[0]).ok_or(InvalidReverseName)?;
+        if pair[1] != b'.' {
+            return Err(InvalidReverseName);
+        }
+        bits |= (n.to_u8() as u128) << (4 * i);
+    }
+    Ok(Ipv6Addr::from(bits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for addr in [
+            Ipv6Addr::UNSPECIFIED,
+            Ipv6Addr::LOCALHOST,
+            Ipv6Addr::from(u128::MAX),
+        ] {
+            assert_eq!(
+                parse_reverse_name(&reverse_name(addr).to_string()),
+                Ok(addr)
+            );
+        }
+        let one = reverse_name(1_u128).to_string();
+        assert!(one.starts_with("1.0.0."));
+        assert_eq!(reversed_nibbles(Ipv6Addr::LOCALHOST)[0], U4::n(1));
+    }
+
+    #[test]
+    fn parsing() {
+        let name = reverse_name(0xfedc_u128).to_string();
+        assert_eq!(parse_reverse_name(&name), Ok(Ipv6Addr::from(0xfedc_u128)));
+        assert_eq!(
+            parse_reverse_name(&format!("{}.", name.to_uppercase())),
+            Ok(Ipv6Addr::from(0xfedc_u128))
+        );
+        assert!(parse_reverse_name("8.b.d.0.1.0.0.2.ip6.arpa").is_err());
+        assert!(parse_reverse_name(&name.replace("c.d", "cd.")).is_err());
+        assert!(parse_reverse_name(&name.replace("c.", "g.")).is_err());
+        assert!(parse_reverse_name(&name.replace("ip6", "ip4")).is_err());
+        assert!(parse_reverse_name("é").is_err());
+    }
+}
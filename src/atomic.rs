@@ -0,0 +1,337 @@
+//! Atomic nibbles.
+//!
+//! [`AtomicU4`] keeps its value in the low nibble of an [`AtomicU8`], whose
+//! high nibble is always zero, so every operation stays within `0..=15`.
+//! [`AtomicNibbles`] packs two independently updatable nibbles per byte.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::U4;
+
+/// A [`U4`] that can be shared between threads.
+#[derive(Debug, Default)]
+#[repr(transparent)]
+pub struct AtomicU4(AtomicU8);
+
+impl AtomicU4 {
+    pub const fn new(n: U4) -> Self {
+        Self(AtomicU8::new(n.to_u8()))
+    }
+
+    pub fn into_inner(self) -> U4 {
+        U4::from_u8(self.0.into_inner())
+    }
+
+    pub fn load(&self, order: Ordering) -> U4 {
+        U4::from_u8(self.0.load(order))
+    }
+
+    pub fn store(&self, n: U4, order: Ordering) {
+        self.0.store(n.to_u8(), order)
+    }
+
+    pub fn swap(&self, n: U4, order: Ordering) -> U4 {
+        U4::from_u8(self.0.swap(n.to_u8(), order))
+    }
+
+    /// Stores `new` if the value is `current`, returning the previous value
+    /// in `Ok` on success and `Err` otherwise.
+    pub fn compare_exchange(
+        &self,
+        current: U4,
+        new: U4,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<U4, U4> {
+        self.0
+            .compare_exchange(current.to_u8(), new.to_u8(), success, failure)
+            .map(U4::from_u8)
+            .map_err(U4::from_u8)
+    }
+
+    /// Like [`compare_exchange`](Self::compare_exchange) but may fail
+    /// spuriously.
+    pub fn compare_exchange_weak(
+        &self,
+        current: U4,
+        new: U4,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<U4, U4> {
+        self.0
+            .compare_exchange_weak(current.to_u8(), new.to_u8(), success, failure)
+            .map(U4::from_u8)
+            .map_err(U4::from_u8)
+    }
+
+    /// Applies `f` until it succeeds or returns `None`, with the semantics of
+    /// [`AtomicU8::fetch_update`].
+    pub fn fetch_update(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: impl FnMut(U4) -> Option<U4>,
+    ) -> Result<U4, U4> {
+        self.0
+            .fetch_update(set_order, fetch_order, |v| {
+                f(U4::from_u8(v)).map(|n| n.to_u8())
+            })
+            .map(U4::from_u8)
+            .map_err(U4::from_u8)
+    }
+
+    /// Wrapping add, returning the previous value.
+    pub fn fetch_add(&self, n: U4, order: Ordering) -> U4 {
+        self.fetch_with(order, |v| v + n)
+    }
+
+    /// Wrapping subtract, returning the previous value.
+    pub fn fetch_sub(&self, n: U4, order: Ordering) -> U4 {
+        self.fetch_with(order, |v| v - n)
+    }
+
+    pub fn fetch_and(&self, n: U4, order: Ordering) -> U4 {
+        U4::from_u8(self.0.fetch_and(n.to_u8(), order))
+    }
+
+    pub fn fetch_or(&self, n: U4, order: Ordering) -> U4 {
+        U4::from_u8(self.0.fetch_or(n.to_u8(), order))
+    }
+
+    pub fn fetch_xor(&self, n: U4, order: Ordering) -> U4 {
+        U4::from_u8(self.0.fetch_xor(n.to_u8(), order))
+    }
+
+    fn fetch_with(&self, order: Ordering, mut f: impl FnMut(U4) -> U4) -> U4 {
+        match self.fetch_update(order, load_order(order), |v| Some(f(v))) {
+            Ok(v) | Err(v) => v,
+        }
+    }
+}
+
+impl From<U4> for AtomicU4 {
+    fn from(n: U4) -> Self {
+        Self::new(n)
+    }
+}
+
+/// A slice of atomic bytes viewed as atomic nibbles, two per byte with the
+/// high nibble first, e.g. for concurrent 4-bit counter arrays.
+///
+/// Updating one nibble never disturbs its neighbour: every write is a
+/// compare-and-swap loop over the containing byte.
+#[derive(Debug, Clone, Copy)]
+pub struct AtomicNibbles<'a> {
+    bytes: &'a [AtomicU8],
+}
+
+impl<'a> AtomicNibbles<'a> {
+    pub fn new(bytes: &'a [AtomicU8]) -> Self {
+        Self { bytes }
+    }
+
+    /// The number of nibbles, twice the number of bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len() * 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// The byte holding nibble `index` and the nibble's shift within it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    fn locate(&self, index: usize) -> (&'a AtomicU8, u32) {
+        let shift = if index.is_multiple_of(2) { 4 } else { 0 };
+        (&self.bytes[index / 2], shift)
+    }
+
+    pub fn load(&self, index: usize, order: Ordering) -> U4 {
+        let (byte, shift) = self.locate(index);
+        U4::from_u8(byte.load(order) >> shift)
+    }
+
+    pub fn store(&self, index: usize, n: U4, order: Ordering) {
+        self.swap(index, n, order);
+    }
+
+    pub fn swap(&self, index: usize, n: U4, order: Ordering) -> U4 {
+        match self.fetch_update(index, order, load_order(order), |_| Some(n)) {
+            Ok(v) | Err(v) => v,
+        }
+    }
+
+    /// Stores `new` at `index` if the nibble there is `current`, whatever
+    /// happens to the other nibble of the byte meanwhile.
+    pub fn compare_exchange(
+        &self,
+        index: usize,
+        current: U4,
+        new: U4,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<U4, U4> {
+        self.fetch_update(index, success, failure, |v| (v == current).then_some(new))
+    }
+
+    /// Applies `f` to nibble `index` until the update succeeds or `f`
+    /// returns `None`, returning the previous nibble.
+    pub fn fetch_update(
+        &self,
+        index: usize,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: impl FnMut(U4) -> Option<U4>,
+    ) -> Result<U4, U4> {
+        let (byte, shift) = self.locate(index);
+        let mask = 0xf << shift;
+        byte.fetch_update(set_order, fetch_order, |b| {
+            let n = f(U4::from_u8(b >> shift))?;
+            Some((b & !mask) | (n.to_u8() << shift))
+        })
+        .map(|b| U4::from_u8(b >> shift))
+        .map_err(|b| U4::from_u8(b >> shift))
+    }
+
+    /// Wrapping add, returning the previous value.
+    pub fn fetch_add(&self, index: usize, n: U4, order: Ordering) -> U4 {
+        self.fetch_with(index, order, |v| Some(v + n))
+    }
+
+    /// Wrapping subtract, returning the previous value.
+    pub fn fetch_sub(&self, index: usize, n: U4, order: Ordering) -> U4 {
+        self.fetch_with(index, order, |v| Some(v - n))
+    }
+
+    /// Adds one unless the counter is already at 15, returning the previous
+    /// value.
+    pub fn increment(&self, index: usize, order: Ordering) -> U4 {
+        self.fetch_with(index, order, |v| (v != U4::MAX).then(|| v + U4::from_u8(1)))
+    }
+
+    /// Subtracts one unless the counter is already 0, returning the previous
+    /// value.
+    pub fn decrement(&self, index: usize, order: Ordering) -> U4 {
+        self.fetch_with(index, order, |v| (v != U4::MIN).then(|| v - U4::from_u8(1)))
+    }
+
+    fn fetch_with(&self, index: usize, order: Ordering, f: impl FnMut(U4) -> Option<U4>) -> U4 {
+        match self.fetch_update(index, order, load_order(order), f) {
+            Ok(v) | Err(v) => v,
+        }
+    }
+}
+
+impl<'a> From<&'a [AtomicU8]> for AtomicNibbles<'a> {
+    fn from(bytes: &'a [AtomicU8]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+/// The strongest ordering valid for the load half of a read-modify-write
+/// performed with `order`.
+fn load_order(order: Ordering) -> Ordering {
+    match order {
+        Ordering::Release => Ordering::Relaxed,
+        Ordering::AcqRel => Ordering::Acquire,
+        order => order,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use super::*;
+
+    #[test]
+    fn operations() {
+        let a = AtomicU4::new(U4::n(14));
+        assert_eq!(a.fetch_add(U4::n(3), Ordering::SeqCst), U4::n(14));
+        assert_eq!(a.load(Ordering::SeqCst), U4::n(1));
+        assert_eq!(a.fetch_sub(U4::n(2), Ordering::SeqCst), U4::n(1));
+        assert_eq!(a.swap(U4::n(5), Ordering::SeqCst), U4::MAX);
+        assert_eq!(
+            a.compare_exchange(U4::n(4), U4::n(0), Ordering::SeqCst, Ordering::SeqCst),
+            Err(U4::n(5))
+        );
+        assert_eq!(
+            a.compare_exchange(U4::n(5), U4::n(9), Ordering::SeqCst, Ordering::SeqCst),
+            Ok(U4::n(5))
+        );
+        assert_eq!(a.fetch_xor(U4::MAX, Ordering::SeqCst), U4::n(9));
+        assert_eq!(a.into_inner(), U4::n(6));
+    }
+
+    #[test]
+    fn nibble_slice() {
+        let bytes = [AtomicU8::new(0xa5), AtomicU8::new(0)];
+        let nibbles = AtomicNibbles::new(&bytes);
+        assert_eq!(nibbles.len(), 4);
+        assert_eq!(nibbles.load(0, Ordering::SeqCst), U4::n(0xa));
+        assert_eq!(nibbles.load(1, Ordering::SeqCst), U4::n(5));
+
+        nibbles.store(1, U4::n(3), Ordering::SeqCst);
+        assert_eq!(bytes[0].load(Ordering::SeqCst), 0xa3);
+        assert_eq!(nibbles.fetch_add(0, U4::n(7), Ordering::SeqCst), U4::n(0xa));
+        assert_eq!(bytes[0].load(Ordering::SeqCst), 0x13);
+        assert_eq!(
+            nibbles.compare_exchange(3, U4::n(1), U4::n(2), Ordering::SeqCst, Ordering::SeqCst),
+            Err(U4::n(0))
+        );
+
+        assert_eq!(nibbles.decrement(2, Ordering::SeqCst), U4::MIN);
+        assert_eq!(nibbles.load(2, Ordering::SeqCst), U4::MIN);
+        for _ in 0..20 {
+            nibbles.increment(3, Ordering::SeqCst);
+        }
+        assert_eq!(bytes[1].load(Ordering::SeqCst), 0x0f);
+    }
+
+    #[test]
+    fn concurrent_neighbours() {
+        let bytes: Arc<[AtomicU8]> = (0..2).map(|_| AtomicU8::new(0)).collect();
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let bytes = Arc::clone(&bytes);
+                thread::spawn(move || {
+                    let nibbles = AtomicNibbles::new(&bytes);
+                    for _ in 0..100 {
+                        nibbles.increment(i, Ordering::Relaxed);
+                        nibbles.decrement(i, Ordering::Relaxed);
+                    }
+                    for _ in 0..=i {
+                        nibbles.increment(i, Ordering::Relaxed);
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(bytes[0].load(Ordering::Relaxed), 0x12);
+        assert_eq!(bytes[1].load(Ordering::Relaxed), 0x34);
+    }
+
+    #[test]
+    fn concurrent_increments() {
+        let a = Arc::new(AtomicU4::default());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let a = Arc::clone(&a);
+                thread::spawn(move || {
+                    for _ in 0..1001 {
+                        a.fetch_add(U4::n(1), Ordering::Relaxed);
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(a.load(Ordering::Relaxed), U4::from_u8((4004 % 16) as u8));
+    }
+}
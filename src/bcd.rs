@@ -0,0 +1,274 @@
+use core::fmt::{Display, Formatter};
+
+use crate::U4;
+
+/// A decimal digit `0..=9` held in a nibble.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct BcdDigit(U4);
+
+/// The nibble is not a decimal digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidDigit(pub u8);
+
+impl Display for InvalidDigit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:#x} is not a BCD digit", self.0)
+    }
+}
+
+impl core::error::Error for InvalidDigit {}
+
+impl BcdDigit {
+    pub const ZERO: BcdDigit = BcdDigit(U4::MIN);
+    pub const NINE: BcdDigit = BcdDigit(U4::from_u8(9));
+
+    pub fn new(n: U4) -> Option<Self> {
+        (n.to_u8() <= 9).then_some(Self(n))
+    }
+
+    pub fn from_u8(n: u8) -> Option<Self> {
+        (n <= 9).then(|| Self(U4::from_u8(n)))
+    }
+
+    pub fn get(self) -> U4 {
+        self.0
+    }
+
+    pub fn to_u8(self) -> u8 {
+        self.0.to_u8()
+    }
+}
+
+impl Display for BcdDigit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for BcdDigit {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=u8}", self.to_u8())
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for BcdDigit {
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        ufmt::uDisplay::fmt(&self.0, f)
+    }
+}
+
+impl TryFrom<U4> for BcdDigit {
+    type Error = InvalidDigit;
+
+    fn try_from(n: U4) -> Result<Self, Self::Error> {
+        Self::new(n).ok_or(InvalidDigit(n.to_u8()))
+    }
+}
+
+impl From<BcdDigit> for U4 {
+    fn from(digit: BcdDigit) -> Self {
+        digit.0
+    }
+}
+
+/// Packs `value` (`0..=99`) as two BCD digits, tens in the high nibble.
+pub fn pack_byte(value: u8) -> Option<u8> {
+    let tens = BcdDigit::from_u8(value / 10)?;
+    let ones = BcdDigit::from_u8(value % 10)?;
+    Some(tens.to_u8() << 4 | ones.to_u8())
+}
+
+/// Unpacks a two-digit BCD byte.
+pub fn unpack_byte(byte: u8) -> Result<u8, InvalidDigit> {
+    let tens = BcdDigit::try_from(U4::from_u8(byte >> 4))?;
+    let ones = BcdDigit::try_from(U4::from_u8(byte))?;
+    Ok(tens.to_u8() * 10 + ones.to_u8())
+}
+
+/// Binary to BCD by shift-and-add-3, using no division: before each of
+/// the `bits` left shifts, every digit of 5 or more gets 3 added so that it
+/// carries into the next digit.
+fn dabble<const N: usize>(value: u64, bits: u32) -> [BcdDigit; N] {
+    let mut digits = [0_u8; N];
+    for bit in (0..bits).rev() {
+        for d in &mut digits {
+            if *d >= 5 {
+                *d += 3;
+            }
+        }
+        let mut carry = (value >> bit) as u8 & 1;
+        for d in digits.iter_mut().rev() {
+            *d = *d << 1 | carry;
+            carry = *d >> 4;
+            *d &= 0xf;
+        }
+    }
+    digits.map(|d| BcdDigit(U4::from_u8(d)))
+}
+
+/// The 10 decimal digits of `value`, most significant first, by double
+/// dabble.
+pub fn double_dabble_u32(value: u32) -> [BcdDigit; 10] {
+    dabble(value as u64, u32::BITS)
+}
+
+/// The 20 decimal digits of `value`, most significant first, by double
+/// dabble.
+pub fn double_dabble_u64(value: u64) -> [BcdDigit; 20] {
+    dabble(value, u64::BITS)
+}
+
+/// The decimal digits of `value` without leading zeros, by double dabble.
+#[cfg(feature = "alloc")]
+pub fn to_bcd_nibbles(value: impl Into<u64>) -> alloc::vec::Vec<U4> {
+    let digits = double_dabble_u64(value.into());
+    let start = digits
+        .iter()
+        .position(|&d| d != BcdDigit::ZERO)
+        .unwrap_or(19);
+    digits[start..].iter().map(|&d| d.get()).collect()
+}
+
+/// Decimal digits could not be converted to binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromBcdError {
+    InvalidDigit(InvalidDigit),
+    /// The number does not fit in a `u64`.
+    Overflow,
+}
+
+impl Display for FromBcdError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidDigit(e) => e.fmt(f),
+            Self::Overflow => f.write_str("BCD number overflows u64"),
+        }
+    }
+}
+
+impl core::error::Error for FromBcdError {}
+
+/// BCD digits, most significant first, to binary by reverse double dabble:
+/// each right shift moves the lowest bit into the result, and every digit
+/// of 8 or more then has 3 subtracted.
+pub fn from_bcd_nibbles(digits: &[U4]) -> Result<u64, FromBcdError> {
+    let start = digits
+        .iter()
+        .position(|&d| d != U4::MIN)
+        .unwrap_or(digits.len());
+    let digits = &digits[start..];
+    let mut work = [0_u8; 20];
+    let offset = work
+        .len()
+        .checked_sub(digits.len())
+        .ok_or(FromBcdError::Overflow)?;
+    for (w, &d) in work[offset..].iter_mut().zip(digits) {
+        *w = BcdDigit::try_from(d)
+            .map_err(FromBcdError::InvalidDigit)?
+            .to_u8();
+    }
+
+    let mut value = 0_u64;
+    for _ in 0..u64::BITS {
+        let mut carry = 0;
+        for w in &mut work {
+            let next = *w & 1;
+            *w = *w >> 1 | carry << 3;
+            carry = next;
+        }
+        value = value >> 1 | (carry as u64) << 63;
+        for w in &mut work {
+            if *w >= 8 {
+                *w -= 3;
+            }
+        }
+    }
+    if work.iter().any(|&w| w != 0) {
+        return Err(FromBcdError::Overflow);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits() {
+        assert_eq!(BcdDigit::new(U4::n(9)), Some(BcdDigit::NINE));
+        assert_eq!(BcdDigit::new(U4::n(10)), None);
+        assert_eq!(BcdDigit::try_from(U4::n(12)), Err(InvalidDigit(12)));
+        assert_eq!(U4::from(BcdDigit::from_u8(7).unwrap()), U4::n(7));
+    }
+
+    #[test]
+    fn packed_bytes() {
+        assert_eq!(pack_byte(59), Some(0x59));
+        assert_eq!(pack_byte(100), None);
+        assert_eq!(unpack_byte(0x07), Ok(7));
+        assert_eq!(unpack_byte(0x3a), Err(InvalidDigit(0xa)));
+        for v in 0..100 {
+            assert_eq!(unpack_byte(pack_byte(v).unwrap()), Ok(v));
+        }
+    }
+
+    #[test]
+    fn double_dabble() {
+        let digits = |v: u64| double_dabble_u64(v).map(|d| d.to_u8());
+        assert_eq!(digits(0), [0; 20]);
+        assert_eq!(
+            double_dabble_u32(4_294_967_295).map(|d| d.to_u8()),
+            [4, 2, 9, 4, 9, 6, 7, 2, 9, 5]
+        );
+        assert_eq!(
+            digits(u64::MAX),
+            [1, 8, 4, 4, 6, 7, 4, 4, 0, 7, 3, 7, 0, 9, 5, 5, 1, 6, 1, 5]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn bcd_nibbles() {
+        for v in [
+            0,
+            1,
+            9,
+            10,
+            99,
+            100,
+            65_535,
+            1_000_000_007,
+            u64::MAX - 1,
+            u64::MAX,
+        ] {
+            let nibbles = to_bcd_nibbles(v);
+            let text: String = nibbles.iter().map(|n| n.to_string()).collect();
+            assert_eq!(text, v.to_string());
+            assert_eq!(from_bcd_nibbles(&nibbles), Ok(v));
+        }
+        assert_eq!(to_bcd_nibbles(42_u8), [U4::n(4), U4::n(2)]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn from_bcd_errors() {
+        let mut too_big = to_bcd_nibbles(u64::MAX);
+        *too_big.last_mut().unwrap() = U4::n(6);
+        assert_eq!(from_bcd_nibbles(&too_big), Err(FromBcdError::Overflow));
+        assert_eq!(
+            from_bcd_nibbles(&[U4::n(1); 21]),
+            Err(FromBcdError::Overflow)
+        );
+        assert_eq!(from_bcd_nibbles(&[U4::MIN; 30]), Ok(0));
+        assert_eq!(
+            from_bcd_nibbles(&[U4::n(1), U4::n(0xa)]),
+            Err(FromBcdError::InvalidDigit(InvalidDigit(0xa)))
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! num-bigint support: `BigUint` as a sequence of hex digits.
+//!
+//! ```
+//! use num_bigint::BigUint;
+//! use u4::{bigint::Nibbles, nibbles};
+//!
+//! let n = BigUint::from(0xbeef_u32);
+//! assert_eq!(n.to_nibbles_be(), nibbles!("beef"));
+//! assert_eq!(BigUint::from_nibbles_le(&nibbles!("feeb")), n);
+//! ```
+
+use alloc::vec::Vec;
+
+use num_bigint::BigUint;
+
+use crate::U4;
+
+/// Conversions between a big integer and its base-16 digits.
+pub trait Nibbles: Sized {
+    /// The digits, least significant first. Zero is a single zero digit.
+    fn to_nibbles_le(&self) -> Vec<U4>;
+
+    /// The digits, most significant first, as the number is written in hex.
+    /// Zero is a single zero digit.
+    fn to_nibbles_be(&self) -> Vec<U4>;
+
+    /// Reads digits least significant first; leading zeros are allowed and
+    /// no digits give zero.
+    fn from_nibbles_le(nibbles: &[U4]) -> Self;
+
+    /// Reads digits most significant first; leading zeros are allowed and
+    /// no digits give zero.
+    fn from_nibbles_be(nibbles: &[U4]) -> Self;
+}
+
+impl Nibbles for BigUint {
+    fn to_nibbles_le(&self) -> Vec<U4> {
+        self.to_radix_le(16).into_iter().map(U4::from_u8).collect()
+    }
+
+    fn to_nibbles_be(&self) -> Vec<U4> {
+        self.to_radix_be(16).into_iter().map(U4::from_u8).collect()
+    }
+
+    fn from_nibbles_le(nibbles: &[U4]) -> Self {
+        let bytes: Vec<u8> = nibbles
+            .chunks(2)
+            .map(|c| c[0].to_u8() | c.get(1).map_or(0, |n| n.to_u8() << 4))
+            .collect();
+        BigUint::from_bytes_le(&bytes)
+    }
+
+    fn from_nibbles_be(nibbles: &[U4]) -> Self {
+        let bytes: Vec<u8> = nibbles
+            .rchunks(2)
+            .map(|c| match c {
+                [hi, lo] => hi.to_u8() << 4 | lo.to_u8(),
+                _ => c[0].to_u8(),
+            })
+            .collect();
+        BigUint::from_bytes_le(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nibbles;
+
+    #[test]
+    fn roundtrip() {
+        let n = BigUint::parse_bytes(b"123456789abcdef0fedcba987654321", 16).unwrap();
+        let be = n.to_nibbles_be();
+        assert_eq!(be.len(), 31);
+        assert_eq!(be[0], U4::n(1));
+        assert_eq!(BigUint::from_nibbles_be(&be), n);
+
+        let mut le = n.to_nibbles_le();
+        assert_eq!(le.iter().rev().copied().collect::<Vec<_>>(), be);
+        assert_eq!(BigUint::from_nibbles_le(&le), n);
+        le.extend([U4::MIN; 3]);
+        assert_eq!(BigUint::from_nibbles_le(&le), n);
+    }
+
+    #[test]
+    fn edge_cases() {
+        let zero = BigUint::default();
+        assert_eq!(zero.to_nibbles_le(), [U4::MIN]);
+        assert_eq!(BigUint::from_nibbles_be(&[]), zero);
+        assert_eq!(BigUint::from_nibbles_le(&[]), zero);
+        assert_eq!(
+            BigUint::from_nibbles_be(&nibbles!("00abc")),
+            BigUint::from(0xabc_u32)
+        );
+        assert_eq!(
+            BigUint::from_nibbles_le(&nibbles!("cba")),
+            BigUint::from(0xabc_u32)
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Nibble dumping and conversion.
+//!
+//! Build with `cargo install u4 --features cli`.
+
+use std::{
+    env, fs,
+    io::{self, Read, Write},
+    process::ExitCode,
+};
+
+use u4::{bcd::BcdDigit, ToHexDisplay, U4};
+
+const USAGE: &str = "\
+usage:
+  u4 dump [FILE]            hex-dump FILE or stdin, one digit per nibble
+  u4 convert FROM TO VALUE  convert nibbles between hex, bin, bcd and gray
+  u4 pack                   hex digits on stdin to bytes on stdout
+  u4 unpack                 bytes on stdin to hex digits on stdout";
+
+/// Nibbles per dump line.
+const LINE: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Repr {
+    Hex,
+    Bin,
+    Bcd,
+    Gray,
+}
+
+impl Repr {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "hex" => Ok(Self::Hex),
+            "bin" => Ok(Self::Bin),
+            "bcd" => Ok(Self::Bcd),
+            "gray" => Ok(Self::Gray),
+            _ => Err(format!("unknown representation {s:?}")),
+        }
+    }
+}
+
+fn to_gray(n: U4) -> U4 {
+    n ^ (n >> 1)
+}
+
+fn from_gray(g: U4) -> U4 {
+    g ^ (g >> 1) ^ (g >> 2) ^ (g >> 3)
+}
+
+fn parse_bits(s: &str) -> Result<Vec<U4>, String> {
+    let bits: Vec<bool> = s
+        .chars()
+        .filter(|c| !matches!(c, '_' | ' '))
+        .map(|c| match c {
+            '0' => Ok(false),
+            '1' => Ok(true),
+            _ => Err(format!("invalid binary digit {c:?}")),
+        })
+        .collect::<Result<_, _>>()?;
+    if !bits.len().is_multiple_of(4) {
+        return Err(format!(
+            "{} bits is not a whole number of nibbles",
+            bits.len()
+        ));
+    }
+    Ok(bits
+        .chunks(4)
+        .map(|b| U4::from_bits_msb0([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+fn parse(repr: Repr, s: &str) -> Result<Vec<U4>, String> {
+    match repr {
+        Repr::Hex => s
+            .bytes()
+            .map(|c| U4::from_hex_digit(c).ok_or(format!("invalid hex digit {:?}", c as char)))
+            .collect(),
+        Repr::Bin => parse_bits(s),
+        Repr::Bcd => s
+            .bytes()
+            .map(|c| {
+                c.checked_sub(b'0')
+                    .and_then(BcdDigit::from_u8)
+                    .map(U4::from)
+                    .ok_or(format!("invalid decimal digit {:?}", c as char))
+            })
+            .collect(),
+        Repr::Gray => Ok(parse_bits(s)?.into_iter().map(from_gray).collect()),
+    }
+}
+
+fn render(repr: Repr, nibbles: &[U4]) -> Result<String, String> {
+    let bits = |n: &U4| format!("{:04b}", n.to_u8());
+    match repr {
+        Repr::Hex => Ok(nibbles.hex().to_string()),
+        Repr::Bin => Ok(nibbles.iter().map(bits).collect::<Vec<_>>().join("_")),
+        Repr::Bcd => nibbles
+            .iter()
+            .map(|&n| {
+                BcdDigit::try_from(n)
+                    .map(|d| d.to_string())
+                    .map_err(|e| e.to_string())
+            })
+            .collect(),
+        Repr::Gray => Ok(nibbles
+            .iter()
+            .map(|&n| bits(&to_gray(n)))
+            .collect::<Vec<_>>()
+            .join("_")),
+    }
+}
+
+fn unpack(bytes: &[u8]) -> Vec<U4> {
+    bytes
+        .iter()
+        .flat_map(|&b| [U4::from_u8(b >> 4), U4::from_u8(b)])
+        .collect()
+}
+
+fn pack(nibbles: &[U4]) -> Vec<u8> {
+    nibbles
+        .chunks(2)
+        .map(|pair| (pair[0].to_u8() << 4) | pair.get(1).map_or(0, U4::to_u8))
+        .collect()
+}
+
+fn dump(bytes: &[u8], out: &mut impl Write) -> io::Result<()> {
+    let nibbles = unpack(bytes);
+    for (line, (chunk, raw)) in nibbles.chunks(LINE).zip(bytes.chunks(LINE / 2)).enumerate() {
+        let text: String = raw
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        writeln!(
+            out,
+            "{:08x}  {:<width$}  |{text}|",
+            line * LINE,
+            chunk.hex().group(4, ' ').to_string(),
+            width = LINE + LINE / 4 - 1,
+        )?;
+    }
+    Ok(())
+}
+
+fn read_input(path: Option<&str>) -> io::Result<Vec<u8>> {
+    match path {
+        Some(path) => fs::read(path),
+        None => {
+            let mut buf = Vec::new();
+            io::stdin().read_to_end(&mut buf)?;
+            Ok(buf)
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let mut stdout = io::stdout().lock();
+    match args.as_slice() {
+        ["dump"] | ["dump", _] => {
+            let bytes = read_input(args.get(1).copied()).map_err(|e| e.to_string())?;
+            dump(&bytes, &mut stdout).map_err(|e| e.to_string())
+        }
+        ["convert", from, to, value] => {
+            let nibbles = parse(Repr::parse(from)?, value)?;
+            writeln!(stdout, "{}", render(Repr::parse(to)?, &nibbles)?).map_err(|e| e.to_string())
+        }
+        ["pack"] => {
+            let text = read_input(None).map_err(|e| e.to_string())?;
+            let text = String::from_utf8(text).map_err(|e| e.to_string())?;
+            let digits: String = text.split_whitespace().collect();
+            let nibbles = parse(Repr::Hex, &digits)?;
+            stdout.write_all(&pack(&nibbles)).map_err(|e| e.to_string())
+        }
+        ["unpack"] => {
+            let bytes = read_input(None).map_err(|e| e.to_string())?;
+            writeln!(stdout, "{}", unpack(&bytes).hex()).map_err(|e| e.to_string())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let nibbles = parse(Repr::Hex, "09aF").unwrap();
+        assert_eq!(render(Repr::Bin, &nibbles).unwrap(), "0000_1001_1010_1111");
+        assert_eq!(render(Repr::Gray, &nibbles).unwrap(), "0000_1101_1111_1000");
+        let gray = render(Repr::Gray, &nibbles).unwrap();
+        assert_eq!(parse(Repr::Gray, &gray).unwrap(), nibbles);
+        assert!(render(Repr::Bcd, &nibbles).is_err());
+        assert_eq!(
+            render(Repr::Hex, &parse(Repr::Bcd, "1905").unwrap()).unwrap(),
+            "1905"
+        );
+        assert!(parse(Repr::Bin, "101").is_err());
+    }
+
+    #[test]
+    fn packing_and_dump() {
+        let nibbles = parse(Repr::Hex, "abc").unwrap();
+        assert_eq!(pack(&nibbles), [0xab, 0xc0]);
+        assert_eq!(unpack(&[0xab, 0xc0]).len(), 4);
+
+        let mut out = Vec::new();
+        dump(b"Hi\n", &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("00000000  {:<39}  |Hi.|\n", "4869 0a")
+        );
+    }
+}
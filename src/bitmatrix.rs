@@ -0,0 +1,266 @@
+//! Linear and affine maps on U4 over GF(2).
+//!
+//! A U4 is treated as a column vector whose element `i` is bit `i` of its
+//! value (bit 0 is the least significant bit).
+
+use core::ops::Mul;
+
+use crate::{sbox::SBox4, U4};
+
+/// A 4×4 matrix over GF(2). Row `i` is a mask selecting the input bits whose
+/// parity forms output bit `i`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitMatrix4 {
+    rows: [U4; 4],
+}
+
+fn parity(x: u8) -> u8 {
+    (x.count_ones() & 1) as u8
+}
+
+impl BitMatrix4 {
+    pub const ZERO: BitMatrix4 = BitMatrix4 { rows: [U4::MIN; 4] };
+    pub const IDENTITY: BitMatrix4 = BitMatrix4 {
+        rows: [
+            U4::from_u8(0b0001),
+            U4::from_u8(0b0010),
+            U4::from_u8(0b0100),
+            U4::from_u8(0b1000),
+        ],
+    };
+
+    pub fn new(rows: [U4; 4]) -> Self {
+        Self { rows }
+    }
+
+    /// The matrix moving input bit `i` to output bit `perm[i]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `perm` is not a permutation of `0..4`.
+    pub fn from_bit_permutation(perm: [u8; 4]) -> Self {
+        let mut rows = [0_u8; 4];
+        for (i, &p) in perm.iter().enumerate() {
+            rows[p as usize] |= 1 << i;
+        }
+        assert!(
+            rows.iter().all(|&r| r.count_ones() == 1),
+            "not a permutation of 0..4"
+        );
+        Self {
+            rows: rows.map(U4::from_u8),
+        }
+    }
+
+    /// The matrix of the linear map `f`, read off the images of the unit
+    /// vectors. Only meaningful if `f` is linear.
+    pub fn from_linear_fn(f: impl Fn(U4) -> U4) -> Self {
+        let mut rows = [0; 4];
+        for col in 0..4 {
+            let image = f(U4::from_u8(1 << col)).to_u8();
+            for (i, row) in rows.iter_mut().enumerate() {
+                *row |= ((image >> i) & 1) << col;
+            }
+        }
+        Self {
+            rows: rows.map(U4::from_u8),
+        }
+    }
+
+    pub fn rows(&self) -> [U4; 4] {
+        self.rows
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.rows[row].to_u8() & (1 << col) != 0
+    }
+
+    pub fn mul_vec(&self, x: U4) -> U4 {
+        let x = x.to_u8();
+        let mut y = 0;
+        for (i, row) in self.rows.iter().enumerate() {
+            y |= parity(row.to_u8() & x) << i;
+        }
+        U4::from_u8(y)
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_linear_fn(|x| {
+            let x = x.to_u8();
+            let col = x.trailing_zeros() as usize;
+            self.rows[col]
+        })
+    }
+
+    pub fn rank(&self) -> u32 {
+        let mut rows = self.rows.map(|r| r.to_u8());
+        let mut rank = 0;
+        for col in 0..4 {
+            let bit = 1 << col;
+            let Some(pivot) = (rank..4).find(|&r| rows[r] & bit != 0) else {
+                continue;
+            };
+            rows.swap(rank, pivot);
+            for r in 0..4 {
+                if r != rank && rows[r] & bit != 0 {
+                    rows[r] ^= rows[rank];
+                }
+            }
+            rank += 1;
+        }
+        rank as u32
+    }
+
+    pub fn is_invertible(&self) -> bool {
+        self.rank() == 4
+    }
+
+    /// Gauss-Jordan inversion, `None` for singular matrices.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.rows.map(|r| r.to_u8());
+        let mut inv = Self::IDENTITY.rows.map(|r| r.to_u8());
+        for col in 0..4 {
+            let bit = 1 << col;
+            let pivot = (col..4).find(|&r| a[r] & bit != 0)?;
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+            for r in 0..4 {
+                if r != col && a[r] & bit != 0 {
+                    a[r] ^= a[col];
+                    inv[r] ^= inv[col];
+                }
+            }
+        }
+        Some(Self {
+            rows: inv.map(U4::from_u8),
+        })
+    }
+}
+
+impl Mul<U4> for BitMatrix4 {
+    type Output = U4;
+
+    fn mul(self, rhs: U4) -> Self::Output {
+        self.mul_vec(rhs)
+    }
+}
+
+impl Mul for BitMatrix4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::from_linear_fn(|x| self.mul_vec(rhs.mul_vec(x)))
+    }
+}
+
+/// The affine map `x -> matrix * x ^ constant`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Affine4 {
+    pub matrix: BitMatrix4,
+    pub constant: U4,
+}
+
+impl Affine4 {
+    pub fn new(matrix: BitMatrix4, constant: U4) -> Self {
+        Self { matrix, constant }
+    }
+
+    pub fn apply(&self, x: U4) -> U4 {
+        self.matrix.mul_vec(x) ^ self.constant
+    }
+
+    /// Recovers the affine map behind a lookup table, `None` if `table` is
+    /// not affine.
+    pub fn from_table(table: &[U4; 16]) -> Option<Self> {
+        let constant = table[0];
+        let matrix = BitMatrix4::from_linear_fn(|x| table[x.to_u8() as usize] ^ constant);
+        let affine = Self { matrix, constant };
+        (0..16)
+            .all(|x| affine.apply(U4::from_u8(x)) == table[x as usize])
+            .then_some(affine)
+    }
+
+    pub fn to_table(&self) -> [U4; 16] {
+        core::array::from_fn(|x| self.apply(U4::from_u8(x as u8)))
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        let matrix = self.matrix.inverse()?;
+        Some(Self {
+            matrix,
+            constant: matrix.mul_vec(self.constant),
+        })
+    }
+
+    /// The map as an S-box, `None` if the matrix is singular.
+    pub fn to_sbox(&self) -> Option<SBox4> {
+        SBox4::new(self.to_table()).ok()
+    }
+
+    /// Recovers the affine map implemented by `sbox`, if there is one.
+    pub fn from_sbox(sbox: &SBox4) -> Option<Self> {
+        Self::from_table(sbox.table())
+    }
+}
+
+impl From<BitMatrix4> for Affine4 {
+    fn from(matrix: BitMatrix4) -> Self {
+        Self::new(matrix, U4::MIN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity() {
+        for x in 0..16 {
+            assert_eq!(BitMatrix4::IDENTITY * U4::n(x), U4::n(x));
+        }
+        assert_eq!(BitMatrix4::IDENTITY.rank(), 4);
+        assert_eq!(BitMatrix4::ZERO.rank(), 0);
+        assert_eq!(BitMatrix4::ZERO.inverse(), None);
+    }
+
+    #[test]
+    fn rotation() {
+        let rot = BitMatrix4::from_bit_permutation([1, 2, 3, 0]);
+        for x in 0..16 {
+            let x = U4::n(x);
+            assert_eq!(
+                rot * x,
+                U4::from_u8(((x.to_u8() << 1) | (x.to_u8() >> 3)) & 0xf)
+            );
+        }
+        assert_eq!(rot.inverse().unwrap() * rot, BitMatrix4::IDENTITY);
+        assert_eq!(rot.transpose(), rot.inverse().unwrap());
+    }
+
+    #[test]
+    fn inverse() {
+        let m = BitMatrix4::new([U4::n(0b0011), U4::n(0b0110), U4::n(0b1100), U4::n(0b1000)]);
+        let inv = m.inverse().unwrap();
+        assert_eq!(m * inv, BitMatrix4::IDENTITY);
+        assert_eq!(inv * m, BitMatrix4::IDENTITY);
+
+        let singular =
+            BitMatrix4::new([U4::n(0b0011), U4::n(0b0110), U4::n(0b0101), U4::n(0b1000)]);
+        assert_eq!(singular.rank(), 3);
+        assert!(!singular.is_invertible());
+    }
+
+    #[test]
+    fn affine() {
+        let m = BitMatrix4::new([U4::n(0b0011), U4::n(0b0110), U4::n(0b1100), U4::n(0b1000)]);
+        let a = Affine4::new(m, U4::n(0b1010));
+        let sbox = a.to_sbox().unwrap();
+        assert_eq!(Affine4::from_sbox(&sbox), Some(a));
+        assert_eq!(Affine4::from_sbox(&SBox4::PRESENT), None);
+
+        let inv = a.inverse().unwrap();
+        for x in 0..16 {
+            assert_eq!(inv.apply(a.apply(U4::n(x))), U4::n(x));
+        }
+    }
+}
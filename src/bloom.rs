@@ -0,0 +1,150 @@
+//! Counting Bloom filter with 4-bit counters.
+
+use core::hash::{BuildHasher, Hash};
+
+use crate::{nibble_counters::NibbleCounters, U4};
+
+/// A Bloom filter that supports removal, keeping a saturating 4-bit counter
+/// per slot, two per byte.
+///
+/// A counter that reaches 15 stays there: it may be shared by more items
+/// than it can count, so removing never decrements it.
+///
+/// ```
+/// use u4::bloom::CountingBloom;
+///
+/// let mut seen = CountingBloom::new(1024, 4);
+/// seen.insert("apple");
+/// assert!(seen.contains("apple"));
+/// assert!(seen.remove("apple"));
+/// assert!(!seen.contains("apple"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CountingBloom<S> {
+    counters: NibbleCounters,
+    hashes: u32,
+    hasher: S,
+}
+
+#[cfg(feature = "std")]
+impl CountingBloom<std::hash::RandomState> {
+    /// A filter of `slots` counters probed `hashes` times per item, with a
+    /// randomly keyed hasher.
+    pub fn new(slots: usize, hashes: u32) -> Self {
+        Self::with_hasher(slots, hashes, Default::default())
+    }
+}
+
+impl<S: BuildHasher> CountingBloom<S> {
+    /// # Panics
+    ///
+    /// Panics if `slots` or `hashes` is zero.
+    pub fn with_hasher(slots: usize, hashes: u32, hasher: S) -> Self {
+        assert!(
+            slots > 0 && hashes > 0,
+            "a Bloom filter needs slots and hashes"
+        );
+        Self {
+            counters: NibbleCounters::new(slots),
+            hashes,
+            hasher,
+        }
+    }
+
+    pub fn slots(&self) -> usize {
+        self.counters.len()
+    }
+
+    pub fn hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    /// The two hashes of `item` that its probe sequence is built from.
+    fn probe<T: Hash + ?Sized>(&self, item: &T) -> (u64, u64) {
+        let h = self.hasher.hash_one(item);
+        (h as u32 as u64, (h >> 32) | 1)
+    }
+
+    /// Probe `i` of `item`, by double hashing.
+    fn slot(&self, (h1, h2): (u64, u64), i: u32) -> usize {
+        (h1.wrapping_add((i as u64).wrapping_mul(h2)) % self.slots() as u64) as usize
+    }
+
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        let probe = self.probe(item);
+        for i in 0..self.hashes {
+            let slot = self.slot(probe, i);
+            self.counters.increment(slot);
+        }
+    }
+
+    /// Whether `item` may have been inserted. False positives are possible,
+    /// false negatives are not unless a never-inserted item was removed.
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        let probe = self.probe(item);
+        (0..self.hashes).all(|i| self.counters.get(self.slot(probe, i)) != U4::MIN)
+    }
+
+    /// Removes one insertion of `item` if it may be present, returning
+    /// whether it was.
+    pub fn remove<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        if !self.contains(item) {
+            return false;
+        }
+        let probe = self.probe(item);
+        for i in 0..self.hashes {
+            let slot = self.slot(probe, i);
+            if self.counters.get(slot) != U4::MAX {
+                self.counters.decrement(slot);
+            }
+        }
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.counters.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    use super::*;
+
+    type Fixed = BuildHasherDefault<DefaultHasher>;
+
+    #[test]
+    fn insert_remove() {
+        let mut bloom = CountingBloom::with_hasher(1000, 5, Fixed::default());
+        for i in 0..100 {
+            bloom.insert(&i);
+        }
+        assert!((0..100).all(|i| bloom.contains(&i)));
+        let false_positives = (100..1100).filter(|i| bloom.contains(i)).count();
+        assert!(false_positives < 50, "{false_positives}");
+
+        for i in 0..50 {
+            assert!(bloom.remove(&i));
+        }
+        assert!((50..100).all(|i| bloom.contains(&i)));
+        let remaining = (0..50).filter(|i| bloom.contains(i)).count();
+        assert!(remaining < 10, "{remaining}");
+    }
+
+    #[test]
+    fn saturation() {
+        let mut bloom = CountingBloom::with_hasher(7, 1, Fixed::default());
+        for _ in 0..20 {
+            bloom.insert("x");
+        }
+        // Removing more often than the counter could track keeps it set.
+        for _ in 0..20 {
+            bloom.remove("x");
+        }
+        assert!(bloom.contains("x"));
+        bloom.clear();
+        assert!(!bloom.contains("x"));
+        assert_eq!(bloom.slots(), 7);
+    }
+}
@@ -0,0 +1,233 @@
+use core::ops::{BitAnd, BitOr, BitXor, Not};
+
+use crate::sbox::SBox4;
+use crate::U4;
+
+/// A Boolean function of four variables, stored as its truth table: bit `x`
+/// of the table is `f(x)`, and variable `i` is bit `i` of the input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BoolFn4(u16);
+
+impl BoolFn4 {
+    pub const ZERO: BoolFn4 = BoolFn4(0);
+    pub const ONE: BoolFn4 = BoolFn4(u16::MAX);
+
+    pub const fn new(truth_table: u16) -> Self {
+        Self(truth_table)
+    }
+
+    pub fn from_fn(f: impl Fn(U4) -> bool) -> Self {
+        Self((0..16).fold(0, |t, x| t | (f(U4::n(x)) as u16) << x))
+    }
+
+    /// The projection `x -> x_i`.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below 4.
+    pub const fn var(i: u32) -> Self {
+        const VARS: [u16; 4] = [0xaaaa, 0xcccc, 0xf0f0, 0xff00];
+        Self(VARS[i as usize])
+    }
+
+    /// Coordinate function `bit` of an S-box.
+    pub fn coordinate(sbox: &SBox4, bit: u32) -> Self {
+        Self::from_fn(|x| (sbox.apply(x).to_u8() >> bit) & 1 != 0)
+    }
+
+    pub const fn truth_table(self) -> u16 {
+        self.0
+    }
+
+    pub const fn eval(self, x: U4) -> bool {
+        (self.0 >> x.to_u8()) & 1 != 0
+    }
+
+    /// The function `x -> self(sbox(x))`.
+    pub fn compose(self, sbox: &SBox4) -> Self {
+        Self::from_fn(|x| self.eval(sbox.apply(x)))
+    }
+
+    /// Number of inputs mapped to 1.
+    pub const fn weight(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub const fn is_balanced(self) -> bool {
+        self.weight() == 8
+    }
+
+    /// Algebraic normal form: bit `m` of the result is the coefficient of
+    /// the monomial whose variables are the set bits of `m`.
+    pub const fn anf(self) -> u16 {
+        mobius(self.0)
+    }
+
+    /// Builds a function from its algebraic normal form, the inverse of
+    /// [`anf`](Self::anf).
+    pub const fn from_anf(anf: u16) -> Self {
+        Self(mobius(anf))
+    }
+
+    /// Size of the largest monomial in the algebraic normal form, or 0 for
+    /// the constant functions.
+    pub const fn degree(self) -> u32 {
+        let anf = self.anf();
+        let mut degree = 0;
+        let mut m = 0_u32;
+        while m < 16 {
+            if anf & (1 << m) != 0 && m.count_ones() > degree {
+                degree = m.count_ones();
+            }
+            m += 1;
+        }
+        degree
+    }
+
+    /// Walsh–Hadamard spectrum: entry `a` is the sum of `(-1)^(f(x) ^ a·x)`
+    /// over all inputs `x`.
+    pub fn walsh(self) -> [i8; 16] {
+        let mut w = [0_i8; 16];
+        for (x, w) in w.iter_mut().enumerate() {
+            *w = if self.0 & (1 << x) != 0 { -1 } else { 1 };
+        }
+        for step in [1, 2, 4, 8] {
+            for a in 0..16 {
+                if a & step == 0 {
+                    let (u, v) = (w[a], w[a | step]);
+                    w[a] = u + v;
+                    w[a | step] = u - v;
+                }
+            }
+        }
+        w
+    }
+
+    /// Hamming distance to the nearest affine function.
+    pub fn nonlinearity(self) -> u32 {
+        let max = self.walsh().iter().map(|w| w.unsigned_abs()).max();
+        8 - max.unwrap_or(0) as u32 / 2
+    }
+
+    /// Whether every Walsh coefficient has magnitude 4, the maximum
+    /// nonlinearity of 6 for four variables.
+    pub fn is_bent(self) -> bool {
+        self.walsh().iter().all(|w| w.unsigned_abs() == 4)
+    }
+}
+
+/// The Möbius transform, which is its own inverse.
+const fn mobius(mut t: u16) -> u16 {
+    t ^= (t & 0x5555) << 1;
+    t ^= (t & 0x3333) << 2;
+    t ^= (t & 0x0f0f) << 4;
+    t ^= (t & 0x00ff) << 8;
+    t
+}
+
+impl From<u16> for BoolFn4 {
+    fn from(truth_table: u16) -> Self {
+        Self(truth_table)
+    }
+}
+
+impl From<BoolFn4> for u16 {
+    fn from(f: BoolFn4) -> Self {
+        f.0
+    }
+}
+
+impl Not for BoolFn4 {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+impl BitAnd for BoolFn4 {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for BoolFn4 {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitXor for BoolFn4 {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variables() {
+        for i in 0..4 {
+            let v = BoolFn4::var(i);
+            for x in (0..16).map(U4::n) {
+                assert_eq!(v.eval(x), (x.to_u8() >> i) & 1 != 0);
+            }
+            assert_eq!(v.anf(), 1 << (1 << i));
+            assert_eq!(v.degree(), 1);
+            assert!(v.is_balanced());
+            assert_eq!(v.nonlinearity(), 0);
+        }
+        let and = BoolFn4::var(0) & BoolFn4::var(1);
+        assert_eq!(and, BoolFn4::from_fn(|x| x.to_u8() & 3 == 3));
+        assert_eq!(BoolFn4::ZERO.degree(), 0);
+        assert_eq!(BoolFn4::ONE.anf(), 1);
+    }
+
+    #[test]
+    fn anf_roundtrip() {
+        for t in (0..=u16::MAX).step_by(97) {
+            let f = BoolFn4::new(t);
+            assert_eq!(BoolFn4::from_anf(f.anf()), f);
+        }
+    }
+
+    #[test]
+    fn spectrum() {
+        let bent = BoolFn4::var(0) & BoolFn4::var(1) ^ BoolFn4::var(2) & BoolFn4::var(3);
+        assert!(bent.is_bent());
+        assert_eq!(bent.nonlinearity(), 6);
+        assert_eq!(bent.degree(), 2);
+        assert!(!bent.is_balanced());
+
+        for t in (0..=u16::MAX).step_by(251) {
+            let f = BoolFn4::new(t);
+            let w = f.walsh();
+            // Parseval's identity.
+            assert_eq!(w.iter().map(|&w| w as i32 * w as i32).sum::<i32>(), 256);
+            assert_eq!(w[0] == 0, f.is_balanced());
+        }
+    }
+
+    #[test]
+    fn sbox_coordinates() {
+        let s = SBox4::PRESENT;
+        for bit in 0..4 {
+            let f = BoolFn4::coordinate(&s, bit);
+            assert!(f.is_balanced());
+            assert_eq!(f.anf(), s.anf(bit));
+            assert_eq!(BoolFn4::var(bit).compose(&s), f,);
+        }
+        assert_eq!(
+            BoolFn4::coordinate(&s, 0).compose(&s.inverse()),
+            BoolFn4::var(0)
+        );
+    }
+}
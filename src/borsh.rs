@@ -0,0 +1,53 @@
+//! Borsh support.
+//!
+//! Canonical layout: a `U4` is one byte holding the value in its low nibble
+//! with the high nibble zero. Deserialization rejects any other byte, so
+//! every value has exactly one encoding.
+
+use ::borsh::{
+    io::{Error, ErrorKind, Read, Result, Write},
+    BorshDeserialize, BorshSerialize,
+};
+
+use crate::U4;
+
+impl BorshSerialize for U4 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.to_u8().serialize(writer)
+    }
+}
+
+impl BorshDeserialize for U4 {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let byte = u8::deserialize_reader(reader)?;
+        if byte > 0xf {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "U4 byte has a non-zero high nibble",
+            ));
+        }
+        Ok(Self::from_u8(byte))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for n in 0..16 {
+            let bytes = ::borsh::to_vec(&U4::n(n)).unwrap();
+            assert_eq!(bytes, [n]);
+            assert_eq!(::borsh::from_slice::<U4>(&bytes).unwrap(), U4::n(n));
+        }
+        let seq = ::borsh::to_vec(&vec![U4::n(1), U4::n(0xf)]).unwrap();
+        assert_eq!(seq, [2, 0, 0, 0, 1, 0xf]);
+    }
+
+    #[test]
+    fn rejects_high_nibble() {
+        let err = ::borsh::from_slice::<U4>(&[0x10]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
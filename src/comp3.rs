@@ -0,0 +1,317 @@
+//! IBM packed decimal (COBOL `COMP-3`).
+//!
+//! A value is stored as decimal digits, one per nibble, followed by a sign
+//! nibble: `C` for positive, `D` for negative and `F` for unsigned. A
+//! leading zero nibble pads the digits to a whole number of bytes. The
+//! decimal point is implied by the field's scale and not stored.
+//!
+//! ```
+//! use u4::comp3::{Comp3, Validation};
+//!
+//! let amount: Comp3 = "-1234.50".parse().unwrap();
+//! assert_eq!(amount.encode(), [0x01, 0x23, 0x45, 0x0d]);
+//!
+//! let back = Comp3::decode(&[0x01, 0x23, 0x45, 0x0d], 2, Validation::Strict).unwrap();
+//! assert_eq!(back.to_string(), "-1234.50");
+//! ```
+
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{bcd::BcdDigit, U4};
+
+/// The sign nibble.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Sign {
+    /// `C`.
+    #[default]
+    Positive,
+    /// `D`.
+    Negative,
+    /// `F`, for fields declared without a sign.
+    Unsigned,
+}
+
+impl Sign {
+    pub fn to_u4(self) -> U4 {
+        U4::from_u8(match self {
+            Self::Positive => 0xc,
+            Self::Negative => 0xd,
+            Self::Unsigned => 0xf,
+        })
+    }
+}
+
+/// How strictly [`Comp3::decode`] checks sign nibbles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Validation {
+    /// Only `C`, `D` and `F` are signs.
+    #[default]
+    Strict,
+    /// Also accept the alternative signs `A` and `E` (positive) and `B`
+    /// (negative), as IBM hardware does.
+    Lenient,
+}
+
+/// The input is not valid packed decimal, or a value does not fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comp3Error {
+    Empty,
+    /// Nibble `index`, counting from the first, is not a decimal digit.
+    InvalidDigit {
+        index: usize,
+    },
+    InvalidSign(u8),
+    /// The value has more digits than the field or target type holds.
+    Overflow,
+    /// A string is not a decimal number.
+    Syntax,
+}
+
+impl Display for Comp3Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty packed decimal"),
+            Self::InvalidDigit { index } => write!(f, "invalid digit at nibble {index}"),
+            Self::InvalidSign(n) => write!(f, "invalid sign nibble {n:#x}"),
+            Self::Overflow => f.write_str("packed decimal too long"),
+            Self::Syntax => f.write_str("invalid decimal number"),
+        }
+    }
+}
+
+impl core::error::Error for Comp3Error {}
+
+/// A packed decimal value: its digits, most significant first, sign and
+/// number of digits after the decimal point.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Comp3 {
+    digits: Vec<BcdDigit>,
+    sign: Sign,
+    scale: usize,
+}
+
+impl Comp3 {
+    pub fn new(digits: Vec<BcdDigit>, sign: Sign, scale: usize) -> Self {
+        Self {
+            digits,
+            sign,
+            scale,
+        }
+    }
+
+    /// `value / 10^scale`, signed `C` or `D`.
+    pub fn from_i128(value: i128, scale: usize) -> Self {
+        let mut digits = Vec::new();
+        let mut v = value.unsigned_abs();
+        loop {
+            digits.push(BcdDigit::from_u8((v % 10) as u8).unwrap());
+            v /= 10;
+            if v == 0 {
+                break;
+            }
+        }
+        digits.reverse();
+        let sign = if value < 0 {
+            Sign::Negative
+        } else {
+            Sign::Positive
+        };
+        Self::new(digits, sign, scale)
+    }
+
+    pub fn digits(&self) -> &[BcdDigit] {
+        &self.digits
+    }
+
+    pub fn sign(&self) -> Sign {
+        self.sign
+    }
+
+    pub fn scale(&self) -> usize {
+        self.scale
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.sign == Sign::Negative
+    }
+
+    /// The value without its decimal point, e.g. `-12345` for `-123.45`.
+    pub fn to_i128(&self) -> Result<i128, Comp3Error> {
+        let magnitude = self.digits.iter().try_fold(0_i128, |acc, d| {
+            acc.checked_mul(10)?.checked_add(d.to_u8() as i128)
+        });
+        let magnitude = magnitude.ok_or(Comp3Error::Overflow)?;
+        Ok(if self.is_negative() {
+            -magnitude
+        } else {
+            magnitude
+        })
+    }
+
+    /// Decodes a field whose implied decimal point is `scale` digits from
+    /// the right.
+    pub fn decode(bytes: &[u8], scale: usize, validation: Validation) -> Result<Self, Comp3Error> {
+        let (&last, _) = bytes.split_last().ok_or(Comp3Error::Empty)?;
+        let sign = match (last & 0xf, validation) {
+            (0xc, _) | (0xa | 0xe, Validation::Lenient) => Sign::Positive,
+            (0xd, _) | (0xb, Validation::Lenient) => Sign::Negative,
+            (0xf, _) => Sign::Unsigned,
+            (n, _) => return Err(Comp3Error::InvalidSign(n)),
+        };
+        let digits = bytes
+            .iter()
+            .flat_map(|&b| [b >> 4, b & 0xf])
+            .take(bytes.len() * 2 - 1)
+            .enumerate()
+            .map(|(index, n)| BcdDigit::from_u8(n).ok_or(Comp3Error::InvalidDigit { index }))
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(digits, sign, scale))
+    }
+
+    /// The shortest encoding: the digits, padded with a leading zero to a
+    /// whole number of bytes, and the sign.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = alloc::vec![0; self.digits.len() / 2 + 1];
+        self.encode_into(&mut out).unwrap();
+        out
+    }
+
+    /// Encodes into a fixed-width field, padding with leading zeros.
+    pub fn encode_into(&self, out: &mut [u8]) -> Result<(), Comp3Error> {
+        let width = (out.len() * 2).checked_sub(1).ok_or(Comp3Error::Overflow)?;
+        let pad = width
+            .checked_sub(self.digits.len())
+            .ok_or(Comp3Error::Overflow)?;
+        let nibbles = core::iter::repeat_n(0, pad)
+            .chain(self.digits.iter().map(|d| d.to_u8()))
+            .chain([self.sign.to_u4().to_u8()]);
+        out.fill(0);
+        for (i, n) in nibbles.enumerate() {
+            out[i / 2] |= if i % 2 == 0 { n << 4 } else { n };
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Comp3 {
+    type Err = Comp3Error;
+
+    /// Parses `[+-]digits[.digits]`; the scale is the number of fractional
+    /// digits given.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (sign, s) = match s.as_bytes().first() {
+            Some(b'-') => (Sign::Negative, &s[1..]),
+            Some(b'+') => (Sign::Positive, &s[1..]),
+            _ => (Sign::Positive, s),
+        };
+        let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+        if int.is_empty() && frac.is_empty() {
+            return Err(Comp3Error::Syntax);
+        }
+        let digits = int
+            .bytes()
+            .chain(frac.bytes())
+            .map(|c| {
+                c.checked_sub(b'0')
+                    .and_then(BcdDigit::from_u8)
+                    .ok_or(Comp3Error::Syntax)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(digits, sign, frac.len()))
+    }
+}
+
+impl Display for Comp3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            f.write_str("-")?;
+        }
+        let int_len = self.digits.len().saturating_sub(self.scale);
+        let int = &self.digits[..int_len];
+        // Leading zeros are field padding; keep one before the point.
+        let start = int
+            .iter()
+            .position(|&d| d != BcdDigit::ZERO)
+            .unwrap_or(int.len().saturating_sub(1));
+        if int.is_empty() {
+            f.write_str("0")?;
+        }
+        for d in &int[start..] {
+            write!(f, "{d}")?;
+        }
+        if self.scale > 0 {
+            f.write_str(".")?;
+            for _ in self.digits.len()..self.scale {
+                f.write_str("0")?;
+            }
+            for d in &self.digits[int_len..] {
+                write!(f, "{d}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode() {
+        let v = Comp3::from_i128(12345, 0);
+        assert_eq!(v.encode(), [0x12, 0x34, 0x5c]);
+        assert_eq!(Comp3::from_i128(-7, 0).encode(), [0x7d]);
+        assert_eq!(Comp3::from_i128(-70, 0).encode(), [0x07, 0x0d]);
+
+        let mut field = [0xff; 4];
+        v.encode_into(&mut field).unwrap();
+        assert_eq!(field, [0x00, 0x12, 0x34, 0x5c]);
+        assert_eq!(v.encode_into(&mut [0; 2]), Err(Comp3Error::Overflow));
+
+        let d = Comp3::decode(&field, 2, Validation::Strict).unwrap();
+        assert_eq!(d.to_string(), "123.45");
+        assert_eq!(d.to_i128(), Ok(12345));
+        let mut big = [0x99; 20];
+        big[19] = 0x9c;
+        let big = Comp3::decode(&big, 0, Validation::Strict);
+        assert_eq!(big.unwrap().to_i128(), Err(Comp3Error::Overflow));
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(
+            Comp3::decode(&[0x12, 0x3b], 0, Validation::Strict),
+            Err(Comp3Error::InvalidSign(0xb))
+        );
+        let lenient = Comp3::decode(&[0x12, 0x3b], 0, Validation::Lenient).unwrap();
+        assert_eq!(lenient.to_i128(), Ok(-123));
+        assert_eq!(
+            Comp3::decode(&[0x1a, 0x3c], 0, Validation::Lenient),
+            Err(Comp3Error::InvalidDigit { index: 1 })
+        );
+        assert_eq!(
+            Comp3::decode(&[], 0, Validation::Strict),
+            Err(Comp3Error::Empty)
+        );
+        let unsigned = Comp3::decode(&[0x4f], 0, Validation::Strict).unwrap();
+        assert_eq!(unsigned.sign(), Sign::Unsigned);
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!("007.10".parse::<Comp3>().unwrap().to_string(), "7.10");
+        assert_eq!("000".parse::<Comp3>().unwrap().to_string(), "0");
+        for s in ["0", "-1", "0.05", "-0.50", "12.345", "100"] {
+            assert_eq!(s.parse::<Comp3>().unwrap().to_string(), s);
+        }
+        assert_eq!(".5".parse::<Comp3>().unwrap().to_string(), "0.5");
+        assert_eq!(Comp3::from_i128(-5, 3).to_string(), "-0.005");
+        for s in ["", "-", ".", "1.2.3", "1e3", "--1"] {
+            assert_eq!(s.parse::<Comp3>(), Err(Comp3Error::Syntax), "{s}");
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{bcd::BcdDigit, U4};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    #[default]
+    Up,
+    Down,
+}
+
+/// What happens when the counter runs past 15 or below 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowMode {
+    #[default]
+    Wrap,
+    Saturate,
+}
+
+/// A 4-bit synchronous up/down counter modelled on the 74161/74191.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Counter4 {
+    value: U4,
+    direction: Direction,
+    mode: OverflowMode,
+}
+
+impl Counter4 {
+    pub fn new(mode: OverflowMode) -> Self {
+        Self {
+            mode,
+            ..Self::default()
+        }
+    }
+
+    pub fn value(&self) -> U4 {
+        self.value
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.direction = direction;
+    }
+
+    pub fn mode(&self) -> OverflowMode {
+        self.mode
+    }
+
+    /// Parallel load.
+    pub fn load(&mut self, value: U4) {
+        self.value = value;
+    }
+
+    /// Clears the counter to zero.
+    pub fn reset(&mut self) {
+        self.value = U4::MIN;
+    }
+
+    /// Whether the counter sits at the end of its range in the current
+    /// direction (15 counting up, 0 counting down), like the 74191 MAX/MIN
+    /// output.
+    pub fn terminal_count(&self) -> bool {
+        match self.direction {
+            Direction::Up => self.value == U4::MAX,
+            Direction::Down => self.value == U4::MIN,
+        }
+    }
+
+    /// Counts up, returning the carry out. In saturating mode the counter
+    /// stays at 15 but still reports the carry.
+    pub fn increment(&mut self) -> bool {
+        let carry = self.value == U4::MAX;
+        if !carry {
+            self.value = U4::from_u8(self.value.to_u8() + 1);
+        } else if self.mode == OverflowMode::Wrap {
+            self.value = U4::MIN;
+        }
+        carry
+    }
+
+    /// Counts down, returning the borrow out. In saturating mode the counter
+    /// stays at 0 but still reports the borrow.
+    pub fn decrement(&mut self) -> bool {
+        let borrow = self.value == U4::MIN;
+        if !borrow {
+            self.value = U4::from_u8(self.value.to_u8() - 1);
+        } else if self.mode == OverflowMode::Wrap {
+            self.value = U4::MAX;
+        }
+        borrow
+    }
+
+    /// Counts one step in the current direction, returning the carry or
+    /// borrow out.
+    pub fn clock(&mut self) -> bool {
+        match self.direction {
+            Direction::Up => self.increment(),
+            Direction::Down => self.decrement(),
+        }
+    }
+}
+
+/// A decade counter counting `0..=9` and carrying on rollover, like one
+/// stage of a 74160/74190.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DecadeCounter {
+    digit: BcdDigit,
+}
+
+impl DecadeCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn value(&self) -> BcdDigit {
+        self.digit
+    }
+
+    pub fn load(&mut self, digit: BcdDigit) {
+        self.digit = digit;
+    }
+
+    pub fn reset(&mut self) {
+        self.digit = BcdDigit::ZERO;
+    }
+
+    /// Counts up, returning the carry out on the 9 -> 0 rollover.
+    pub fn increment(&mut self) -> bool {
+        let carry = self.digit == BcdDigit::NINE;
+        self.digit = if carry {
+            BcdDigit::ZERO
+        } else {
+            BcdDigit::from_u8(self.digit.to_u8() + 1).unwrap()
+        };
+        carry
+    }
+
+    /// Counts down, returning the borrow out on the 0 -> 9 rollover.
+    pub fn decrement(&mut self) -> bool {
+        let borrow = self.digit == BcdDigit::ZERO;
+        self.digit = if borrow {
+            BcdDigit::NINE
+        } else {
+            BcdDigit::from_u8(self.digit.to_u8() - 1).unwrap()
+        };
+        borrow
+    }
+}
+
+/// `N` chained decade counters, least significant digit first.
+///
+/// `N` must be between 1 and 19, so that [`value`](Self::value) fits a
+/// `u64`; other sizes fail to compile:
+///
+/// ```compile_fail
+/// let _ = u4::counter::BcdCounter::<0>::new();
+/// ```
+///
+/// ```compile_fail
+/// let _ = u4::counter::BcdCounter::<20>::new();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BcdCounter<const N: usize> {
+    stages: [DecadeCounter; N],
+}
+
+impl<const N: usize> Default for BcdCounter<N> {
+    fn default() -> Self {
+        let () = Self::VALID_DIGITS;
+        Self {
+            stages: [DecadeCounter::new(); N],
+        }
+    }
+}
+
+impl<const N: usize> BcdCounter<N> {
+    const VALID_DIGITS: () = assert!(N > 0 && N <= 19, "BcdCounter needs 1 to 19 digits");
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The digits, least significant first.
+    pub fn digits(&self) -> [BcdDigit; N] {
+        self.stages.map(|s| s.value())
+    }
+
+    pub fn load(&mut self, digits: [BcdDigit; N]) {
+        for (stage, digit) in self.stages.iter_mut().zip(digits) {
+            stage.load(digit);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.stages.iter_mut().for_each(DecadeCounter::reset);
+    }
+
+    /// The count as a binary number.
+    pub fn value(&self) -> u64 {
+        self.stages
+            .iter()
+            .rev()
+            .fold(0, |acc, s| acc * 10 + s.value().to_u8() as u64)
+    }
+
+    /// Counts up, rippling the carry through the stages. Returns the carry
+    /// out of the most significant digit.
+    pub fn increment(&mut self) -> bool {
+        self.stages.iter_mut().all(DecadeCounter::increment)
+    }
+
+    /// Counts down, rippling the borrow through the stages. Returns the
+    /// borrow out of the most significant digit.
+    pub fn decrement(&mut self) -> bool {
+        self.stages.iter_mut().all(DecadeCounter::decrement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap() {
+        let mut c = Counter4::new(OverflowMode::Wrap);
+        for _ in 0..15 {
+            assert!(!c.clock());
+        }
+        assert!(c.terminal_count());
+        assert!(c.clock());
+        assert_eq!(c.value(), U4::n(0));
+
+        c.set_direction(Direction::Down);
+        assert!(c.terminal_count());
+        assert!(c.clock());
+        assert_eq!(c.value(), U4::n(15));
+    }
+
+    #[test]
+    fn saturate() {
+        let mut c = Counter4::new(OverflowMode::Saturate);
+        c.load(U4::n(14));
+        assert!(!c.increment());
+        assert!(c.increment());
+        assert_eq!(c.value(), U4::n(15));
+
+        c.reset();
+        assert!(c.decrement());
+        assert_eq!(c.value(), U4::n(0));
+    }
+
+    #[test]
+    fn decade() {
+        let mut c = DecadeCounter::new();
+        for _ in 0..9 {
+            assert!(!c.increment());
+        }
+        assert_eq!(c.value(), BcdDigit::NINE);
+        assert!(c.increment());
+        assert_eq!(c.value(), BcdDigit::ZERO);
+        assert!(c.decrement());
+        assert_eq!(c.value(), BcdDigit::NINE);
+    }
+
+    #[test]
+    fn chained() {
+        let mut c = BcdCounter::<3>::new();
+        for _ in 0..199 {
+            assert!(!c.increment());
+        }
+        assert_eq!(c.value(), 199);
+        assert!(!c.increment());
+        assert_eq!(c.value(), 200);
+
+        c.load([BcdDigit::NINE; 3]);
+        assert!(c.increment());
+        assert_eq!(c.value(), 0);
+        assert!(c.decrement());
+        assert_eq!(c.value(), 999);
+    }
+
+    #[test]
+    fn widest() {
+        let mut c = BcdCounter::<19>::new();
+        c.load([BcdDigit::NINE; 19]);
+        assert_eq!(c.value(), 9_999_999_999_999_999_999);
+        assert!(c.increment());
+        assert_eq!(c.value(), 0);
+    }
+}
@@ -0,0 +1,126 @@
+//! Constant-time comparison, selection and table lookup.
+//!
+//! These avoid branches and secret-dependent memory accesses on the nibble
+//! values, with [`black_box`] barriers against the optimizer undoing that.
+//! As with any Rust code this is best effort rather than a guarantee about
+//! the generated machine code. With the `subtle` feature, [`U4`] also
+//! implements `subtle`'s `ConstantTimeEq` and `ConditionallySelectable`.
+//!
+//! ```
+//! use u4::{ct, sbox::SBox4, U4};
+//!
+//! let key = U4::n(0xb);
+//! assert_eq!(ct::eq(key, U4::n(0xb)), 1);
+//! assert_eq!(ct::select(U4::n(1), U4::n(2), 1), U4::n(2));
+//! assert_eq!(SBox4::PRESENT.apply_ct(key), SBox4::PRESENT.apply(key));
+//! ```
+
+use core::hint::black_box;
+
+use crate::sbox::SBox4;
+use crate::U4;
+
+/// The value as a `u8`, assembled without branching on its bits.
+fn bits(n: U4) -> u8 {
+    let [b3, b2, b1, b0] = n.to_bits_msb0();
+    (b3 as u8) << 3 | (b2 as u8) << 2 | (b1 as u8) << 1 | b0 as u8
+}
+
+fn from_bits(v: u8) -> U4 {
+    U4::from_bits_lsb0([v & 1 != 0, v & 2 != 0, v & 4 != 0, v & 8 != 0])
+}
+
+/// `0xff` if `choice` is 1 and `0` if it is 0.
+fn mask(choice: u8) -> u8 {
+    black_box(0_u8.wrapping_sub(choice & 1))
+}
+
+/// 1 if `a == b`, else 0.
+pub fn eq(a: U4, b: U4) -> u8 {
+    let diff = black_box(bits(a) ^ bits(b));
+    // Zero only when the nibbles match; any set bit borrows out of bit 7.
+    1 ^ (diff.wrapping_neg() >> 7)
+}
+
+/// `b` if `choice` is 1 and `a` if it is 0. Only the low bit of `choice`
+/// is used.
+pub fn select(a: U4, b: U4, choice: u8) -> U4 {
+    let m = mask(choice);
+    from_bits(bits(a) ^ (m & (bits(a) ^ bits(b))))
+}
+
+/// Swaps `a` and `b` if `choice` is 1.
+pub fn swap(a: &mut U4, b: &mut U4, choice: u8) {
+    let t = mask(choice) & (bits(*a) ^ bits(*b));
+    *a = from_bits(bits(*a) ^ t);
+    *b = from_bits(bits(*b) ^ t);
+}
+
+/// `table[index]`, reading every entry so the access pattern does not
+/// depend on `index`.
+pub fn lookup(table: &[U4; 16], index: U4) -> U4 {
+    let mut out = 0;
+    for (i, &entry) in table.iter().enumerate() {
+        out |= mask(eq(index, U4::from_u8(i as u8))) & bits(entry);
+    }
+    from_bits(out)
+}
+
+impl SBox4 {
+    /// [`apply`](Self::apply) with a constant-time table lookup.
+    pub fn apply_ct(&self, n: U4) -> U4 {
+        lookup(self.table(), n)
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for U4 {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        subtle::Choice::from(eq(*self, *other))
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConditionallySelectable for U4 {
+    fn conditional_select(a: &Self, b: &Self, choice: subtle::Choice) -> Self {
+        select(*a, *b, choice.unwrap_u8())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_plain_operations() {
+        let table = SBox4::GIFT;
+        for a in (0..16).map(U4::n) {
+            assert_eq!(bits(a), a.to_u8());
+            assert_eq!(table.apply_ct(a), table.apply(a));
+            for b in (0..16).map(U4::n) {
+                assert_eq!(eq(a, b), (a == b) as u8);
+                assert_eq!(select(a, b, 0), a);
+                assert_eq!(select(a, b, 1), b);
+                let (mut x, mut y) = (a, b);
+                swap(&mut x, &mut y, 1);
+                assert_eq!((x, y), (b, a));
+                swap(&mut x, &mut y, 0);
+                assert_eq!((x, y), (b, a));
+            }
+        }
+    }
+
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn subtle_traits() {
+        use subtle::{ConditionallySelectable, ConstantTimeEq};
+
+        let (a, b) = (U4::n(3), U4::n(12));
+        assert!(bool::from(a.ct_eq(&a)));
+        assert!(!bool::from(a.ct_eq(&b)));
+        assert_eq!(U4::conditional_select(&a, &b, 1.into()), b);
+        let mut c = a;
+        c.conditional_assign(&b, 0.into());
+        assert_eq!(c, a);
+    }
+}
@@ -0,0 +1,124 @@
+//! DTMF symbols as nibbles.
+//!
+//! Codes follow the MT8870 and compatible decoders: `1`–`9` are 1–9, `0`
+//! is 10, `*` 11, `#` 12, `A`–`C` 13–15 and `D` 0.
+
+use core::fmt::{self, Display, Formatter};
+
+use crate::U4;
+
+/// Row (low group) frequencies in Hz, top row first.
+pub const ROW_HZ: [u16; 4] = [697, 770, 852, 941];
+/// Column (high group) frequencies in Hz, left column first.
+pub const COLUMN_HZ: [u16; 4] = [1209, 1336, 1477, 1633];
+
+const KEYPAD: [[char; 4]; 4] = [
+    ['1', '2', '3', 'A'],
+    ['4', '5', '6', 'B'],
+    ['7', '8', '9', 'C'],
+    ['*', '0', '#', 'D'],
+];
+
+const SYMBOLS: [char; 16] = [
+    'D', '1', '2', '3', '4', '5', '6', '7', '8', '9', '0', '*', '#', 'A', 'B', 'C',
+];
+
+/// One of the 16 DTMF symbols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Dtmf(U4);
+
+impl Dtmf {
+    pub fn from_code(code: U4) -> Self {
+        Self(code)
+    }
+
+    pub fn code(self) -> U4 {
+        self.0
+    }
+
+    /// Parses `0`–`9`, `*`, `#` and `A`–`D` in either case.
+    pub fn from_symbol(c: char) -> Option<Self> {
+        let c = c.to_ascii_uppercase();
+        SYMBOLS
+            .iter()
+            .position(|&s| s == c)
+            .map(|i| Self(U4::from_u8(i as u8)))
+    }
+
+    pub fn symbol(self) -> char {
+        SYMBOLS[self.0.to_u8() as usize]
+    }
+
+    /// The keypad row and column, from the top left.
+    pub fn position(self) -> (usize, usize) {
+        let s = self.symbol();
+        (0..16)
+            .map(|i| (i / 4, i % 4))
+            .find(|&(r, c)| KEYPAD[r][c] == s)
+            .unwrap()
+    }
+
+    /// The row and column tone frequencies in Hz.
+    pub fn frequencies(self) -> (u16, u16) {
+        let (r, c) = self.position();
+        (ROW_HZ[r], COLUMN_HZ[c])
+    }
+
+    /// The symbol for an exact row and column frequency pair.
+    pub fn from_frequencies(row_hz: u16, column_hz: u16) -> Option<Self> {
+        let r = ROW_HZ.iter().position(|&f| f == row_hz)?;
+        let c = COLUMN_HZ.iter().position(|&f| f == column_hz)?;
+        Self::from_symbol(KEYPAD[r][c])
+    }
+}
+
+impl From<U4> for Dtmf {
+    fn from(code: U4) -> Self {
+        Self(code)
+    }
+}
+
+impl From<Dtmf> for U4 {
+    fn from(d: Dtmf) -> Self {
+        d.0
+    }
+}
+
+impl Display for Dtmf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes() {
+        let dial: String = [1, 10, 11, 12, 13, 0]
+            .map(|c| Dtmf::from_code(U4::n(c)).symbol())
+            .iter()
+            .collect();
+        assert_eq!(dial, "10*#AD");
+        assert_eq!(Dtmf::from_symbol('b').map(Dtmf::code), Some(U4::n(14)));
+        assert_eq!(Dtmf::from_symbol('E'), None);
+        for code in (0..16).map(U4::n) {
+            let d = Dtmf::from(code);
+            assert_eq!(Dtmf::from_symbol(d.symbol()), Some(d));
+            let (row, col) = d.frequencies();
+            assert_eq!(Dtmf::from_frequencies(row, col), Some(d));
+        }
+    }
+
+    #[test]
+    fn frequencies() {
+        let f = |c| Dtmf::from_symbol(c).unwrap().frequencies();
+        assert_eq!(f('1'), (697, 1209));
+        assert_eq!(f('0'), (941, 1336));
+        assert_eq!(f('#'), (941, 1477));
+        assert_eq!(f('D'), (941, 1633));
+        assert_eq!(Dtmf::from_frequencies(700, 1209), None);
+        assert_eq!(Dtmf::from_symbol('5').unwrap().to_string(), "5");
+    }
+}
@@ -0,0 +1,284 @@
+//! C interface.
+//!
+//! Nibbles cross the boundary as `uint8_t` holding the value in the low four
+//! bits; inputs are masked. Fallible functions return a [`U4Status`] and
+//! write their result through an out pointer.
+//!
+//! Build a static library with
+//! `cargo rustc --release --no-default-features --features ffi --crate-type staticlib`
+//! and generate the header with `cbindgen --config cbindgen.toml`.
+
+use core::{ffi::c_char, slice};
+
+use crate::{bcd::BcdDigit, line4b5b, U4};
+
+/// Result code of fallible FFI calls.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum U4Status {
+    Ok = 0,
+    /// A pointer argument was null.
+    NullPointer = 1,
+    /// An input value was out of range.
+    InvalidInput = 2,
+    /// The output buffer is too short.
+    BufferTooSmall = 3,
+}
+
+/// The two nibbles of a byte.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U4Pair {
+    pub high: u8,
+    pub low: u8,
+}
+
+fn nibble(n: u8) -> U4 {
+    U4::from_u8(n)
+}
+
+#[no_mangle]
+pub extern "C" fn u4_wrapping_add(a: u8, b: u8) -> u8 {
+    nibble(a).wrapping_add(nibble(b)).to_u8()
+}
+
+#[no_mangle]
+pub extern "C" fn u4_wrapping_sub(a: u8, b: u8) -> u8 {
+    (nibble(a) - nibble(b)).to_u8()
+}
+
+#[no_mangle]
+pub extern "C" fn u4_wrapping_mul(a: u8, b: u8) -> u8 {
+    (nibble(a) * nibble(b)).to_u8()
+}
+
+#[no_mangle]
+pub extern "C" fn u4_rotate_left(n: u8, by: u32) -> u8 {
+    nibble(n).rotate_left(by).to_u8()
+}
+
+#[no_mangle]
+pub extern "C" fn u4_split(byte: u8) -> U4Pair {
+    U4Pair {
+        high: byte >> 4,
+        low: byte & 0xf,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn u4_join(pair: U4Pair) -> u8 {
+    (nibble(pair.high).to_u8() << 4) | nibble(pair.low).to_u8()
+}
+
+/// Packs `len` nibbles two per byte, high nibble first, into
+/// `ceil(len / 2)` bytes of `out`. An odd final nibble is padded with zero.
+///
+/// # Safety
+///
+/// `nibbles` must be valid for `len` reads and `out` for `out_len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn u4_pack(
+    nibbles: *const u8,
+    len: usize,
+    out: *mut u8,
+    out_len: usize,
+) -> U4Status {
+    if nibbles.is_null() || out.is_null() {
+        return U4Status::NullPointer;
+    }
+    if out_len < len.div_ceil(2) {
+        return U4Status::BufferTooSmall;
+    }
+    let (nibbles, out) = unsafe {
+        (
+            slice::from_raw_parts(nibbles, len),
+            slice::from_raw_parts_mut(out, out_len),
+        )
+    };
+    for (byte, pair) in out.iter_mut().zip(nibbles.chunks(2)) {
+        *byte = (nibble(pair[0]).to_u8() << 4) | pair.get(1).map_or(0, |&n| nibble(n).to_u8());
+    }
+    U4Status::Ok
+}
+
+/// Unpacks `len` bytes into `2 * len` nibbles of `out`, high nibble first.
+///
+/// # Safety
+///
+/// `bytes` must be valid for `len` reads and `out` for `out_len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn u4_unpack(
+    bytes: *const u8,
+    len: usize,
+    out: *mut u8,
+    out_len: usize,
+) -> U4Status {
+    if bytes.is_null() || out.is_null() {
+        return U4Status::NullPointer;
+    }
+    if out_len / 2 < len {
+        return U4Status::BufferTooSmall;
+    }
+    let (bytes, out) = unsafe {
+        (
+            slice::from_raw_parts(bytes, len),
+            slice::from_raw_parts_mut(out, out_len),
+        )
+    };
+    for (pair, &byte) in out.chunks_mut(2).zip(bytes) {
+        pair[0] = byte >> 4;
+        pair[1] = byte & 0xf;
+    }
+    U4Status::Ok
+}
+
+/// The lowercase hex digit for `n`.
+#[no_mangle]
+pub extern "C" fn u4_to_hex_char(n: u8) -> c_char {
+    b"0123456789abcdef"[nibble(n).to_u8() as usize] as c_char
+}
+
+/// Parses a hex digit of either case.
+///
+/// # Safety
+///
+/// `out` must be null or valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn u4_from_hex_char(c: c_char, out: *mut u8) -> U4Status {
+    if out.is_null() {
+        return U4Status::NullPointer;
+    }
+    match (c as u8 as char).to_digit(16) {
+        Some(d) => {
+            unsafe { *out = d as u8 };
+            U4Status::Ok
+        }
+        None => U4Status::InvalidInput,
+    }
+}
+
+/// Packs `value` (`0..=99`) as two BCD digits.
+///
+/// # Safety
+///
+/// `out` must be null or valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn u4_bcd_encode(value: u8, out: *mut u8) -> U4Status {
+    if out.is_null() {
+        return U4Status::NullPointer;
+    }
+    if value > 99 {
+        return U4Status::InvalidInput;
+    }
+    unsafe { *out = ((value / 10) << 4) | (value % 10) };
+    U4Status::Ok
+}
+
+/// Decodes a packed BCD byte, rejecting non-decimal nibbles.
+///
+/// # Safety
+///
+/// `out` must be null or valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn u4_bcd_decode(bcd: u8, out: *mut u8) -> U4Status {
+    if out.is_null() {
+        return U4Status::NullPointer;
+    }
+    match (BcdDigit::from_u8(bcd >> 4), BcdDigit::from_u8(bcd & 0xf)) {
+        (Some(tens), Some(ones)) => {
+            unsafe { *out = tens.to_u8() * 10 + ones.to_u8() };
+            U4Status::Ok
+        }
+        _ => U4Status::InvalidInput,
+    }
+}
+
+/// The 4B/5B code group for data nibble `n`.
+#[no_mangle]
+pub extern "C" fn u4_4b5b_encode(n: u8) -> u8 {
+    line4b5b::encode(nibble(n))
+}
+
+/// Decodes a 4B/5B code group carrying data.
+///
+/// # Safety
+///
+/// `out` must be null or valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn u4_4b5b_decode(code: u8, out: *mut u8) -> U4Status {
+    if out.is_null() {
+        return U4Status::NullPointer;
+    }
+    match line4b5b::decode_data(code) {
+        Ok(n) => {
+            unsafe { *out = n.to_u8() };
+            U4Status::Ok
+        }
+        Err(_) => U4Status::InvalidInput,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr;
+
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(u4_wrapping_add(9, 9), 2);
+        assert_eq!(u4_wrapping_sub(1, 2), 15);
+        assert_eq!(u4_wrapping_mul(0x13, 5), 15);
+        assert_eq!(u4_rotate_left(0b1001, 1), 0b0011);
+        assert_eq!(u4_join(u4_split(0xa7)), 0xa7);
+    }
+
+    #[test]
+    fn pack_unpack() {
+        let nibbles = [1, 0xf, 2];
+        let mut packed = [0; 2];
+        let status = unsafe { u4_pack(nibbles.as_ptr(), 3, packed.as_mut_ptr(), 2) };
+        assert_eq!(status, U4Status::Ok);
+        assert_eq!(packed, [0x1f, 0x20]);
+
+        let mut out = [0; 4];
+        let status = unsafe { u4_unpack(packed.as_ptr(), 2, out.as_mut_ptr(), 4) };
+        assert_eq!(status, U4Status::Ok);
+        assert_eq!(out, [1, 0xf, 2, 0]);
+
+        let status = unsafe { u4_unpack(packed.as_ptr(), 2, out.as_mut_ptr(), 3) };
+        assert_eq!(status, U4Status::BufferTooSmall);
+        let status = unsafe { u4_pack(ptr::null(), 0, packed.as_mut_ptr(), 2) };
+        assert_eq!(status, U4Status::NullPointer);
+    }
+
+    #[test]
+    fn codecs() {
+        let mut out = 0;
+        assert_eq!(u4_to_hex_char(0xb), b'b' as c_char);
+        assert_eq!(
+            unsafe { u4_from_hex_char(b'F' as c_char, &mut out) },
+            U4Status::Ok
+        );
+        assert_eq!(out, 0xf);
+        assert_eq!(
+            unsafe { u4_from_hex_char(b'g' as c_char, &mut out) },
+            U4Status::InvalidInput
+        );
+
+        assert_eq!(unsafe { u4_bcd_encode(42, &mut out) }, U4Status::Ok);
+        assert_eq!(out, 0x42);
+        assert_eq!(unsafe { u4_bcd_decode(0x99, &mut out) }, U4Status::Ok);
+        assert_eq!(out, 99);
+        assert_eq!(
+            unsafe { u4_bcd_decode(0x1a, &mut out) },
+            U4Status::InvalidInput
+        );
+
+        assert_eq!(
+            unsafe { u4_4b5b_decode(u4_4b5b_encode(7), &mut out) },
+            U4Status::Ok
+        );
+        assert_eq!(out, 7);
+    }
+}
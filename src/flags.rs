@@ -0,0 +1,231 @@
+//! Four named boolean flags packed into a [`U4`](crate::U4).
+//!
+//! ```
+//! use u4::nibble_flags;
+//!
+//! nibble_flags! {
+//!     /// Negative, overflow, zero and carry.
+//!     pub struct Status {
+//!         N = 0b1000,
+//!         V = 0b0100,
+//!         Z = 0b0010,
+//!         C = 0b0001,
+//!     }
+//! }
+//!
+//! let mut s = Status::Z | Status::C;
+//! s.toggle(Status::C);
+//! s.set(Status::N);
+//! assert!(s.test(Status::N | Status::Z));
+//! assert_eq!(s.to_string(), "N-Z-");
+//! ```
+
+/// Declares a flags type over a [`U4`](crate::U4) with one associated
+/// constant per named bit.
+///
+/// The generated type supports `set`, `clear`, `test` and `toggle`, the
+/// bitwise operators, conversion to and from `U4`, and a `Display` that
+/// writes each flag's name when set and `-` when clear, in declaration
+/// order.
+///
+/// Each mask must be a non-zero value in `0..=15`; anything else fails to
+/// compile:
+///
+/// ```compile_fail
+/// u4::nibble_flags! {
+///     struct Wide {
+///         HIGH = 0x10,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! nibble_flags {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$flag_meta:meta])* $flag:ident = $mask:expr),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+        $vis struct $name($crate::U4);
+
+        $(
+            const _: () = assert!(
+                $mask <= 0xf && ($mask as u8).count_ones() >= 1,
+                concat!("nibble_flags! mask for ", stringify!($flag), " must be non-zero and fit in 4 bits"),
+            );
+        )+
+
+        #[allow(dead_code)]
+        impl $name {
+            $(
+                $(#[$flag_meta])*
+                pub const $flag: Self = Self($crate::U4::from_u8($mask));
+            )+
+
+            /// No flags set.
+            pub const EMPTY: Self = Self($crate::U4::MIN);
+
+            /// Every declared flag set.
+            pub const ALL: Self = Self($crate::U4::from_u8(0 $(| $mask)+));
+
+            /// Flags from raw bits, dropping bits that name no flag.
+            pub const fn from_bits_truncate(bits: $crate::U4) -> Self {
+                Self($crate::U4::from_u8(bits.to_u8() & Self::ALL.0.to_u8()))
+            }
+
+            pub const fn bits(self) -> $crate::U4 {
+                self.0
+            }
+
+            pub const fn is_empty(self) -> bool {
+                self.0.to_u8() == 0
+            }
+
+            /// Whether every flag in `flags` is set.
+            pub const fn test(self, flags: Self) -> bool {
+                self.0.to_u8() & flags.0.to_u8() == flags.0.to_u8()
+            }
+
+            pub fn set(&mut self, flags: Self) {
+                *self |= flags;
+            }
+
+            pub fn clear(&mut self, flags: Self) {
+                *self &= !flags;
+            }
+
+            pub fn toggle(&mut self, flags: Self) {
+                *self ^= flags;
+            }
+
+            /// Sets or clears `flags` depending on `value`.
+            pub fn set_to(&mut self, flags: Self, value: bool) {
+                if value {
+                    self.set(flags);
+                } else {
+                    self.clear(flags);
+                }
+            }
+        }
+
+        impl ::core::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, rhs: Self) -> Self {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl ::core::ops::BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, rhs: Self) -> Self {
+                Self(self.0 & rhs.0)
+            }
+        }
+
+        impl ::core::ops::BitXor for $name {
+            type Output = Self;
+
+            fn bitxor(self, rhs: Self) -> Self {
+                Self(self.0 ^ rhs.0)
+            }
+        }
+
+        impl ::core::ops::Not for $name {
+            type Output = Self;
+
+            /// Complements within the declared flags.
+            fn not(self) -> Self {
+                Self::from_bits_truncate(!self.0)
+            }
+        }
+
+        impl ::core::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, rhs: Self) {
+                *self = *self | rhs;
+            }
+        }
+
+        impl ::core::ops::BitAndAssign for $name {
+            fn bitand_assign(&mut self, rhs: Self) {
+                *self = *self & rhs;
+            }
+        }
+
+        impl ::core::ops::BitXorAssign for $name {
+            fn bitxor_assign(&mut self, rhs: Self) {
+                *self = *self ^ rhs;
+            }
+        }
+
+        impl ::core::convert::From<$name> for $crate::U4 {
+            fn from(flags: $name) -> Self {
+                flags.0
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                $(
+                    if self.test(Self::$flag) {
+                        f.write_str(stringify!($flag))?;
+                    } else {
+                        f.write_str("-")?;
+                    }
+                )+
+                Ok(())
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::U4;
+
+    nibble_flags! {
+        struct Status {
+            N = 0b1000,
+            V = 0b0100,
+            Z = 0b0010,
+            C = 0b0001,
+        }
+    }
+
+    nibble_flags! {
+        struct Partial {
+            LO = 0b0001,
+            HI = 0b1000,
+        }
+    }
+
+    #[test]
+    fn set_clear_toggle() {
+        let mut s = Status::EMPTY;
+        s.set(Status::Z | Status::C);
+        assert!(s.test(Status::Z));
+        assert!(!s.test(Status::Z | Status::N));
+        s.clear(Status::C);
+        s.toggle(Status::N);
+        assert_eq!(s, Status::N | Status::Z);
+        s.set_to(Status::N, false);
+        assert_eq!(U4::from(s), U4::n(0b0010));
+        assert_eq!(!Status::EMPTY, Status::ALL);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Status::ALL.to_string(), "NVZC");
+        assert_eq!((Status::V | Status::C).to_string(), "-V-C");
+        assert_eq!(Partial::HI.to_string(), "-HI");
+    }
+
+    #[test]
+    fn undeclared_bits() {
+        assert_eq!(Partial::from_bits_truncate(U4::MAX), Partial::ALL);
+        assert_eq!((!Partial::LO).bits(), U4::n(0b1000));
+    }
+}
@@ -0,0 +1,63 @@
+//! Random nibbles straight from the OS, without the rand crate.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use ::getrandom::Error;
+
+use crate::U4;
+
+impl U4 {
+    /// A uniformly random nibble from the operating system's RNG.
+    pub fn random() -> Result<Self, Error> {
+        Ok(Self::from_u8(::getrandom::u32()? as u8))
+    }
+
+    /// Fills `buf` with uniformly random nibbles, two per byte drawn.
+    pub fn fill_random(buf: &mut [U4]) -> Result<(), Error> {
+        let mut bytes = [0; 64];
+        for chunk in buf.chunks_mut(2 * bytes.len()) {
+            let bytes = &mut bytes[..chunk.len().div_ceil(2)];
+            ::getrandom::fill(bytes)?;
+            for (i, n) in chunk.iter_mut().enumerate() {
+                *n = Self::from_u8(bytes[i / 2] >> (4 * (i % 2)));
+            }
+        }
+        Ok(())
+    }
+
+    /// `len` uniformly random nibbles.
+    #[cfg(feature = "alloc")]
+    pub fn random_vec(len: usize) -> Result<Vec<U4>, Error> {
+        let mut v = alloc::vec![U4::MIN; len];
+        Self::fill_random(&mut v)?;
+        Ok(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random() {
+        let mut seen = [false; 16];
+        for _ in 0..1000 {
+            seen[U4::random().unwrap().to_u8() as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn random_vec() {
+        let v = U4::random_vec(301).unwrap();
+        assert_eq!(v.len(), 301);
+        let mut counts = [0; 16];
+        for n in v.iter().chain(&U4::random_vec(2000).unwrap()) {
+            counts[n.to_u8() as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| c > 50), "{counts:?}");
+        assert!(U4::random_vec(0).unwrap().is_empty());
+    }
+}
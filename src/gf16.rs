@@ -0,0 +1,238 @@
+//! Arithmetic in GF(2^4).
+//!
+//! [`Gf16`] is parameterized by the reduction polynomial, written as a bit
+//! mask including the x^4 term (`0b1_0011` is x^4 + x + 1). The free functions
+//! in this module operate in the field defined by x^4 + x + 1.
+
+use crate::U4;
+
+/// x^4 + x + 1, used by Mini-AES and most Reed-Solomon codes over GF(16).
+pub const POLY_X4_X_1: u8 = 0b1_0011;
+/// x^4 + x^3 + 1.
+pub const POLY_X4_X3_1: u8 = 0b1_1001;
+/// x^4 + x^3 + x^2 + x + 1. Irreducible but not primitive, the generator is x + 1.
+pub const POLY_X4_X3_X2_X_1: u8 = 0b1_1111;
+
+const fn reduce(mut p: u8, poly: u8) -> u8 {
+    let mut i = 7;
+    while i >= 4 {
+        if p & (1 << i) != 0 {
+            p ^= poly << (i - 4);
+        }
+        i -= 1;
+    }
+    p
+}
+
+const fn mul_mod(a: u8, b: u8, poly: u8) -> u8 {
+    reduce(U4::from_u8(a).clmul(U4::from_u8(b)), poly)
+}
+
+const fn is_irreducible(poly: u8) -> bool {
+    if poly & 0xf0 != 0x10 {
+        return false;
+    }
+    let mut a = 1;
+    while a < 16 {
+        let mut b = 1;
+        while b < 16 {
+            if mul_mod(a, b, poly) == 0 {
+                return false;
+            }
+            b += 1;
+        }
+        a += 1;
+    }
+    true
+}
+
+const fn order(g: u8, poly: u8) -> u8 {
+    let mut x = g;
+    let mut n = 1;
+    while x != 1 {
+        x = mul_mod(x, g, poly);
+        n += 1;
+    }
+    n
+}
+
+const fn generator(poly: u8) -> u8 {
+    assert!(
+        is_irreducible(poly),
+        "GF(16) polynomial must be irreducible of degree 4"
+    );
+    let mut g = 2;
+    while order(g, poly) != 15 {
+        g += 1;
+    }
+    g
+}
+
+const fn build_exp(poly: u8) -> [u8; 15] {
+    let g = generator(poly);
+    let mut exp = [0; 15];
+    let mut x = 1_u8;
+    let mut i = 0;
+    while i < 15 {
+        exp[i] = x;
+        x = mul_mod(x, g, poly);
+        i += 1;
+    }
+    exp
+}
+
+const fn build_log(exp: &[u8; 15]) -> [u8; 16] {
+    let mut log = [0; 16];
+    let mut i = 0;
+    while i < 15 {
+        log[exp[i] as usize] = i as u8;
+        i += 1;
+    }
+    log
+}
+
+/// The field GF(2^4) defined by the reduction polynomial `POLY`.
+///
+/// Using a reducible polynomial is a compile-time error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Gf16<const POLY: u8>;
+
+/// The field used by the free functions of this module.
+pub type Standard = Gf16<POLY_X4_X_1>;
+
+impl<const POLY: u8> Gf16<POLY> {
+    /// The smallest primitive element of the field.
+    pub const GENERATOR: u8 = generator(POLY);
+    /// Powers of the generator: `EXP[i] = g^i`.
+    pub const EXP: [u8; 15] = build_exp(POLY);
+    /// Discrete logarithms base the generator. `LOG[0]` is unused.
+    pub const LOG: [u8; 16] = build_log(&Self::EXP);
+
+    pub fn add(a: U4, b: U4) -> U4 {
+        a ^ b
+    }
+
+    pub fn mul(a: U4, b: U4) -> U4 {
+        let (a, b) = (a.to_u8(), b.to_u8());
+        if a == 0 || b == 0 {
+            return U4::MIN;
+        }
+        let l = (Self::LOG[a as usize] + Self::LOG[b as usize]) % 15;
+        U4::from_u8(Self::EXP[l as usize])
+    }
+
+    /// Multiplicative inverse, `None` for zero.
+    pub fn inv(a: U4) -> Option<U4> {
+        let a = a.to_u8();
+        if a == 0 {
+            return None;
+        }
+        let l = (15 - Self::LOG[a as usize]) % 15;
+        Some(U4::from_u8(Self::EXP[l as usize]))
+    }
+
+    /// `a / b`, `None` when dividing by zero.
+    pub fn div(a: U4, b: U4) -> Option<U4> {
+        Self::inv(b).map(|b| Self::mul(a, b))
+    }
+
+    pub fn pow(a: U4, e: u32) -> U4 {
+        let a = a.to_u8();
+        if e == 0 {
+            return U4::from_u8(1);
+        }
+        if a == 0 {
+            return U4::MIN;
+        }
+        let l = (Self::LOG[a as usize] as u32 * (e % 15)) % 15;
+        U4::from_u8(Self::EXP[l as usize])
+    }
+}
+
+/// Powers of the generator `x` in [`Standard`].
+pub const EXP: [u8; 15] = Standard::EXP;
+/// Discrete logarithms base `x` in [`Standard`]. `LOG[0]` is unused.
+pub const LOG: [u8; 16] = Standard::LOG;
+
+pub fn add(a: U4, b: U4) -> U4 {
+    Standard::add(a, b)
+}
+
+pub fn mul(a: U4, b: U4) -> U4 {
+    Standard::mul(a, b)
+}
+
+/// Multiplicative inverse, `None` for zero.
+pub fn inv(a: U4) -> Option<U4> {
+    Standard::inv(a)
+}
+
+/// `a / b`, `None` when dividing by zero.
+pub fn div(a: U4, b: U4) -> Option<U4> {
+    Standard::div(a, b)
+}
+
+pub fn pow(a: U4, e: u32) -> U4 {
+    Standard::pow(a, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_table() {
+        // x^3 * x = x^4 = x + 1
+        assert_eq!(mul(U4::n(8), U4::n(2)), U4::n(3));
+        assert_eq!(mul(U4::n(0xf), U4::n(0)), U4::n(0));
+        assert_eq!(mul(U4::n(1), U4::n(0xb)), U4::n(0xb));
+    }
+
+    #[test]
+    fn inverse() {
+        assert_eq!(inv(U4::n(0)), None);
+        for i in 1..16 {
+            let a = U4::n(i);
+            assert_eq!(mul(a, inv(a).unwrap()), U4::n(1));
+        }
+    }
+
+    #[test]
+    fn division() {
+        assert_eq!(div(U4::n(3), U4::n(0)), None);
+        assert_eq!(div(U4::n(3), U4::n(2)), Some(U4::n(8)));
+    }
+
+    #[test]
+    fn power() {
+        assert_eq!(pow(U4::n(2), 4), U4::n(3));
+        assert_eq!(pow(U4::n(7), 15), U4::n(1));
+        assert_eq!(pow(U4::n(0), 0), U4::n(1));
+    }
+
+    #[test]
+    fn other_polynomials() {
+        type F = Gf16<POLY_X4_X3_1>;
+        // x^4 = x^3 + 1
+        assert_eq!(F::mul(U4::n(8), U4::n(2)), U4::n(9));
+
+        type G = Gf16<POLY_X4_X3_X2_X_1>;
+        assert_eq!(G::GENERATOR, 3);
+        for i in 1..16 {
+            let a = U4::n(i);
+            assert_eq!(F::mul(a, F::inv(a).unwrap()), U4::n(1));
+            assert_eq!(G::mul(a, G::inv(a).unwrap()), U4::n(1));
+        }
+    }
+
+    #[test]
+    fn matches_schoolbook() {
+        type G = Gf16<POLY_X4_X3_X2_X_1>;
+        for a in 0..16 {
+            for b in 0..16 {
+                let expected = mul_mod(a, b, POLY_X4_X3_X2_X_1);
+                assert_eq!(G::mul(U4::n(a), U4::n(b)), U4::n(expected));
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! The HD44780 LCD controller's 4-bit interface.
+//!
+//! In 4-bit mode every byte is sent as two nibbles on D4–D7, high nibble
+//! first, each latched by a pulse on EN. RS selects between the instruction
+//! register (low) and data register (high).
+
+use crate::U4;
+
+/// Pin-level access to the controller. Implement this for your GPIO setup.
+pub trait Hd44780Bus {
+    /// Drives the RS line.
+    fn set_rs(&mut self, high: bool);
+
+    /// Drives D4 (bit 0) to D7 (bit 3).
+    fn set_data(&mut self, nibble: U4);
+
+    /// Raises and lowers EN, latching the data lines.
+    fn pulse_enable(&mut self);
+
+    /// Waits at least `us` microseconds.
+    fn delay_us(&mut self, _us: u32) {}
+}
+
+/// A single latched nibble transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NibbleWrite {
+    pub rs: bool,
+    pub data: U4,
+}
+
+/// The two transfers that send `byte`, high nibble first.
+pub fn nibble_writes(byte: u8, rs: bool) -> [NibbleWrite; 2] {
+    [
+        NibbleWrite {
+            rs,
+            data: U4::from_u8(byte >> 4),
+        },
+        NibbleWrite {
+            rs,
+            data: U4::from_u8(byte),
+        },
+    ]
+}
+
+/// Drives an HD44780 over a 4-bit [`Hd44780Bus`].
+#[derive(Debug)]
+pub struct Hd44780<B> {
+    bus: B,
+}
+
+impl<B: Hd44780Bus> Hd44780<B> {
+    pub fn new(bus: B) -> Self {
+        Self { bus }
+    }
+
+    pub fn into_inner(self) -> B {
+        self.bus
+    }
+
+    fn write_nibble(&mut self, write: NibbleWrite) {
+        self.bus.set_rs(write.rs);
+        self.bus.set_data(write.data);
+        self.bus.pulse_enable();
+    }
+
+    fn write_byte(&mut self, byte: u8, rs: bool) {
+        for write in nibble_writes(byte, rs) {
+            self.write_nibble(write);
+        }
+        self.bus.delay_us(40);
+    }
+
+    /// Switches a freshly powered controller from 8-bit to 4-bit mode with
+    /// the datasheet's initialization-by-instruction sequence.
+    pub fn init_4bit(&mut self) {
+        self.bus.delay_us(15_000);
+        for delay in [4_100, 100, 40] {
+            self.write_nibble(NibbleWrite {
+                rs: false,
+                data: U4::from_u8(0x3),
+            });
+            self.bus.delay_us(delay);
+        }
+        self.write_nibble(NibbleWrite {
+            rs: false,
+            data: U4::from_u8(0x2),
+        });
+        self.bus.delay_us(40);
+    }
+
+    pub fn write_command(&mut self, command: u8) {
+        self.write_byte(command, false);
+        // Clear display and return home are the slow instructions.
+        if command <= 0x03 {
+            self.bus.delay_us(1_520);
+        }
+    }
+
+    pub fn write_data(&mut self, data: u8) {
+        self.write_byte(data, true);
+    }
+
+    pub fn write_bytes(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.write_data(byte);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        rs: bool,
+        data: U4,
+        latched: Vec<NibbleWrite>,
+    }
+
+    impl Hd44780Bus for Recorder {
+        fn set_rs(&mut self, high: bool) {
+            self.rs = high;
+        }
+
+        fn set_data(&mut self, nibble: U4) {
+            self.data = nibble;
+        }
+
+        fn pulse_enable(&mut self) {
+            self.latched.push(NibbleWrite {
+                rs: self.rs,
+                data: self.data,
+            });
+        }
+    }
+
+    #[test]
+    fn sends_high_nibble_first() {
+        let mut lcd = Hd44780::new(Recorder::default());
+        lcd.write_command(0x28);
+        lcd.write_data(b'A');
+        let latched = lcd.into_inner().latched;
+        let expected: Vec<_> = nibble_writes(0x28, false)
+            .into_iter()
+            .chain(nibble_writes(0x41, true))
+            .collect();
+        assert_eq!(latched, expected);
+        assert_eq!(latched[2].data, U4::n(4));
+    }
+
+    #[test]
+    fn init_sequence() {
+        let mut lcd = Hd44780::new(Recorder::default());
+        lcd.init_4bit();
+        let data: Vec<_> = lcd.into_inner().latched.iter().map(|w| w.data).collect();
+        assert_eq!(data, vec![U4::n(3), U4::n(3), U4::n(3), U4::n(2)]);
+    }
+}
@@ -1,16 +1,131 @@
-use std::{fmt::Write, num::ParseIntError};
+use core::fmt::{self, Display, Formatter, Write};
 
-pub fn decode_hex(s: &str) -> Result<Vec<u8>, ParseIntError> {
-    (0..s.len())
-        .step_by(2)
-        .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
-        .collect()
+use crate::U4;
+
+/// Error from parsing a fixed-length run of hex digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseHexError {
+    /// The byte at `index` is not a hex digit.
+    InvalidDigit { index: usize, byte: u8 },
+    /// The input had `found` digits instead of `expected`.
+    WrongLength { expected: usize, found: usize },
+}
+
+impl Display for ParseHexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDigit { index, byte } => {
+                write!(f, "invalid hex digit {:?} at index {index}", *byte as char)
+            }
+            Self::WrongLength { expected, found } => {
+                write!(f, "expected {expected} hex digits, found {found}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseHexError {}
+
+pub const fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
 }
 
-pub fn encode_hex(bytes: &[u8]) -> String {
-    let mut s = String::with_capacity(bytes.len() * 2);
+#[cfg(feature = "alloc")]
+pub fn encode_hex(bytes: &[u8]) -> alloc::string::String {
+    let mut s = alloc::string::String::with_capacity(bytes.len() * 2);
     for &b in bytes {
         write!(&mut s, "{:02x}", b).unwrap();
     }
     s
 }
+
+/// Formats nibbles as a contiguous hex string without allocating, one digit
+/// per nibble.
+///
+/// ```
+/// use u4::{HexDisplay, ToHexDisplay, U4};
+///
+/// let nibbles = [0xd, 0xe, 0xa, 0xd, 0xb, 0xe, 0xe, 0xf].map(U4::n);
+/// assert_eq!(nibbles.hex().to_string(), "deadbeef");
+/// assert_eq!(format!("{}", nibbles.hex().group(4, ' ').uppercase()), "DEAD BEEF");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HexDisplay<'a> {
+    nibbles: &'a [U4],
+    group: Option<(usize, char)>,
+    uppercase: bool,
+}
+
+impl<'a> HexDisplay<'a> {
+    pub fn new(nibbles: &'a [U4]) -> Self {
+        Self {
+            nibbles,
+            group: None,
+            uppercase: false,
+        }
+    }
+
+    /// Writes `separator` between every `size` digits. A `size` of zero
+    /// disables grouping.
+    pub fn group(mut self, size: usize, separator: char) -> Self {
+        self.group = (size > 0).then_some((size, separator));
+        self
+    }
+
+    pub fn uppercase(mut self) -> Self {
+        self.uppercase = true;
+        self
+    }
+}
+
+impl Display for HexDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let digits: &[u8; 16] = if self.uppercase {
+            b"0123456789ABCDEF"
+        } else {
+            b"0123456789abcdef"
+        };
+        for (i, n) in self.nibbles.iter().enumerate() {
+            if let Some((size, separator)) = self.group {
+                if i > 0 && i % size == 0 {
+                    f.write_char(separator)?;
+                }
+            }
+            f.write_char(digits[n.to_u8() as usize] as char)?;
+        }
+        Ok(())
+    }
+}
+
+/// Adds [`hex`](ToHexDisplay::hex) to nibble slices.
+pub trait ToHexDisplay {
+    fn hex(&self) -> HexDisplay<'_>;
+}
+
+impl ToHexDisplay for [U4] {
+    fn hex(&self) -> HexDisplay<'_> {
+        HexDisplay::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_display() {
+        let nibbles: Vec<U4> = (0..16).map(U4::n).collect();
+        assert_eq!(nibbles.hex().to_string(), "0123456789abcdef");
+        assert_eq!(nibbles[..7].hex().group(3, ':').to_string(), "012:345:6");
+        assert_eq!(
+            nibbles[10..].hex().uppercase().group(0, '-').to_string(),
+            "ABCDEF"
+        );
+        assert_eq!([U4::MIN; 0].hex().group(2, ' ').to_string(), "");
+    }
+}
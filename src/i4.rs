@@ -0,0 +1,175 @@
+//! A signed nibble and its conversions to and from [`U4`].
+//!
+//! ```
+//! use u4::{i4::I4, U4};
+//!
+//! let pc = U4::n(14);
+//! let jump = I4::new(-3).unwrap();
+//! assert_eq!(pc.wrapping_add_signed(jump), U4::n(11));
+//! assert_eq!(U4::n(2).checked_add_signed(jump), None);
+//! assert_eq!(U4::n(0xd).cast_signed(), jump);
+//! ```
+
+use core::fmt::{self, Display, Formatter};
+
+use crate::U4;
+
+/// A 4-bit two's complement integer, `-8..=7`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct I4(i8);
+
+impl I4 {
+    pub const MIN: I4 = I4(-8);
+    pub const MAX: I4 = I4(7);
+
+    /// `n` as a signed nibble, or `None` outside `-8..=7`.
+    pub const fn new(n: i8) -> Option<Self> {
+        if n < -8 || n > 7 {
+            None
+        } else {
+            Some(Self(n))
+        }
+    }
+
+    pub const fn to_i8(self) -> i8 {
+        self.0
+    }
+
+    /// The same bits read as unsigned, so -1 becomes 15.
+    pub const fn cast_unsigned(self) -> U4 {
+        U4::from_u8(self.0 as u8)
+    }
+}
+
+impl Display for I4 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for I4 {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=i8}", self.0)
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for I4 {
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        ufmt::uDisplay::fmt(&self.0, f)
+    }
+}
+
+/// A value does not fit the target nibble type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromNibbleError;
+
+impl Display for TryFromNibbleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "out of range nibble conversion")
+    }
+}
+
+impl core::error::Error for TryFromNibbleError {}
+
+/// Fails for negative values.
+impl TryFrom<I4> for U4 {
+    type Error = TryFromNibbleError;
+
+    fn try_from(n: I4) -> Result<Self, Self::Error> {
+        if n.0 < 0 {
+            Err(TryFromNibbleError)
+        } else {
+            Ok(U4::from_u8(n.0 as u8))
+        }
+    }
+}
+
+/// Fails for values above 7.
+impl TryFrom<U4> for I4 {
+    type Error = TryFromNibbleError;
+
+    fn try_from(n: U4) -> Result<Self, Self::Error> {
+        I4::new(n.to_u8() as i8).ok_or(TryFromNibbleError)
+    }
+}
+
+impl U4 {
+    /// The same bits read as two's complement, so 15 becomes -1.
+    pub const fn cast_signed(self) -> I4 {
+        I4(((self.to_u8() << 4) as i8) >> 4)
+    }
+
+    /// `self + rhs`, or `None` if the result leaves `0..=15`.
+    pub const fn checked_add_signed(self, rhs: I4) -> Option<Self> {
+        let sum = self.to_u8() as i8 + rhs.0;
+        if sum < 0 || sum > 15 {
+            None
+        } else {
+            Some(Self::from_u8(sum as u8))
+        }
+    }
+
+    /// `self + rhs` modulo 16.
+    pub const fn wrapping_add_signed(self, rhs: I4) -> Self {
+        Self::from_u8(self.to_u8().wrapping_add(rhs.0 as u8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn casts() {
+        for n in (0..16).map(U4::n) {
+            let s = n.cast_signed();
+            assert_eq!(s.cast_unsigned(), n);
+            assert_eq!(s.to_i8().rem_euclid(16) as u8, n.to_u8());
+            assert_eq!(I4::try_from(n).is_ok(), n.to_u8() < 8);
+            assert_eq!(U4::try_from(s).is_ok(), s.to_i8() >= 0);
+        }
+        assert_eq!(I4::new(-9), None);
+        assert_eq!(I4::new(8), None);
+        assert_eq!(U4::MAX.cast_signed().to_i8(), -1);
+        assert_eq!(I4::MIN.cast_unsigned(), U4::n(8));
+        assert!(I4::MIN < I4::MAX);
+        assert_eq!(U4::try_from(I4::MIN), Err(TryFromNibbleError));
+    }
+
+    #[test]
+    fn signed_addition() {
+        for n in (0..16).map(U4::n) {
+            for d in (-8..8).map(|d| I4::new(d).unwrap()) {
+                let sum = n.to_u8() as i8 + d.to_i8();
+                let expected = (0..16).contains(&sum).then(|| U4::n(sum as u8));
+                assert_eq!(n.checked_add_signed(d), expected);
+                assert_eq!(n.wrapping_add_signed(d), U4::n(sum.rem_euclid(16) as u8));
+            }
+        }
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn ufmt() {
+        struct Buf(String);
+
+        impl ufmt::uWrite for Buf {
+            type Error = core::convert::Infallible;
+
+            fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+                self.0.push_str(s);
+                Ok(())
+            }
+        }
+
+        let mut buf = Buf(String::new());
+        ufmt::uwrite!(buf, "{} {:?}", I4::MIN, I4::MAX).unwrap();
+        assert_eq!(buf.0, format!("-8 {:?}", I4::MAX));
+    }
+}
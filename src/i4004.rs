@@ -0,0 +1,146 @@
+//! Instruction fields of the Intel 4004.
+//!
+//! Each instruction byte is an `OPR` nibble, the operation, followed by an
+//! `OPA` nibble, the modifier. `OPA` names one of the sixteen index
+//! registers `R0`–`R15`, or with its low bit dropped one of the eight
+//! register pairs `P0`–`P7`. Jumps and calls carry a 12-bit ROM address.
+
+use crate::U4;
+
+/// One instruction byte.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Instruction(pub u8);
+
+impl Instruction {
+    pub fn from_nibbles(opr: U4, opa: U4) -> Self {
+        Self(opr.to_u8() << 4 | opa.to_u8())
+    }
+
+    /// The operation nibble.
+    pub fn opr(self) -> U4 {
+        U4::from_u8(self.0 >> 4)
+    }
+
+    /// The modifier nibble.
+    pub fn opa(self) -> U4 {
+        U4::from_u8(self.0)
+    }
+
+    /// `OPA` as an index register, as used by `INC`, `ADD`, `SUB`, `LD`
+    /// and `XCH`.
+    pub fn register(self) -> U4 {
+        self.opa()
+    }
+
+    /// `OPA` as a register pair `0..=7`, as used by `FIM`, `SRC`, `FIN` and
+    /// `JIN`.
+    pub fn pair(self) -> U4 {
+        U4::from_u8(self.0 >> 1 & 0x7)
+    }
+
+    /// The low bit of `OPA`, which tells apart instructions sharing an
+    /// `OPR` that take a register pair: `FIM` (0) and `SRC` (1), `FIN` (0)
+    /// and `JIN` (1).
+    pub fn pair_bit(self) -> bool {
+        self.0 & 1 != 0
+    }
+
+    /// Whether the instruction takes a second byte: `JCN`, `FIM`, `JUN`,
+    /// `JMS` and `ISZ`.
+    pub fn is_two_byte(self) -> bool {
+        match self.opr().to_u8() {
+            0x1 | 0x4 | 0x5 | 0x7 => true,
+            0x2 => !self.pair_bit(),
+            _ => false,
+        }
+    }
+}
+
+impl From<u8> for Instruction {
+    fn from(byte: u8) -> Self {
+        Self(byte)
+    }
+}
+
+impl From<Instruction> for u8 {
+    fn from(i: Instruction) -> Self {
+        i.0
+    }
+}
+
+/// The two registers of pair `pair`, even register first. The even
+/// register holds the high nibble of the pair's byte.
+pub fn pair_registers(pair: U4) -> (U4, U4) {
+    let even = (pair.to_u8() & 0x7) << 1;
+    (U4::from_u8(even), U4::from_u8(even + 1))
+}
+
+/// A 12-bit address from its nibbles, most significant first.
+pub const fn address12(high: U4, mid: U4, low: U4) -> u16 {
+    (high.to_u8() as u16) << 8 | (mid.to_u8() as u16) << 4 | low.to_u8() as u16
+}
+
+/// The nibbles of the low 12 bits of `address`, most significant first.
+pub const fn split_address12(address: u16) -> (U4, U4, U4) {
+    (
+        U4::from_u8((address >> 8) as u8),
+        U4::from_u8((address >> 4) as u8),
+        U4::from_u8(address as u8),
+    )
+}
+
+/// The target of `JUN` or `JMS`: the first byte's `OPA` is the high nibble
+/// and the second byte the low eight bits.
+pub fn long_address(first: Instruction, second: u8) -> u16 {
+    address12(first.opa(), U4::from_u8(second >> 4), U4::from_u8(second))
+}
+
+/// The target of `JCN` or `ISZ`: the second byte replaces the low eight
+/// bits of `next`, the address following the instruction. Taking the page
+/// from `next` matches the 4004, where an instruction in the last two
+/// bytes of a page jumps within the following page.
+pub fn short_address(next: u16, second: u8) -> u16 {
+    (next & 0xf00) | second as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields() {
+        // XCH R3
+        let xch = Instruction(0xb3);
+        assert_eq!((xch.opr(), xch.register()), (U4::n(0xb), U4::n(3)));
+        assert!(!xch.is_two_byte());
+
+        // FIM P3 and SRC P3
+        let (fim, src) = (Instruction(0x26), Instruction(0x27));
+        assert_eq!(fim.pair(), U4::n(3));
+        assert_eq!(src.pair(), U4::n(3));
+        assert!(!fim.pair_bit() && src.pair_bit());
+        assert!(fim.is_two_byte() && !src.is_two_byte());
+        assert_eq!(pair_registers(fim.pair()), (U4::n(6), U4::n(7)));
+
+        assert_eq!(
+            Instruction::from_nibbles(U4::n(0xd), U4::n(5)),
+            Instruction(0xd5)
+        );
+        assert!(Instruction(0x14).is_two_byte());
+        assert!(Instruction(0x70).is_two_byte());
+    }
+
+    #[test]
+    fn addresses() {
+        // JUN 0x123
+        assert_eq!(long_address(Instruction(0x41), 0x23), 0x123);
+        assert_eq!(split_address12(0x123), (U4::n(1), U4::n(2), U4::n(3)));
+        for a in (0..0x1000).step_by(7) {
+            let (h, m, l) = split_address12(a);
+            assert_eq!(address12(h, m, l), a);
+        }
+        // JCN at 0x3fd..0x3fe stays on page 3; at 0x3fe..0x3ff it leaves.
+        assert_eq!(short_address(0x3ff, 0x10), 0x310);
+        assert_eq!(short_address(0x400, 0x10), 0x410);
+    }
+}
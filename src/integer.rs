@@ -0,0 +1,116 @@
+use crate::U4;
+
+impl U4 {
+    /// Greatest common divisor, `gcd(0, 0) == 0`.
+    pub fn gcd(self, other: Self) -> Self {
+        let (mut a, mut b) = (self.to_u8(), other.to_u8());
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        Self::from_u8(a)
+    }
+
+    /// Least common multiple, `None` if it does not fit in four bits.
+    pub fn checked_lcm(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.to_u8(), other.to_u8());
+        if a == 0 || b == 0 {
+            return Some(Self::MIN);
+        }
+        let lcm = a / self.gcd(other).to_u8() * b;
+        (lcm <= Self::MAX.to_u8()).then(|| Self::from_u8(lcm))
+    }
+
+    /// Least common multiple.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result does not fit in four bits.
+    pub fn lcm(self, other: Self) -> Self {
+        self.checked_lcm(other)
+            .expect("attempt to compute lcm with overflow")
+    }
+
+    /// Quotient and remainder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rhs` is zero.
+    pub fn div_rem(self, rhs: Self) -> (Self, Self) {
+        (self / rhs, self % rhs)
+    }
+
+    /// Whether `self` is a multiple of `rhs`. Zero is only a multiple of
+    /// itself when `rhs` is zero, matching `u8::is_multiple_of`.
+    pub fn is_multiple_of(self, rhs: Self) -> bool {
+        self.to_u8().is_multiple_of(rhs.to_u8())
+    }
+}
+
+#[cfg(feature = "num-integer")]
+impl num_integer::Integer for U4 {
+    fn div_floor(&self, other: &Self) -> Self {
+        *self / *other
+    }
+
+    fn mod_floor(&self, other: &Self) -> Self {
+        *self % *other
+    }
+
+    fn gcd(&self, other: &Self) -> Self {
+        U4::gcd(*self, *other)
+    }
+
+    fn lcm(&self, other: &Self) -> Self {
+        U4::lcm(*self, *other)
+    }
+
+    fn is_multiple_of(&self, other: &Self) -> bool {
+        U4::is_multiple_of(*self, *other)
+    }
+
+    fn is_even(&self) -> bool {
+        self.to_u8().is_multiple_of(2)
+    }
+
+    fn is_odd(&self) -> bool {
+        !self.is_even()
+    }
+
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        U4::div_rem(*self, *other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::U4;
+
+    #[test]
+    fn gcd_lcm() {
+        assert_eq!(U4::n(12).gcd(U4::n(8)), U4::n(4));
+        assert_eq!(U4::n(0).gcd(U4::n(9)), U4::n(9));
+        assert_eq!(U4::n(0).gcd(U4::n(0)), U4::n(0));
+        assert_eq!(U4::n(4).lcm(U4::n(6)), U4::n(12));
+        assert_eq!(U4::n(7).checked_lcm(U4::n(8)), None);
+        assert_eq!(U4::n(0).checked_lcm(U4::n(8)), Some(U4::n(0)));
+    }
+
+    #[test]
+    fn multiples() {
+        assert_eq!(U4::n(14).div_rem(U4::n(4)), (U4::n(3), U4::n(2)));
+        assert!(U4::n(12).is_multiple_of(U4::n(3)));
+        assert!(!U4::n(12).is_multiple_of(U4::n(5)));
+        assert!(U4::n(0).is_multiple_of(U4::n(0)));
+        assert!(!U4::n(3).is_multiple_of(U4::n(0)));
+    }
+
+    #[cfg(feature = "num-integer")]
+    #[test]
+    fn integer_trait() {
+        use num_integer::Integer;
+
+        assert_eq!(Integer::gcd(&U4::n(6), &U4::n(9)), U4::n(3));
+        assert!(U4::n(6).is_even());
+        assert_eq!(U4::n(7).div_ceil(&U4::n(2)), U4::n(4));
+    }
+}
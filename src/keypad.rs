@@ -0,0 +1,159 @@
+//! Decoding scans of a 4×4 matrix keypad into key codes.
+//!
+//! Bitmasks are active high: bit `i` set means row or column `i` is
+//! active, counting from the top row and left column. Invert the port
+//! reading first for the usual pull-up wiring.
+//!
+//! ```
+//! use u4::{keypad::KeypadLayout, U4};
+//!
+//! let keypad = KeypadLayout::CHIP8;
+//! assert_eq!(keypad.key(0, 3), Some(U4::n(0xc)));
+//! assert_eq!(keypad.decode_masks(0b0100, 0b0001), Ok(Some(U4::n(7))));
+//! ```
+
+use core::fmt::{self, Display, Formatter};
+
+use crate::U4;
+
+/// More than one key was active in a scan where one was expected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultipleKeys;
+
+impl Display for MultipleKeys {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "more than one key pressed")
+    }
+}
+
+impl core::error::Error for MultipleKeys {}
+
+/// The key code at each row and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeypadLayout {
+    keys: [[U4; 4]; 4],
+}
+
+const fn layout(rows: [[u8; 4]; 4]) -> KeypadLayout {
+    let mut keys = [[U4::MIN; 4]; 4];
+    let mut i = 0;
+    while i < 16 {
+        keys[i / 4][i % 4] = U4::from_u8(rows[i / 4][i % 4]);
+        i += 1;
+    }
+    KeypadLayout { keys }
+}
+
+impl KeypadLayout {
+    /// The COSMAC VIP and CHIP-8 hex keypad: `123C`, `456D`, `789E`,
+    /// `A0BF`.
+    pub const CHIP8: KeypadLayout = layout([
+        [0x1, 0x2, 0x3, 0xc],
+        [0x4, 0x5, 0x6, 0xd],
+        [0x7, 0x8, 0x9, 0xe],
+        [0xa, 0x0, 0xb, 0xf],
+    ]);
+
+    /// The common membrane keypad `123A`, `456B`, `789C`, `*0#D`, with `*`
+    /// as E and `#` as F.
+    pub const MEMBRANE: KeypadLayout = layout([
+        [0x1, 0x2, 0x3, 0xa],
+        [0x4, 0x5, 0x6, 0xb],
+        [0x7, 0x8, 0x9, 0xc],
+        [0xe, 0x0, 0xf, 0xd],
+    ]);
+
+    pub const fn new(keys: [[U4; 4]; 4]) -> Self {
+        Self { keys }
+    }
+
+    /// The key at `row` and `column`, or `None` if either is out of range.
+    pub fn key(&self, row: usize, column: usize) -> Option<U4> {
+        self.keys.get(row)?.get(column).copied()
+    }
+
+    /// The row and column of `code`, if it is on the keypad.
+    pub fn position(&self, code: U4) -> Option<(usize, usize)> {
+        (0..16)
+            .map(|i| (i / 4, i % 4))
+            .find(|&(r, c)| self.keys[r][c] == code)
+    }
+
+    /// The key at the single active row and column of two masks, `None` if
+    /// either mask is empty, or an error if either has several bits set.
+    /// Only the low four bits of each mask are used.
+    pub fn decode_masks(&self, rows: u8, columns: u8) -> Result<Option<U4>, MultipleKeys> {
+        let (rows, columns) = (rows & 0xf, columns & 0xf);
+        if rows == 0 || columns == 0 {
+            return Ok(None);
+        }
+        if !rows.is_power_of_two() || !columns.is_power_of_two() {
+            return Err(MultipleKeys);
+        }
+        let (r, c) = (rows.trailing_zeros(), columns.trailing_zeros());
+        Ok(Some(self.keys[r as usize][c as usize]))
+    }
+
+    /// Every key pressed in a full scan, as a mask with bit `code` set for
+    /// each. `scan[r]` is the column mask read while driving row `r`.
+    pub fn pressed(&self, scan: [u8; 4]) -> u16 {
+        let mut keys = 0;
+        for (r, columns) in scan.iter().enumerate() {
+            for c in (0..4).filter(|c| columns & (1 << c) != 0) {
+                keys |= 1 << self.keys[r][c].to_u8();
+            }
+        }
+        keys
+    }
+
+    /// The one key pressed in a full scan, `None` if there is none, or an
+    /// error if there are several.
+    pub fn decode_scan(&self, scan: [u8; 4]) -> Result<Option<U4>, MultipleKeys> {
+        match self.pressed(scan) {
+            0 => Ok(None),
+            keys if keys.is_power_of_two() => Ok(Some(U4::from_u8(keys.trailing_zeros() as u8))),
+            _ => Err(MultipleKeys),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layouts_are_permutations() {
+        for layout in [KeypadLayout::CHIP8, KeypadLayout::MEMBRANE] {
+            let mut seen = 0_u16;
+            for r in 0..4 {
+                for c in 0..4 {
+                    let code = layout.key(r, c).unwrap();
+                    seen |= 1 << code.to_u8();
+                    assert_eq!(layout.position(code), Some((r, c)));
+                }
+            }
+            assert_eq!(seen, u16::MAX);
+        }
+        assert_eq!(KeypadLayout::MEMBRANE.key(3, 0), Some(U4::n(0xe)));
+        assert_eq!(KeypadLayout::MEMBRANE.key(4, 0), None);
+    }
+
+    #[test]
+    fn masks() {
+        let k = KeypadLayout::CHIP8;
+        assert_eq!(k.decode_masks(0b1000, 0b0010), Ok(Some(U4::n(0))));
+        assert_eq!(k.decode_masks(0, 0b0010), Ok(None));
+        assert_eq!(k.decode_masks(0b0011, 0b0010), Err(MultipleKeys));
+        assert_eq!(k.decode_masks(0b1_0001, 0b1000), Ok(Some(U4::n(0xc))));
+    }
+
+    #[test]
+    fn scans() {
+        let k = KeypadLayout::MEMBRANE;
+        assert_eq!(k.decode_scan([0; 4]), Ok(None));
+        assert_eq!(k.decode_scan([0, 0, 0, 0b0100]), Ok(Some(U4::n(0xf))));
+        let both = [0b0001, 0, 0, 0b1000];
+        assert_eq!(k.pressed(both), 1 << 1 | 1 << 0xd);
+        assert_eq!(k.decode_scan(both), Err(MultipleKeys));
+    }
+}
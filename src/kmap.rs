@@ -0,0 +1,309 @@
+//! Karnaugh maps and two-level minimization of [`BoolFn4`]s.
+//!
+//! Variables are named `A` to `D` from the most significant input bit
+//! down, so minterm `m` is the input whose `ABCD` spells `m` in binary.
+
+use core::fmt::{self, Display, Formatter};
+
+use crate::boolfn::BoolFn4;
+use crate::U4;
+
+const GRAY: [u8; 4] = [0b00, 0b01, 0b11, 0b10];
+const NAMES: [char; 4] = ['D', 'C', 'B', 'A'];
+
+/// A Karnaugh map of a [`BoolFn4`], displayed as a Gray-ordered grid with
+/// `AB` down the side and `CD` across the top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KMap {
+    f: BoolFn4,
+    dont_care: u16,
+}
+
+impl KMap {
+    pub fn new(f: BoolFn4) -> Self {
+        Self { f, dont_care: 0 }
+    }
+
+    /// Marks the inputs set in `dont_care` with `-`.
+    pub fn with_dont_cares(self, dont_care: u16) -> Self {
+        Self { dont_care, ..self }
+    }
+}
+
+impl Display for KMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "AB\\CD")?;
+        for cd in GRAY {
+            write!(f, " {cd:02b}")?;
+        }
+        for ab in GRAY {
+            write!(f, "\n   {ab:02b}")?;
+            for cd in GRAY {
+                let m = ab << 2 | cd;
+                let cell = if self.dont_care & (1 << m) != 0 {
+                    '-'
+                } else if self.f.eval(U4::n(m)) {
+                    '1'
+                } else {
+                    '0'
+                };
+                write!(f, "  {cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A product term: the inputs `x` with `x & mask == value`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Cube {
+    value: u8,
+    mask: u8,
+}
+
+impl Cube {
+    /// The cube fixing the variables in `mask` to their bits in `value`.
+    pub fn new(value: U4, mask: U4) -> Self {
+        Self {
+            value: value.to_u8() & mask.to_u8(),
+            mask: mask.to_u8(),
+        }
+    }
+
+    pub fn value(self) -> U4 {
+        U4::from_u8(self.value)
+    }
+
+    pub fn mask(self) -> U4 {
+        U4::from_u8(self.mask)
+    }
+
+    pub fn contains(self, x: U4) -> bool {
+        x.to_u8() & self.mask == self.value
+    }
+
+    /// Number of variables appearing in the term.
+    pub fn literals(self) -> u32 {
+        self.mask.count_ones()
+    }
+
+    /// The minterms covered, as a truth table.
+    pub fn minterms(self) -> u16 {
+        (0..16)
+            .filter(|&x| x & self.mask == self.value)
+            .fold(0, |t, x| t | 1 << x)
+    }
+}
+
+impl Display for Cube {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.mask == 0 {
+            return write!(f, "1");
+        }
+        for bit in (0..4).rev() {
+            if self.mask & (1 << bit) != 0 {
+                write!(f, "{}", NAMES[bit])?;
+                if self.value & (1 << bit) == 0 {
+                    write!(f, "'")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A sum of products, as produced by [`BoolFn4::minimize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sop {
+    terms: [Cube; 16],
+    len: usize,
+}
+
+impl Sop {
+    pub fn terms(&self) -> &[Cube] {
+        &self.terms[..self.len]
+    }
+
+    /// Total number of literals across all terms.
+    pub fn literals(&self) -> u32 {
+        self.terms().iter().map(|c| c.literals()).sum()
+    }
+
+    pub fn to_fn(&self) -> BoolFn4 {
+        BoolFn4::new(self.terms().iter().fold(0, |t, c| t | c.minterms()))
+    }
+
+    fn cost(&self) -> (usize, u32) {
+        (self.len, self.literals())
+    }
+}
+
+impl Display for Sop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.len == 0 {
+            return write!(f, "0");
+        }
+        for (i, term) in self.terms().iter().enumerate() {
+            if i > 0 {
+                write!(f, " + ")?;
+            }
+            write!(f, "{term}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Quine–McCluskey: merges cubes differing in one variable until nothing
+/// merges, keeping the ones never merged. Returns the primes and how many
+/// there are; four variables have at most 3^4 cubes.
+fn prime_implicants(on: u16) -> ([Cube; 81], usize) {
+    let index = |c: Cube| (c.mask << 4 | c.value) as usize;
+    let mut current = [false; 256];
+    for m in (0..16).filter(|m| on & (1 << m) != 0) {
+        current[index(Cube::new(U4::n(m), U4::MAX))] = true;
+    }
+    let mut primes = ([Cube::default(); 81], 0);
+    for _ in 0..=4 {
+        let mut next = [false; 256];
+        let mut merged = [false; 256];
+        for (i, _) in current.iter().enumerate().filter(|(_, &c)| c) {
+            let (value, mask) = (i as u8 & 0xf, i as u8 >> 4);
+            for bit in (0..4).map(|b| 1 << b).filter(|b| mask & b != 0) {
+                let partner = (mask << 4 | value ^ bit) as usize;
+                if current[partner] {
+                    merged[i] = true;
+                    merged[partner] = true;
+                    next[((mask & !bit) << 4 | value & !bit) as usize] = true;
+                }
+            }
+        }
+        for i in (0..256).filter(|&i| current[i] && !merged[i]) {
+            primes.0[primes.1] = Cube {
+                value: i as u8 & 0xf,
+                mask: (i >> 4) as u8,
+            };
+            primes.1 += 1;
+        }
+        current = next;
+    }
+    primes
+}
+
+/// Branch and bound over the primes covering the lowest uncovered minterm.
+fn cover(primes: &[Cube], uncovered: u16, chosen: &mut Sop, best: &mut Option<Sop>) {
+    if uncovered == 0 {
+        if best.is_none_or(|b| chosen.cost() < b.cost()) {
+            *best = Some(*chosen);
+        }
+        return;
+    }
+    if best.is_some_and(|b| chosen.len >= b.len) {
+        return;
+    }
+    let m = uncovered.trailing_zeros();
+    for &p in primes.iter().filter(|p| p.minterms() & (1 << m) != 0) {
+        chosen.terms[chosen.len] = p;
+        chosen.len += 1;
+        cover(primes, uncovered & !p.minterms(), chosen, best);
+        chosen.len -= 1;
+    }
+}
+
+impl BoolFn4 {
+    pub fn kmap(self) -> KMap {
+        KMap::new(self)
+    }
+
+    /// A minimal sum-of-products form: fewest terms, then fewest literals.
+    pub fn minimize(self) -> Sop {
+        self.minimize_with_dont_cares(0)
+    }
+
+    /// Like [`minimize`](Self::minimize), but the inputs set in `dont_care`
+    /// may take either value in the result.
+    pub fn minimize_with_dont_cares(self, dont_care: u16) -> Sop {
+        let on = self.truth_table() & !dont_care;
+        let (primes, len) = prime_implicants(on | dont_care);
+        let mut chosen = Sop {
+            terms: [Cube::default(); 16],
+            len: 0,
+        };
+        let mut best = None;
+        cover(&primes[..len], on, &mut chosen, &mut best);
+        let mut sop = best.unwrap_or(chosen);
+        sop.terms[..sop.len].sort_unstable_by_key(|c| (c.literals(), !c.mask, c.value));
+        sop
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minterms(ms: &[u8]) -> BoolFn4 {
+        BoolFn4::new(ms.iter().fold(0, |t, m| t | 1 << m))
+    }
+
+    #[test]
+    fn kmap_display() {
+        let f = minterms(&[0, 1, 2, 5, 6, 7, 8, 9, 10, 14]);
+        let expected = "\
+AB\\CD 00 01 11 10
+   00  1  1  0  1
+   01  0  1  1  1
+   11  0  0  0  1
+   10  1  1  0  1";
+        assert_eq!(f.kmap().to_string(), expected);
+        let dc = f.kmap().with_dont_cares(1 << 15).to_string();
+        assert_eq!(dc.lines().nth(3), Some("   11  0  0  -  1"));
+    }
+
+    #[test]
+    fn textbook_example() {
+        let f = minterms(&[0, 1, 2, 5, 6, 7, 8, 9, 10, 14]);
+        let sop = f.minimize();
+        assert_eq!(sop.to_fn(), f);
+        // B'D' is prime but covered by the others.
+        assert_eq!(sop.terms().len(), 3);
+        assert_eq!(sop.literals(), 7);
+        assert_eq!(sop.to_string(), "B'C' + CD' + A'BD");
+    }
+
+    #[test]
+    fn dont_cares() {
+        // A BCD digit is at least 5; inputs 10..16 never occur.
+        let f = minterms(&[5, 6, 7, 8, 9]);
+        let sop = f.minimize_with_dont_cares(0xfc00);
+        assert_eq!(sop.to_string(), "A + BC + BD");
+        assert_eq!(sop.to_fn().truth_table() & 0x3ff, f.truth_table());
+    }
+
+    #[test]
+    fn constants_and_parity() {
+        assert_eq!(BoolFn4::ZERO.minimize().to_string(), "0");
+        assert_eq!(BoolFn4::ONE.minimize().to_string(), "1");
+        let parity = BoolFn4::from_fn(|x| x.to_u8().count_ones() % 2 == 1);
+        let sop = parity.minimize();
+        assert_eq!(sop.terms().len(), 8);
+        assert_eq!(sop.literals(), 32);
+    }
+
+    #[test]
+    fn covers_every_function() {
+        for t in (0..=u16::MAX).step_by(61) {
+            let f = BoolFn4::new(t);
+            let sop = f.minimize();
+            assert_eq!(sop.to_fn(), f);
+            // Each prime in a minimal cover is needed.
+            for skip in 0..sop.terms().len() {
+                let rest = sop
+                    .terms()
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| i != skip)
+                    .fold(0, |acc, (_, c)| acc | c.minterms());
+                assert_ne!(rest, t);
+            }
+        }
+    }
+}
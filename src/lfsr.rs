@@ -0,0 +1,128 @@
+use crate::U4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LfsrKind {
+    /// External XOR: the parity of the tapped bits is shifted in at the top.
+    Fibonacci,
+    /// Internal XOR: the bit shifted out toggles the tapped bits.
+    Galois,
+}
+
+/// A 4-bit right-shifting linear feedback shift register.
+///
+/// Iterating yields the output bit (the bit shifted out at the bottom) of
+/// each step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lfsr4 {
+    state: U4,
+    taps: U4,
+    kind: LfsrKind,
+}
+
+impl Lfsr4 {
+    pub fn new(kind: LfsrKind, taps: U4, seed: U4) -> Self {
+        Self {
+            state: seed,
+            taps,
+            kind,
+        }
+    }
+
+    pub fn fibonacci(taps: U4, seed: U4) -> Self {
+        Self::new(LfsrKind::Fibonacci, taps, seed)
+    }
+
+    pub fn galois(taps: U4, seed: U4) -> Self {
+        Self::new(LfsrKind::Galois, taps, seed)
+    }
+
+    pub fn state(&self) -> U4 {
+        self.state
+    }
+
+    pub fn taps(&self) -> U4 {
+        self.taps
+    }
+
+    pub fn kind(&self) -> LfsrKind {
+        self.kind
+    }
+
+    /// Advances one step and returns the bit shifted out.
+    pub fn next_bit(&mut self) -> bool {
+        let state = self.state.to_u8();
+        let taps = self.taps.to_u8();
+        let out = state & 1;
+        let next = match self.kind {
+            LfsrKind::Fibonacci => {
+                let feedback = ((state & taps).count_ones() & 1) as u8;
+                (state >> 1) | (feedback << 3)
+            }
+            LfsrKind::Galois => {
+                if out == 1 {
+                    (state >> 1) ^ taps
+                } else {
+                    state >> 1
+                }
+            }
+        };
+        self.state = U4::from_u8(next);
+        out == 1
+    }
+
+    /// Advances one step and returns the new state.
+    pub fn step(&mut self) -> U4 {
+        self.next_bit();
+        self.state
+    }
+
+    /// Number of steps until the register returns to its current state, or
+    /// `None` if it never does (the taps do not define an invertible map).
+    pub fn period(&self) -> Option<usize> {
+        let mut lfsr = *self;
+        (1..=16).find(|_| lfsr.step() == self.state)
+    }
+}
+
+impl Iterator for Lfsr4 {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_bit())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maximal_length() {
+        let fib = Lfsr4::fibonacci(U4::n(0b0011), U4::n(1));
+        assert_eq!(fib.period(), Some(15));
+
+        let galois = Lfsr4::galois(U4::n(0b1100), U4::n(1));
+        assert_eq!(galois.period(), Some(15));
+    }
+
+    #[test]
+    fn zero_state() {
+        let lfsr = Lfsr4::galois(U4::n(0b1100), U4::n(0));
+        assert_eq!(lfsr.period(), Some(1));
+    }
+
+    #[test]
+    fn not_invertible() {
+        let lfsr = Lfsr4::fibonacci(U4::n(0b0010), U4::n(1));
+        assert_eq!(lfsr.period(), None);
+    }
+
+    #[test]
+    fn bits() {
+        let mut lfsr = Lfsr4::galois(U4::n(0b1100), U4::n(0b1001));
+        assert_eq!(lfsr.step(), U4::n(0b1000));
+
+        let bits: Vec<bool> = lfsr.take(4).collect();
+        assert_eq!(bits, vec![false, false, false, true]);
+    }
+}
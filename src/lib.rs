@@ -1,48 +1,197 @@
-use std::{
+#![cfg_attr(not(test), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(all(feature = "std", not(test)))]
+extern crate std;
+
+use core::{
     fmt::{Display, Formatter},
     num::ParseIntError,
 };
 
-use hex::{decode_hex, encode_hex};
-
+#[cfg(feature = "alloc")]
+use hex::encode_hex;
+use hex::hex_digit;
+pub use hex::{HexDisplay, ParseHexError, ToHexDisplay};
+#[cfg(feature = "num-traits")]
+pub use num::FromStrRadixError;
+pub use ufix::Rounding;
+
+pub mod alias;
+pub mod alu;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+pub mod arpa;
+#[cfg(target_has_atomic = "8")]
+pub mod atomic;
+pub mod bcd;
+#[cfg(feature = "num-bigint")]
+pub mod bigint;
+pub mod bitmatrix;
+#[cfg(feature = "alloc")]
+pub mod bloom;
+pub mod boolfn;
+#[cfg(feature = "borsh")]
+mod borsh;
+#[cfg(feature = "alloc")]
+pub mod comp3;
+pub mod counter;
+pub mod ct;
+pub mod dtmf;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod flags;
+#[cfg(feature = "getrandom")]
+mod getrandom;
+pub mod gf16;
+pub mod hd44780;
 mod hex;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct U4 {
+pub mod i4;
+pub mod i4004;
+mod integer;
+pub mod keypad;
+pub mod kmap;
+pub mod lfsr;
+pub mod limbs;
+pub mod line4b5b;
+pub mod logic;
+pub mod lut;
+#[doc(hidden)]
+pub mod macros;
+pub mod midi;
+pub mod mini_aes;
+mod modular;
+pub mod morton;
+#[cfg(feature = "alloc")]
+pub mod nibble_counters;
+pub mod nibble_enum;
+#[cfg(feature = "alloc")]
+pub mod nibble_path;
+#[cfg(feature = "num-traits")]
+mod num;
+pub mod op_table;
+pub mod opcode;
+pub mod pack;
+pub mod perm;
+pub mod planes;
+pub mod port;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "quickcheck")]
+mod quickcheck;
+#[cfg(feature = "rand")]
+pub mod rand;
+#[cfg(feature = "alloc")]
+pub mod rank_select;
+pub mod register;
+#[cfg(feature = "rkyv")]
+pub mod rkyv;
+#[cfg(feature = "alloc")]
+pub mod rle4;
+pub mod rtc;
+pub mod sbox;
+#[cfg(feature = "schemars")]
+mod schemars;
+pub mod search;
+#[cfg(feature = "alloc")]
+pub mod semi_octet;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "alloc")]
+pub mod srec;
+pub mod stats;
+#[cfg(feature = "test_support")]
+pub mod test_support;
+pub mod total;
+#[cfg(feature = "alloc")]
+pub mod toy_cipher;
+pub mod trace;
+#[cfg(feature = "alloc")]
+pub mod trie;
+pub mod ufix;
+pub mod varint;
+#[cfg(feature = "zeroize")]
+mod zeroize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct U4 {
     bits: [bool; 4],
 }
 
 impl Display for U4 {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.to_u8())
     }
 }
 
-impl std::ops::BitXor for U4 {
+#[cfg(feature = "defmt")]
+impl defmt::Format for U4 {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=u8}", self.to_u8())
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for U4 {
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        ufmt::uDisplay::fmt(&self.to_u8(), f)
+    }
+}
+
+impl core::ops::BitXor for U4 {
     type Output = Self;
 
     fn bitxor(self, rhs: Self) -> Self::Output {
         let mut bits = [false; 4];
-        for i in 0..4 {
-            bits[i] = self.bits[i] ^ rhs.bits[i];
+        for (i, bit) in bits.iter_mut().enumerate() {
+            *bit = self.bits[i] ^ rhs.bits[i];
         }
         Self { bits }
     }
 }
 
-impl std::ops::BitOr for U4 {
+impl core::ops::BitOr for U4 {
     type Output = Self;
 
     fn bitor(self, rhs: Self) -> Self::Output {
         let mut bits = [false; 4];
-        for i in 0..4 {
-            bits[i] = self.bits[i] || rhs.bits[i];
+        for (i, bit) in bits.iter_mut().enumerate() {
+            *bit = self.bits[i] || rhs.bits[i];
         }
         Self { bits }
     }
 }
 
-impl std::ops::Add for U4 {
+impl core::ops::BitAnd for U4 {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let mut bits = [false; 4];
+        for (i, bit) in bits.iter_mut().enumerate() {
+            *bit = self.bits[i] && rhs.bits[i];
+        }
+        Self { bits }
+    }
+}
+
+impl core::ops::Not for U4 {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            bits: self.bits.map(|bit| !bit),
+        }
+    }
+}
+
+impl core::ops::Add for U4 {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -83,7 +232,7 @@ impl std::ops::Add for U4 {
     }
 }
 
-impl std::ops::Sub for U4 {
+impl core::ops::Sub for U4 {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self::Output {
         let mut bits = [false; 4];
@@ -92,24 +241,9 @@ impl std::ops::Sub for U4 {
             let a = self.bits[i];
             let b = rhs.bits[i];
 
-            // 1 - 1
-            if a && b {
-                if carry {
-                    carry = false;
-                    bits[i] = true;
-                } else {
-                    carry = false;
-                    bits[i] = false;
-                }
-            // 0 - 0
-            } else if !a && !b {
-                if carry {
-                    carry = true;
-                    bits[i] = true;
-                } else {
-                    carry = false;
-                    bits[i] = false;
-                }
+            // 1 - 1 and 0 - 0: the borrow passes through unchanged.
+            if a == b {
+                bits[i] = carry;
             // 0 - 1
             } else if !a && b {
                 if carry {
@@ -135,69 +269,313 @@ impl std::ops::Sub for U4 {
     }
 }
 
+impl core::ops::Mul for U4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::from_u8(self.to_u8().wrapping_mul(rhs.to_u8()))
+    }
+}
+
+impl core::ops::Div for U4 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Self::from_u8(self.to_u8() / rhs.to_u8())
+    }
+}
+
+impl core::ops::Rem for U4 {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        Self::from_u8(self.to_u8() % rhs.to_u8())
+    }
+}
+
+/// Bits shifted past either end are discarded; shifting by 4 or more gives 0.
+impl core::ops::Shl<usize> for U4 {
+    type Output = Self;
+
+    fn shl(self, rhs: usize) -> Self::Output {
+        if rhs >= Self::BITS {
+            return Self::MIN;
+        }
+        Self::from_u8(self.to_u8() << rhs)
+    }
+}
+
+/// Bits shifted past either end are discarded; shifting by 4 or more gives 0.
+impl core::ops::Shr<usize> for U4 {
+    type Output = Self;
+
+    fn shr(self, rhs: usize) -> Self::Output {
+        if rhs >= Self::BITS {
+            return Self::MIN;
+        }
+        Self::from_u8(self.to_u8() >> rhs)
+    }
+}
+
+/// A bit slice given to `U4::try_from` does not hold exactly four bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitCountError(pub usize);
+
+impl Display for BitCountError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "expected 4 bits, got {}", self.0)
+    }
+}
+
+impl core::error::Error for BitCountError {}
+
+/// Reads the bits in MSB 0 order, as [`U4::from_bits_msb0`].
+impl From<[bool; 4]> for U4 {
+    fn from(bits: [bool; 4]) -> Self {
+        Self::from_bits_msb0(bits)
+    }
+}
+
+/// Reads the bits in MSB 0 order, as [`U4::from_bits_msb0`].
+impl TryFrom<&[bool]> for U4 {
+    type Error = BitCountError;
+
+    fn try_from(bits: &[bool]) -> Result<Self, Self::Error> {
+        match <[bool; 4]>::try_from(bits) {
+            Ok(bits) => Ok(Self::from_bits_msb0(bits)),
+            Err(_) => Err(BitCountError(bits.len())),
+        }
+    }
+}
+
+/// Operators with a `u8` right-hand side act on its full value and keep the
+/// low nibble of the result, matching the wrapping `U4` operators: `+ 17`
+/// adds 1, and `/ 16` gives 0. Division and remainder by zero panic.
+macro_rules! impl_u8_rhs {
+    ($($trait:ident $method:ident $op:ident,)*) => {$(
+        impl core::ops::$trait<u8> for U4 {
+            type Output = Self;
+
+            fn $method(self, rhs: u8) -> Self::Output {
+                Self::from_u8(self.to_u8().$op(rhs))
+            }
+        }
+    )*};
+}
+
+impl_u8_rhs! {
+    Add add wrapping_add,
+    Sub sub wrapping_sub,
+    Mul mul wrapping_mul,
+    Div div div,
+    Rem rem rem,
+    BitAnd bitand bitand,
+    BitOr bitor bitor,
+    BitXor bitxor bitxor,
+}
+
+/// Compares by value, so a `u8` above 15 is never equal to a nibble and
+/// always greater than one.
+impl PartialEq<u8> for U4 {
+    fn eq(&self, other: &u8) -> bool {
+        self.to_u8() == *other
+    }
+}
+
+impl PartialEq<U4> for u8 {
+    fn eq(&self, other: &U4) -> bool {
+        *self == other.to_u8()
+    }
+}
+
+impl PartialOrd<u8> for U4 {
+    fn partial_cmp(&self, other: &u8) -> Option<core::cmp::Ordering> {
+        self.to_u8().partial_cmp(other)
+    }
+}
+
+impl PartialOrd<U4> for u8 {
+    fn partial_cmp(&self, other: &U4) -> Option<core::cmp::Ordering> {
+        self.partial_cmp(&other.to_u8())
+    }
+}
+
 impl U4 {
     pub const MIN: U4 = U4 { bits: [false; 4] };
     pub const MAX: U4 = U4 { bits: [true; 4] };
     pub const BITS: usize = 4;
 
-    fn n(n: u8) -> Self {
+    pub fn n(n: u8) -> Self {
         Self::from_u8(n)
     }
 
-    fn to_u8(&self) -> u8 {
+    pub const fn to_u8(&self) -> u8 {
         let mut sum = 0;
-        for i in 0..Self::BITS {
+        let mut i = 0;
+        while i < Self::BITS {
             let bit = self.bits[Self::BITS - i - 1];
 
             if bit {
                 sum += 2_u8.pow(i as u32);
             }
+            i += 1;
         }
         sum
     }
 
-    fn from_bytes(a: &[u8]) -> Self {
+    /// Reads the low nibble of `a[0]`, panicking if `a` is empty.
+    #[deprecated(
+        note = "use `U4::from_u8(a[0])`, or `from_bits_msb0`/`from_bits_lsb0` for bit arrays"
+    )]
+    pub fn from_bytes(a: &[u8]) -> Self {
         let mut bits = [false; Self::BITS];
-        for i in 0..U4::BITS {
-            bits[i] = (a[0] & (1 << i)) != 0;
+        for (i, bit) in bits.iter_mut().enumerate() {
+            *bit = (a[0] & (1 << i)) != 0;
         }
         bits.reverse();
         U4 { bits }
     }
 
-    // From Hex String
-    fn from_hex_str(s: &str) -> Result<Self, ParseIntError> {
-        let s = decode_hex(s)?;
-        Ok(Self::from_bytes(&s))
+    /// Builds a nibble from bits in MSB 0 order: `bits[0]` is the most
+    /// significant bit, as written in binary literals.
+    pub const fn from_bits_msb0(bits: [bool; 4]) -> Self {
+        U4 { bits }
     }
 
-    fn to_hex_str(&self) -> String {
-        encode_hex(&self.to_u8().to_le_bytes())
+    /// Builds a nibble from bits in LSB 0 order: `bits[i]` is the bit worth
+    /// `2^i`.
+    pub const fn from_bits_lsb0(bits: [bool; 4]) -> Self {
+        U4 {
+            bits: [bits[3], bits[2], bits[1], bits[0]],
+        }
     }
 
-    fn from_u8(u: u8) -> Self {
-        let mut bits = [false; Self::BITS];
-        for i in 0..Self::BITS {
-            bits[i] = (u & (1 << i)) != 0;
+    /// `f` evaluated at every nibble in order, for building lookup tables. See
+    /// [`u4_table!`] for a compile-time version.
+    pub fn table(f: impl Fn(U4) -> U4) -> [U4; 16] {
+        core::array::from_fn(|i| f(Self::from_u8(i as u8)))
+    }
+
+    /// Builds a nibble bit by bit: bit `i`, worth `2^i`, is `f(i)`, with `f`
+    /// called for `i` from 0 to 3 in order.
+    pub fn from_fn(mut f: impl FnMut(usize) -> bool) -> Self {
+        Self::from_bits_lsb0(core::array::from_fn(&mut f))
+    }
+
+    pub const fn to_bits_msb0(self) -> [bool; 4] {
+        self.bits
+    }
+
+    pub const fn to_bits_lsb0(self) -> [bool; 4] {
+        let b = self.bits;
+        [b[3], b[2], b[1], b[0]]
+    }
+
+    /// Quantizes an 8-bit channel value to 4 bits, mapping `0..=255` onto
+    /// `0..=15` so that both ends are preserved. Unlike `v >> 4`, this is
+    /// the inverse of [`to_u8_scaled`](Self::to_u8_scaled).
+    pub const fn from_u8_scaled(v: u8, rounding: Rounding) -> Self {
+        Self::from_u8(rounding.div(v as u32, 17) as u8)
+    }
+
+    /// Expands to an 8-bit channel value by bit replication, `0xa` to
+    /// `0xaa`, which maps 15 to 255.
+    pub const fn to_u8_scaled(self) -> u8 {
+        self.to_u8() * 0x11
+    }
+
+    /// Parses a hex number of up to two digits and keeps its low nibble, so
+    /// `"b"` and `"0b"` both give 11.
+    pub const fn from_hex_str(s: &str) -> Result<Self, ParseIntError> {
+        match u8::from_str_radix(s, 16) {
+            Ok(n) => Ok(Self::from_u8(n)),
+            Err(e) => Err(e),
         }
-        bits.reverse();
-        U4 { bits }
     }
 
-    pub fn rotate_left(self, n: u32) -> Self {
-        let mut bits = [false; Self::BITS];
-        for i in 0..Self::BITS {
-            bits[i] = self.bits[(i + n as usize) % Self::BITS];
+    /// A single hex digit of either case.
+    pub const fn from_hex_digit(c: u8) -> Option<Self> {
+        match hex_digit(c) {
+            Some(n) => Some(Self::from_u8(n)),
+            None => None,
         }
-        Self { bits }
     }
 
-    pub fn rotate_right(self, n: u32) -> Self {
+    /// Exactly `N` hex digits, one nibble each, e.g. an S-box table.
+    ///
+    /// ```
+    /// use u4::U4;
+    ///
+    /// const KEY: [U4; 4] = match U4::from_hex_array("c3f0") {
+    ///     Ok(key) => key,
+    ///     Err(_) => panic!("bad key"),
+    /// };
+    /// assert_eq!(KEY[0], U4::n(0xc));
+    /// ```
+    pub const fn from_hex_array<const N: usize>(s: &str) -> Result<[Self; N], ParseHexError> {
+        let bytes = s.as_bytes();
+        if bytes.len() != N {
+            return Err(ParseHexError::WrongLength {
+                expected: N,
+                found: bytes.len(),
+            });
+        }
+        let mut out = [Self::MIN; N];
+        let mut i = 0;
+        while i < N {
+            out[i] = match Self::from_hex_digit(bytes[i]) {
+                Some(n) => n,
+                None => {
+                    return Err(ParseHexError::InvalidDigit {
+                        index: i,
+                        byte: bytes[i],
+                    })
+                }
+            };
+            i += 1;
+        }
+        Ok(out)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn to_hex_str(&self) -> alloc::string::String {
+        encode_hex(&self.to_u8().to_le_bytes())
+    }
+
+    pub const fn from_u8(u: u8) -> Self {
         let mut bits = [false; Self::BITS];
-        for i in 0..Self::BITS {
-            bits[i] = self.bits[(i + Self::BITS - n as usize) % Self::BITS];
+        let mut i = 0;
+        while i < Self::BITS {
+            bits[Self::BITS - i - 1] = (u & (1 << i)) != 0;
+            i += 1;
         }
-        Self { bits }
+        U4 { bits }
+    }
+
+    /// Rotates left by `n` bits; any `n` is accepted and taken modulo 4.
+    pub const fn rotate_left(self, n: u32) -> Self {
+        let n = n % Self::BITS as u32;
+        let v = self.to_u8();
+        Self::from_u8((v << n) | (v >> ((Self::BITS as u32 - n) % Self::BITS as u32)))
+    }
+
+    /// Rotates right by `n` bits; any `n` is accepted and taken modulo 4.
+    pub const fn rotate_right(self, n: u32) -> Self {
+        self.rotate_left(Self::BITS as u32 - n % Self::BITS as u32)
+    }
+
+    /// Rotates left by one bit through `carry` (RCL): the old carry enters
+    /// bit 0 and the old bit 3 is returned as the new carry.
+    pub const fn rotate_left_through_carry(self, carry: bool) -> (Self, bool) {
+        let v = self.to_u8();
+        (Self::from_u8((v << 1) | carry as u8), v & 0b1000 != 0)
+    }
+
+    /// Rotates right by one bit through `carry` (RCR): the old carry enters
+    /// bit 3 and the old bit 0 is returned as the new carry.
+    pub const fn rotate_right_through_carry(self, carry: bool) -> (Self, bool) {
+        let v = self.to_u8();
+        (Self::from_u8((v >> 1) | (carry as u8) << 3), v & 1 != 0)
     }
 
     pub fn wrapping_add(self, rhs: Self) -> Self {
@@ -207,6 +585,79 @@ impl U4 {
     pub fn wrapping_sub(self, rhs: Self) -> Self {
         self - rhs
     }
+
+    /// `self + rhs + carry`, returning the sum and the carry out.
+    pub const fn carrying_add(self, rhs: Self, carry: bool) -> (Self, bool) {
+        let sum = self.to_u8() + rhs.to_u8() + carry as u8;
+        (Self::from_u8(sum), sum > 15)
+    }
+
+    /// `self - rhs - borrow`, returning the difference and the borrow out.
+    pub const fn borrowing_sub(self, rhs: Self, borrow: bool) -> (Self, bool) {
+        let diff = self.to_u8().wrapping_sub(rhs.to_u8() + borrow as u8);
+        (Self::from_u8(diff), diff > 15)
+    }
+
+    /// Carry-less (GF(2) polynomial) multiplication without reduction.
+    pub const fn clmul(self, rhs: Self) -> u8 {
+        let (a, b) = (self.to_u8(), rhs.to_u8());
+        let mut r = 0;
+        let mut i = 0;
+        while i < Self::BITS {
+            if b & (1 << i) != 0 {
+                r ^= a << i;
+            }
+            i += 1;
+        }
+        r
+    }
+
+    /// Decimal-adjusts a BCD digit after a binary addition, like the
+    /// 8080/Z80 `DAA` applied to one nibble of the accumulator.
+    ///
+    /// `flag` is the digit's flag from the binary addition: AC for the low
+    /// digit, CY for the high one. `carry_in` is the correction carry
+    /// returned for the digit below. Six is added if the digit is above 9
+    /// or `flag` is set.
+    ///
+    /// Returns the corrected digit and the correction carry, the carry out
+    /// of adding that six, which is what feeds the next digit. It is not the
+    /// decimal carry: that is the correction carry or `flag`.
+    ///
+    /// ```
+    /// # use u4::U4;
+    /// // 0x09 + 0x09 = 0x12 with AC set and CY clear; DAA gives 0x18.
+    /// let (ac, cy) = (true, false);
+    /// let (lo, c) = U4::n(2).decimal_adjust_add(false, ac);
+    /// let (hi, c) = U4::n(1).decimal_adjust_add(c, cy);
+    /// assert_eq!((hi, lo, c || cy), (U4::n(1), U4::n(8), false));
+    /// ```
+    pub fn decimal_adjust_add(self, carry_in: bool, flag: bool) -> (Self, bool) {
+        let mut sum = self.to_u8() + carry_in as u8;
+        if flag || sum > 9 {
+            sum += 6;
+        }
+        (Self::from_u8(sum), sum > 0xf)
+    }
+
+    /// Decimal-adjusts a BCD digit after a binary subtraction, like the Z80
+    /// `DAA` after a subtraction or the 8086 `DAS`, applied to one nibble.
+    ///
+    /// `flag` is the digit's borrow flag from the binary subtraction: AC for
+    /// the low digit, CY for the high one. `borrow_in` is the correction
+    /// borrow returned for the digit below. Six is subtracted if the digit
+    /// is above 9 or `flag` is set.
+    ///
+    /// Returns the corrected digit and the correction borrow, the borrow out
+    /// of subtracting that six. The decimal borrow is the correction borrow
+    /// or `flag`.
+    pub fn decimal_adjust_sub(self, borrow_in: bool, flag: bool) -> (Self, bool) {
+        let mut diff = self.to_u8() as i8 - borrow_in as i8;
+        if flag || !(0..=9).contains(&diff) {
+            diff -= 6;
+        }
+        (Self::from_u8(diff as u8), diff < 0)
+    }
 }
 
 #[cfg(test)]
@@ -241,6 +692,44 @@ mod tests {
     }
 
     #[test]
+    fn rotate_exhaustive() {
+        for v in 0..16_u8 {
+            let u = U4::n(v);
+            let wide = v * 0x11;
+            for n in [0, 1, 2, 3, 4, 5, 7, 8, 31, 32, 33, u32::MAX] {
+                let expect_left = (wide << (n % 4)) >> 4;
+                let expect_right = wide >> (n % 4);
+                assert_eq!(u.rotate_left(n), U4::n(expect_left), "{v} rotl {n}");
+                assert_eq!(u.rotate_right(n), U4::n(expect_right), "{v} rotr {n}");
+                assert_eq!(u.rotate_left(n).rotate_right(n), u);
+            }
+        }
+    }
+
+    #[test]
+    fn rotate_through_carry() {
+        assert_eq!(
+            U4::n(0b1001).rotate_left_through_carry(false),
+            (U4::n(0b0010), true)
+        );
+        assert_eq!(
+            U4::n(0b0001).rotate_right_through_carry(true),
+            (U4::n(0b1000), true)
+        );
+        // Five steps through the carry cycle back to the start.
+        for v in 0..32_u8 {
+            let (mut n, mut c) = (U4::n(v), v & 0x10 != 0);
+            for _ in 0..5 {
+                (n, c) = n.rotate_left_through_carry(c);
+            }
+            assert_eq!((n, c), (U4::n(v), v & 0x10 != 0));
+            let (l, lc) = U4::n(v).rotate_left_through_carry(c);
+            assert_eq!(l.rotate_right_through_carry(lc), (U4::n(v), c));
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
     fn from_bytes() {
         let a = 11_u8.to_le_bytes();
         let b = 1_u8.to_le_bytes();
@@ -251,10 +740,123 @@ mod tests {
         assert_eq!(u, U4::n(1));
     }
 
+    #[test]
+    fn bool_conversions() {
+        let bits = [true, false, true, true];
+        assert_eq!(U4::from(bits), U4::n(0b1011));
+        assert_eq!(U4::try_from(&bits[..]), Ok(U4::n(0b1011)));
+        assert_eq!(U4::try_from(&bits[1..]), Err(BitCountError(3)));
+        assert_eq!(U4::try_from(&[false; 5][..]), Err(BitCountError(5)));
+        assert_eq!(BitCountError(3).to_string(), "expected 4 bits, got 3");
+
+        assert_eq!(U4::from_fn(|i| i == 0 || i == 3), U4::n(0b1001));
+        for n in (0..16).map(U4::n) {
+            assert_eq!(U4::from_fn(|i| n.to_bits_lsb0()[i]), n);
+        }
+        let mut read = [false, true, true, false].into_iter();
+        assert_eq!(U4::from_fn(|_| read.next().unwrap()), U4::n(0b0110));
+    }
+
+    #[test]
+    fn u8_rhs() {
+        for a in (0..16).map(U4::n) {
+            for b in 0..=255_u8 {
+                let n = U4::n(b);
+                assert_eq!(a + b, a + n);
+                assert_eq!(a - b, a - n);
+                assert_eq!(a * b, a * n);
+                assert_eq!(a & b, a & n);
+                assert_eq!(a | b, a | n);
+                assert_eq!(a ^ b, a ^ n);
+                if b != 0 {
+                    assert_eq!(a / b, a.to_u8() / b);
+                    assert_eq!(a % b, a.to_u8() % b);
+                }
+            }
+        }
+        assert_eq!(U4::n(3) + 17, U4::n(4));
+        assert_eq!(U4::n(9) / 16, U4::MIN);
+    }
+
+    #[test]
+    fn compare_u8() {
+        let n = U4::n(0xf);
+        assert!(n == 0xf);
+        assert!(0xf == n);
+        assert!(n != 0x1f);
+        assert!(n < 16);
+        assert!(16_u8 > n);
+        assert!(U4::n(3) > 2);
+        assert!(2 < U4::n(3));
+        assert!(U4::MIN <= 0);
+    }
+
+    #[test]
+    fn scaled() {
+        for n in (0..16).map(U4::n) {
+            let v = n.to_u8_scaled();
+            for rounding in [Rounding::Down, Rounding::Up, Rounding::Nearest] {
+                assert_eq!(U4::from_u8_scaled(v, rounding), n);
+            }
+        }
+        assert_eq!(U4::n(0xa).to_u8_scaled(), 0xaa);
+        assert_eq!(U4::from_u8_scaled(255, Rounding::Down), U4::MAX);
+        assert_eq!(U4::from_u8_scaled(0xf0, Rounding::Nearest), U4::n(14));
+        assert_eq!(U4::from_u8_scaled(0xf0, Rounding::Up), U4::MAX);
+        assert_eq!(U4::from_u8_scaled(8, Rounding::Nearest), U4::n(0));
+        assert_eq!(U4::from_u8_scaled(9, Rounding::Nearest), U4::n(1));
+        // The bins are as even as 256 values allow.
+        let mut bins = [0; 16];
+        for v in 0..=255 {
+            bins[U4::from_u8_scaled(v, Rounding::Nearest).to_u8() as usize] += 1;
+        }
+        assert_eq!(bins[0], 9);
+        assert!(bins[1..15].iter().all(|&b| b == 17));
+    }
+
+    #[test]
+    fn bit_order() {
+        let msb0 = [true, false, true, true];
+        assert_eq!(U4::from_bits_msb0(msb0), U4::n(0b1011));
+        assert_eq!(U4::from_bits_lsb0(msb0), U4::n(0b1101));
+        for n in (0..16).map(U4::n) {
+            assert_eq!(U4::from_bits_msb0(n.to_bits_msb0()), n);
+            assert_eq!(U4::from_bits_lsb0(n.to_bits_lsb0()), n);
+            assert_eq!(n.to_bits_lsb0()[0], n.to_u8() & 1 == 1);
+        }
+    }
+
     #[test]
     fn from_hex() {
         let u = U4::from_hex_str("0b").unwrap();
         assert_eq!(u, U4::n(11));
+        assert_eq!(U4::from_hex_str("F"), Ok(U4::MAX));
+        assert!(U4::from_hex_str("").is_err());
+        assert!(U4::from_hex_str("x").is_err());
+    }
+
+    #[test]
+    fn from_hex_const() {
+        const D: Option<U4> = U4::from_hex_digit(b'a');
+        assert_eq!(D, Some(U4::n(10)));
+        assert_eq!(U4::from_hex_digit(b'g'), None);
+
+        const A: Result<[U4; 3], ParseHexError> = U4::from_hex_array("1Fe");
+        assert_eq!(A, Ok([U4::n(1), U4::n(15), U4::n(14)]));
+        assert_eq!(
+            U4::from_hex_array::<2>("1x"),
+            Err(ParseHexError::InvalidDigit {
+                index: 1,
+                byte: b'x'
+            })
+        );
+        assert_eq!(
+            U4::from_hex_array::<2>("123"),
+            Err(ParseHexError::WrongLength {
+                expected: 2,
+                found: 3
+            })
+        );
     }
 
     #[test]
@@ -271,6 +873,8 @@ mod tests {
         let b = U4::from_u8(2);
 
         assert_eq!(a - b, U4::from_u8(1));
+        assert_eq!(b - a, U4::MAX);
+        assert_eq!(U4::n(0b0100) - U4::n(0b0111), U4::n(0b1101));
     }
 
     #[test]
@@ -296,6 +900,19 @@ mod tests {
 
         assert_eq!(a | b, U4::from_u8(7));
     }
+    #[test]
+    fn and() {
+        let a = U4::from_u8(3);
+        let b = U4::from_u8(6);
+
+        assert_eq!(a & b, U4::from_u8(2));
+    }
+
+    #[test]
+    fn not() {
+        assert_eq!(!U4::from_u8(5), U4::from_u8(10));
+    }
+
     #[test]
     fn xor() {
         let a = U4::from_u8(3);
@@ -303,4 +920,103 @@ mod tests {
 
         assert_eq!(a ^ b, U4::from_u8(6));
     }
+
+    /// Adjusts an 8-bit binary result digit by digit, returning the byte and
+    /// the decimal carry or borrow.
+    fn daa(a: u8, ac: bool, cy: bool, sub: bool) -> (u8, bool) {
+        let adjust = if sub {
+            U4::decimal_adjust_sub
+        } else {
+            U4::decimal_adjust_add
+        };
+        let (lo, c) = adjust(U4::n(a & 0xf), false, ac);
+        let (hi, c) = adjust(U4::n(a >> 4), c, cy);
+        (hi.to_u8() << 4 | lo.to_u8(), c || cy)
+    }
+
+    #[test]
+    fn decimal_adjust_add() {
+        assert_eq!(U4::n(2).decimal_adjust_add(false, true), (U4::n(8), false));
+        assert_eq!(
+            U4::n(0xd).decimal_adjust_add(false, false),
+            (U4::n(3), true)
+        );
+        assert_eq!(U4::n(7).decimal_adjust_add(false, false), (U4::n(7), false));
+        // 09 + 09, 15 + 27, 99 + 99, 50 + 50, 99 + 01, 38 + 45
+        assert_eq!(daa(0x12, true, false, false), (0x18, false));
+        assert_eq!(daa(0x3c, false, false, false), (0x42, false));
+        assert_eq!(daa(0x32, true, true, false), (0x98, true));
+        assert_eq!(daa(0xa0, false, false, false), (0x00, true));
+        assert_eq!(daa(0x9a, false, false, false), (0x00, true));
+        assert_eq!(daa(0x7d, false, false, false), (0x83, false));
+    }
+
+    #[test]
+    fn decimal_adjust_sub() {
+        assert_eq!(
+            U4::n(0xe).decimal_adjust_sub(false, true),
+            (U4::n(8), false)
+        );
+        assert_eq!(U4::n(4).decimal_adjust_sub(false, false), (U4::n(4), false));
+        // 10 - 01, 00 - 01, 32 - 15, 45 - 38, 20 - 30
+        assert_eq!(daa(0x0f, true, false, true), (0x09, false));
+        assert_eq!(daa(0xff, true, true, true), (0x99, true));
+        assert_eq!(daa(0x1d, true, false, true), (0x17, false));
+        assert_eq!(daa(0x0d, true, false, true), (0x07, false));
+        assert_eq!(daa(0xf0, false, true, true), (0x90, true));
+    }
+
+    #[test]
+    fn clmul() {
+        assert_eq!(U4::n(0b11).clmul(U4::n(0b11)), 0b101);
+        assert_eq!(U4::n(0xf).clmul(U4::n(0xf)), 0b1010101);
+        assert_eq!(U4::n(0x8).clmul(U4::n(0x8)), 0b1000000);
+        assert_eq!(U4::n(0x9).clmul(U4::n(0)), 0);
+    }
+
+    #[test]
+    fn mul() {
+        assert_eq!(U4::from_u8(3) * U4::from_u8(4), U4::from_u8(12));
+        assert_eq!(U4::from_u8(5) * U4::from_u8(4), U4::from_u8(4));
+    }
+
+    #[test]
+    fn div_rem() {
+        assert_eq!(U4::from_u8(15) / U4::from_u8(4), U4::from_u8(3));
+        assert_eq!(U4::from_u8(15) % U4::from_u8(4), U4::from_u8(3));
+    }
+
+    #[test]
+    fn shifts() {
+        assert_eq!(U4::n(0b0111) << 1, U4::n(0b1110));
+        assert_eq!(U4::n(0b1011) << 2, U4::n(0b1100));
+        assert_eq!(U4::n(0b1011) >> 3, U4::n(0b0001));
+        assert_eq!(U4::MAX << 4, U4::MIN);
+        assert_eq!(U4::MAX >> 9, U4::MIN);
+    }
+
+    #[test]
+    #[should_panic]
+    fn div_by_zero() {
+        let _ = U4::from_u8(1) / U4::MIN;
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn ufmt() {
+        struct Buf(String);
+
+        impl ufmt::uWrite for Buf {
+            type Error = core::convert::Infallible;
+
+            fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+                self.0.push_str(s);
+                Ok(())
+            }
+        }
+
+        let mut buf = Buf(String::new());
+        ufmt::uwrite!(buf, "{} {:?}", U4::from_u8(11), U4::from_u8(1)).unwrap();
+        assert_eq!(buf.0, format!("11 {:?}", U4::from_u8(1)));
+    }
 }
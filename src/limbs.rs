@@ -0,0 +1,170 @@
+//! Schoolbook arithmetic on slices of nibble limbs.
+//!
+//! Limb 0 is the least significant, so `[U4::n(0xd), U4::n(0xc)]` is
+//! `0xcd`.
+//!
+//! ```
+//! use u4::{limbs, U4};
+//!
+//! let mut a = [0xf, 0xf, 0x0].map(U4::n);
+//! let carry = limbs::add_limbs(&mut a, &[U4::n(1)]);
+//! assert_eq!((a, carry), ([0x0, 0x0, 0x1].map(U4::n), false));
+//! ```
+
+use crate::U4;
+
+/// `a += b`, returning the carry out of the top limb.
+///
+/// # Panics
+///
+/// If `b` has more limbs than `a`.
+pub fn add_limbs(a: &mut [U4], b: &[U4]) -> bool {
+    assert!(b.len() <= a.len(), "right-hand side has more limbs");
+    let mut carry = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        let y = b.get(i).copied().unwrap_or(U4::MIN);
+        (*x, carry) = x.carrying_add(y, carry);
+    }
+    carry
+}
+
+/// `a -= b`, returning the borrow out of the top limb.
+///
+/// # Panics
+///
+/// If `b` has more limbs than `a`.
+pub fn sub_limbs(a: &mut [U4], b: &[U4]) -> bool {
+    assert!(b.len() <= a.len(), "right-hand side has more limbs");
+    let mut borrow = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        let y = b.get(i).copied().unwrap_or(U4::MIN);
+        (*x, borrow) = x.borrowing_sub(y, borrow);
+    }
+    borrow
+}
+
+/// `a *= m`, returning the limb carried out of the top.
+pub fn mul_limb(a: &mut [U4], m: U4) -> U4 {
+    let mut carry = 0;
+    for x in a {
+        let p = x.to_u8() * m.to_u8() + carry;
+        *x = U4::from_u8(p);
+        carry = p >> 4;
+    }
+    U4::from_u8(carry)
+}
+
+/// Shifts `a` left by `bits`, discarding bits shifted out of the top.
+pub fn shl_limbs(a: &mut [U4], bits: usize) {
+    let (limbs, bits) = (bits / 4, bits % 4);
+    let len = a.len();
+    if limbs >= len {
+        a.fill(U4::MIN);
+        return;
+    }
+    a.copy_within(..len - limbs, limbs);
+    a[..limbs].fill(U4::MIN);
+    if bits > 0 {
+        let mut carry = 0;
+        for x in &mut a[limbs..] {
+            let v = x.to_u8() << bits | carry;
+            *x = U4::from_u8(v);
+            carry = v >> 4;
+        }
+    }
+}
+
+/// Shifts `a` right by `bits`, discarding bits shifted out of the bottom.
+pub fn shr_limbs(a: &mut [U4], bits: usize) {
+    let (limbs, bits) = (bits / 4, bits % 4);
+    let len = a.len();
+    if limbs >= len {
+        a.fill(U4::MIN);
+        return;
+    }
+    a.copy_within(limbs.., 0);
+    a[len - limbs..].fill(U4::MIN);
+    if bits > 0 {
+        let mut carry = 0;
+        for x in a[..len - limbs].iter_mut().rev() {
+            let v = x.to_u8();
+            *x = U4::from_u8(v >> bits | carry);
+            carry = v << (4 - bits);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack;
+
+    const VALUES: [u16; 6] = [0, 1, 0x00ff, 0x1234, 0xbeef, 0xffff];
+
+    #[test]
+    fn add_and_sub() {
+        for x in VALUES {
+            for y in VALUES {
+                let mut a = pack::u16_to_le(x);
+                let carry = add_limbs(&mut a, &pack::u16_to_le(y));
+                let (sum, overflow) = x.overflowing_add(y);
+                assert_eq!((pack::u16_from_le(a), carry), (sum, overflow));
+
+                let mut a = pack::u16_to_le(x);
+                let borrow = sub_limbs(&mut a, &pack::u16_to_le(y));
+                let (diff, overflow) = x.overflowing_sub(y);
+                assert_eq!((pack::u16_from_le(a), borrow), (diff, overflow));
+            }
+        }
+        let mut a = [U4::MAX; 3];
+        assert!(add_limbs(&mut a, &[U4::n(1)]));
+        assert_eq!(a, [U4::MIN; 3]);
+        assert!(!sub_limbs(&mut a, &[]));
+    }
+
+    #[test]
+    fn carrying_and_borrowing() {
+        for a in (0..16).map(U4::n) {
+            for b in (0..16).map(U4::n) {
+                for c in [false, true] {
+                    let total = a.to_u8() + b.to_u8() + c as u8;
+                    assert_eq!(a.carrying_add(b, c), (U4::n(total), total > 15));
+                    let (d, borrow) = a.borrowing_sub(b, c);
+                    assert_eq!(borrow, a.to_u8() < b.to_u8() + c as u8);
+                    assert_eq!(d.carrying_add(b, c).0, a);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn multiply_and_shift() {
+        for x in VALUES {
+            let mut a = pack::u16_to_le(x);
+            let carry = mul_limb(&mut a, U4::n(11));
+            assert_eq!(pack::u16_from_le(a), x.wrapping_mul(11));
+            assert_eq!(carry.to_u8() as u32, (x as u32 * 11) >> 16);
+
+            for bits in 0..20 {
+                let mut a = pack::u16_to_le(x);
+                shl_limbs(&mut a, bits);
+                assert_eq!(
+                    pack::u16_from_le(a),
+                    x.checked_shl(bits as u32).unwrap_or(0)
+                );
+                let mut a = pack::u16_to_le(x);
+                shr_limbs(&mut a, bits);
+                assert_eq!(
+                    pack::u16_from_le(a),
+                    x.checked_shr(bits as u32).unwrap_or(0)
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "more limbs")]
+    fn longer_rhs() {
+        add_limbs(&mut [U4::MIN], &[U4::MIN; 2]);
+    }
+}
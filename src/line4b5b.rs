@@ -0,0 +1,164 @@
+use core::fmt::{Display, Formatter};
+
+use crate::U4;
+
+const DATA: [u8; 16] = [
+    0b11110, 0b01001, 0b10100, 0b10101, 0b01010, 0b01011, 0b01110, 0b01111, 0b10010, 0b10011,
+    0b10110, 0b10111, 0b11010, 0b11011, 0b11100, 0b11101,
+];
+
+/// Control symbols of the 4B/5B code (FDDI / 100BASE-X).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Quiet,
+    Idle,
+    Halt,
+    J,
+    K,
+    T,
+    R,
+    S,
+}
+
+impl Control {
+    pub fn code(self) -> u8 {
+        match self {
+            Control::Quiet => 0b00000,
+            Control::Idle => 0b11111,
+            Control::Halt => 0b00100,
+            Control::J => 0b11000,
+            Control::K => 0b10001,
+            Control::T => 0b01101,
+            Control::R => 0b00111,
+            Control::S => 0b11001,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbol {
+    Data(U4),
+    Control(Control),
+}
+
+impl Symbol {
+    pub fn code(self) -> u8 {
+        match self {
+            Symbol::Data(n) => encode(n),
+            Symbol::Control(c) => c.code(),
+        }
+    }
+}
+
+/// A 5-bit code group that is neither a data nor a control symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidSymbol(pub u8);
+
+impl Display for InvalidSymbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid 4B/5B code group {:#07b}", self.0)
+    }
+}
+
+impl core::error::Error for InvalidSymbol {}
+
+pub fn encode(n: U4) -> u8 {
+    DATA[n.to_u8() as usize]
+}
+
+pub fn decode(code: u8) -> Result<Symbol, InvalidSymbol> {
+    if let Some(n) = DATA.iter().position(|&c| c == code) {
+        return Ok(Symbol::Data(U4::from_u8(n as u8)));
+    }
+    let control = [
+        Control::Quiet,
+        Control::Idle,
+        Control::Halt,
+        Control::J,
+        Control::K,
+        Control::T,
+        Control::R,
+        Control::S,
+    ];
+    control
+        .into_iter()
+        .find(|c| c.code() == code)
+        .map(Symbol::Control)
+        .ok_or(InvalidSymbol(code))
+}
+
+/// Decodes a code group that must carry data, rejecting control symbols.
+pub fn decode_data(code: u8) -> Result<U4, InvalidSymbol> {
+    match decode(code)? {
+        Symbol::Data(n) => Ok(n),
+        Symbol::Control(_) => Err(InvalidSymbol(code)),
+    }
+}
+
+pub struct Encoder<I> {
+    iter: I,
+}
+
+impl<I: Iterator<Item = U4>> Iterator for Encoder<I> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(encode)
+    }
+}
+
+pub struct Decoder<I> {
+    iter: I,
+}
+
+impl<I: Iterator<Item = u8>> Iterator for Decoder<I> {
+    type Item = Result<Symbol, InvalidSymbol>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(decode)
+    }
+}
+
+pub fn encode_iter<I: IntoIterator<Item = U4>>(nibbles: I) -> Encoder<I::IntoIter> {
+    Encoder {
+        iter: nibbles.into_iter(),
+    }
+}
+
+pub fn decode_iter<I: IntoIterator<Item = u8>>(codes: I) -> Decoder<I::IntoIter> {
+    Decoder {
+        iter: codes.into_iter(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for i in 0..16 {
+            let n = U4::from_u8(i);
+            assert_eq!(decode(encode(n)), Ok(Symbol::Data(n)));
+        }
+    }
+
+    #[test]
+    fn control() {
+        assert_eq!(decode(0b11111), Ok(Symbol::Control(Control::Idle)));
+        assert_eq!(decode_data(0b11000), Err(InvalidSymbol(0b11000)));
+        assert_eq!(decode(0b00001), Err(InvalidSymbol(0b00001)));
+    }
+
+    #[test]
+    fn iter() {
+        let codes: Vec<u8> = encode_iter([U4::n(0), U4::n(0xf)]).collect();
+        assert_eq!(codes, vec![0b11110, 0b11101]);
+
+        let symbols: Vec<_> = decode_iter(codes).collect();
+        assert_eq!(
+            symbols,
+            vec![Ok(Symbol::Data(U4::n(0))), Ok(Symbol::Data(U4::n(0xf)))]
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! Combinational building blocks for logic-design exercises.
+//!
+//! ```
+//! use u4::logic::{Decoder4to16, PriorityEncoder16to4};
+//! use u4::U4;
+//!
+//! let lines = Decoder4to16::new().output(U4::n(9));
+//! assert_eq!(lines, 1 << 9);
+//! assert_eq!(PriorityEncoder16to4::new().output(lines), Some(U4::n(9)));
+//! ```
+
+use crate::boolfn::BoolFn4;
+use crate::U4;
+
+/// A 4-to-16 line decoder with an active-high enable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decoder4to16 {
+    pub enable: bool,
+}
+
+impl Decoder4to16 {
+    /// An enabled decoder.
+    pub fn new() -> Self {
+        Self { enable: true }
+    }
+
+    /// The one-hot output lines: bit `select` set, or none if disabled.
+    pub fn output(&self, select: U4) -> u16 {
+        (self.enable as u16) << select.to_u8()
+    }
+}
+
+impl Default for Decoder4to16 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A 16-to-4 priority encoder with an active-high enable. The highest
+/// active input line wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriorityEncoder16to4 {
+    pub enable: bool,
+}
+
+impl PriorityEncoder16to4 {
+    /// An enabled encoder.
+    pub fn new() -> Self {
+        Self { enable: true }
+    }
+
+    /// The index of the highest set bit of `lines`, or `None` (the valid
+    /// output low) if no line is set or the encoder is disabled.
+    pub fn output(&self, lines: u16) -> Option<U4> {
+        if !self.enable || lines == 0 {
+            return None;
+        }
+        Some(U4::from_u8(15 - lines.leading_zeros() as u8))
+    }
+}
+
+impl Default for PriorityEncoder16to4 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A 16-to-1 multiplexer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mux16<T> {
+    pub inputs: [T; 16],
+}
+
+impl<T: Copy> Mux16<T> {
+    pub fn new(inputs: [T; 16]) -> Self {
+        Self { inputs }
+    }
+
+    pub fn output(&self, select: U4) -> T {
+        self.inputs[select.to_u8() as usize]
+    }
+}
+
+impl Mux16<bool> {
+    /// A multiplexer over single-bit input lines, bit `i` of `lines` being
+    /// input `i`.
+    pub fn from_lines(lines: u16) -> Self {
+        Self::new(core::array::from_fn(|i| lines & (1 << i) != 0))
+    }
+}
+
+/// Any four-variable function is a multiplexer with its truth table wired
+/// to the inputs.
+impl From<BoolFn4> for Mux16<bool> {
+    fn from(f: BoolFn4) -> Self {
+        Self::from_lines(f.truth_table())
+    }
+}
+
+/// A signal and its depth in gates from the adder inputs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Signal {
+    pub value: bool,
+    pub depth: u32,
+}
+
+impl Signal {
+    fn input(value: bool) -> Self {
+        Self { value, depth: 0 }
+    }
+
+    fn gate(value: bool, inputs: [Signal; 2]) -> Self {
+        Self {
+            value,
+            depth: inputs[0].depth.max(inputs[1].depth) + 1,
+        }
+    }
+
+    fn xor(self, rhs: Self) -> Self {
+        Self::gate(self.value ^ rhs.value, [self, rhs])
+    }
+
+    fn and(self, rhs: Self) -> Self {
+        Self::gate(self.value & rhs.value, [self, rhs])
+    }
+
+    fn or(self, rhs: Self) -> Self {
+        Self::gate(self.value | rhs.value, [self, rhs])
+    }
+}
+
+/// The internal signals of one full adder stage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FullAdder {
+    pub a: Signal,
+    pub b: Signal,
+    pub carry_in: Signal,
+    /// `a ^ b`.
+    pub propagate: Signal,
+    /// `a & b`.
+    pub generate: Signal,
+    pub sum: Signal,
+    pub carry_out: Signal,
+}
+
+impl FullAdder {
+    /// Two XOR gates, two AND gates and one OR gate.
+    pub const GATES: GateCount = GateCount {
+        and: 2,
+        or: 1,
+        xor: 2,
+    };
+
+    pub fn eval(a: Signal, b: Signal, carry_in: Signal) -> Self {
+        let propagate = a.xor(b);
+        let generate = a.and(b);
+        Self {
+            a,
+            b,
+            carry_in,
+            propagate,
+            generate,
+            sum: propagate.xor(carry_in),
+            carry_out: generate.or(propagate.and(carry_in)),
+        }
+    }
+}
+
+/// Number of two-input gates in a circuit, by kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GateCount {
+    pub and: u32,
+    pub or: u32,
+    pub xor: u32,
+}
+
+impl GateCount {
+    pub fn total(&self) -> u32 {
+        self.and + self.or + self.xor
+    }
+}
+
+/// A 4-bit ripple-carry adder simulated gate by gate, each stage's carry
+/// out feeding the next stage's carry in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RippleCarryAdder {
+    /// One per bit, bit 0 first.
+    pub stages: [FullAdder; 4],
+}
+
+impl RippleCarryAdder {
+    pub const GATES: GateCount = GateCount {
+        and: 4 * FullAdder::GATES.and,
+        or: 4 * FullAdder::GATES.or,
+        xor: 4 * FullAdder::GATES.xor,
+    };
+
+    pub fn eval(a: U4, b: U4, carry_in: bool) -> Self {
+        let (a, b) = (a.to_bits_lsb0(), b.to_bits_lsb0());
+        let mut carry = Signal::input(carry_in);
+        let stages = core::array::from_fn(|i| {
+            let stage = FullAdder::eval(Signal::input(a[i]), Signal::input(b[i]), carry);
+            carry = stage.carry_out;
+            stage
+        });
+        Self { stages }
+    }
+
+    pub fn sum(&self) -> U4 {
+        U4::from_bits_lsb0(self.stages.map(|s| s.sum.value))
+    }
+
+    pub fn carry_out(&self) -> bool {
+        self.stages[3].carry_out.value
+    }
+
+    /// Gate delays along the longest path, through the carry chain.
+    pub fn depth(&self) -> u32 {
+        self.stages
+            .iter()
+            .flat_map(|s| [s.sum.depth, s.carry_out.depth])
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoder_encoder() {
+        let (dec, enc) = (Decoder4to16::new(), PriorityEncoder16to4::new());
+        for n in (0..16).map(U4::n) {
+            let lines = dec.output(n);
+            assert_eq!(lines.count_ones(), 1);
+            assert_eq!(enc.output(lines), Some(n));
+            // Lower lines do not change the encoding.
+            assert_eq!(enc.output(lines | (lines - 1)), Some(n));
+        }
+        assert_eq!(enc.output(0), None);
+        assert_eq!(Decoder4to16 { enable: false }.output(U4::n(3)), 0);
+        assert_eq!(PriorityEncoder16to4 { enable: false }.output(1), None);
+    }
+
+    #[test]
+    fn ripple_carry_adder() {
+        for a in (0..16).map(U4::n) {
+            for b in (0..16).map(U4::n) {
+                for c in [false, true] {
+                    let adder = RippleCarryAdder::eval(a, b, c);
+                    let expected = a.to_u8() + b.to_u8() + c as u8;
+                    assert_eq!(adder.sum().to_u8(), expected & 0xf);
+                    assert_eq!(adder.carry_out(), expected > 0xf);
+                }
+            }
+        }
+        let adder = RippleCarryAdder::eval(U4::n(0), U4::n(0), false);
+        assert_eq!(adder.stages.map(|s| s.carry_out.depth), [3, 5, 7, 9]);
+        assert_eq!(adder.stages.map(|s| s.sum.depth), [2, 4, 6, 8]);
+        assert_eq!(adder.depth(), 9);
+        assert_eq!(RippleCarryAdder::GATES.total(), 20);
+    }
+
+    #[test]
+    fn mux() {
+        let mux = Mux16::new(core::array::from_fn(|i| i * 10));
+        assert_eq!(mux.output(U4::n(7)), 70);
+
+        let f = BoolFn4::var(0) ^ BoolFn4::var(3);
+        let mux = Mux16::from(f);
+        for n in (0..16).map(U4::n) {
+            assert_eq!(mux.output(n), f.eval(n));
+        }
+    }
+}
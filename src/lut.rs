@@ -0,0 +1,107 @@
+//! Applying a nibble lookup table to packed byte buffers.
+
+use crate::sbox::SBox4;
+use crate::U4;
+
+/// A `[U4; 16]` lookup table expanded to act on whole bytes, mapping both
+/// nibbles of each byte with one load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NibbleLut {
+    bytes: [u8; 256],
+}
+
+impl NibbleLut {
+    pub fn new(table: &[U4; 16]) -> Self {
+        let t = table.map(|n| n.to_u8());
+        Self {
+            bytes: core::array::from_fn(|b| t[b >> 4] << 4 | t[b & 0xf]),
+        }
+    }
+
+    /// The table mapping `n` to `f(n)`.
+    pub fn from_fn(f: impl Fn(U4) -> U4) -> Self {
+        Self::new(&core::array::from_fn(|i| f(U4::from_u8(i as u8))))
+    }
+
+    /// Maps both nibbles of `b`.
+    pub fn map_byte(&self, b: u8) -> u8 {
+        self.bytes[b as usize]
+    }
+
+    pub fn byte_table(&self) -> &[u8; 256] {
+        &self.bytes
+    }
+
+    /// Maps every nibble of `buf` in place.
+    pub fn apply(&self, buf: &mut [u8]) {
+        for b in buf {
+            *b = self.bytes[*b as usize];
+        }
+    }
+
+    /// Writes the mapping of every nibble of `src` to `dst`.
+    ///
+    /// # Panics
+    ///
+    /// If `src` and `dst` have different lengths.
+    pub fn apply_to(&self, src: &[u8], dst: &mut [u8]) {
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "source and destination lengths differ"
+        );
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d = self.bytes[s as usize];
+        }
+    }
+}
+
+impl From<&SBox4> for NibbleLut {
+    fn from(sbox: &SBox4) -> Self {
+        Self::new(sbox.table())
+    }
+}
+
+impl From<SBox4> for NibbleLut {
+    fn from(sbox: SBox4) -> Self {
+        Self::from(&sbox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sbox_roundtrip() {
+        let s = SBox4::PRESENT;
+        let (lut, inv) = (NibbleLut::from(s), NibbleLut::from(s.inverse()));
+        for b in 0..=255_u8 {
+            let mapped = lut.map_byte(b);
+            assert_eq!(mapped >> 4, s.apply(U4::n(b >> 4)).to_u8());
+            assert_eq!(mapped & 0xf, s.apply(U4::n(b & 0xf)).to_u8());
+            assert_eq!(inv.map_byte(mapped), b);
+        }
+
+        let plain: Vec<u8> = (0..=255).collect();
+        let mut buf = plain.clone();
+        lut.apply(&mut buf);
+        let mut back = vec![0; buf.len()];
+        inv.apply_to(&buf, &mut back);
+        assert_eq!(back, plain);
+    }
+
+    #[test]
+    fn palette_remap() {
+        let swap = NibbleLut::from_fn(|n| U4::MAX - n);
+        let mut buf = [0x0f, 0x12];
+        swap.apply(&mut buf);
+        assert_eq!(buf, [0xf0, 0xed]);
+    }
+
+    #[test]
+    #[should_panic(expected = "lengths differ")]
+    fn length_mismatch() {
+        NibbleLut::from_fn(|n| n).apply_to(&[0; 2], &mut [0; 3]);
+    }
+}
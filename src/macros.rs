@@ -0,0 +1,195 @@
+use crate::U4;
+
+/// A [`U4`](crate::U4) constant from an integer, checked at compile time.
+///
+/// ```
+/// use u4::{u4, U4};
+///
+/// const OPCODE: U4 = u4!(0xA);
+/// assert_eq!(OPCODE.to_u8(), 10);
+/// ```
+///
+/// Values outside `0..=15` fail to compile:
+///
+/// ```compile_fail
+/// let _ = u4::u4!(16);
+/// ```
+#[macro_export]
+macro_rules! u4 {
+    ($n:expr) => {{
+        const N: $crate::U4 = {
+            let n = $n;
+            #[allow(unused_comparisons)]
+            let in_range = n >= 0 && n <= 15;
+            assert!(in_range, "u4! value out of range 0..=15");
+            $crate::U4::from_u8(n as u8)
+        };
+        N
+    }};
+}
+
+/// An [`I4`](crate::i4::I4) constant from an integer, checked at compile
+/// time.
+///
+/// ```
+/// use u4::{i4, i4::I4};
+///
+/// const BACK: I4 = i4!(-3);
+/// assert_eq!(BACK.to_i8(), -3);
+/// ```
+///
+/// Values outside `-8..=7` fail to compile:
+///
+/// ```compile_fail
+/// let _ = u4::i4!(8);
+/// ```
+#[macro_export]
+macro_rules! i4 {
+    ($n:expr) => {{
+        const N: $crate::i4::I4 = {
+            let n = $n;
+            let in_range = n >= -8 && n <= 7;
+            assert!(in_range, "i4! value out of range -8..=7");
+            match $crate::i4::I4::new(n as i8) {
+                Some(n) => n,
+                None => unreachable!(),
+            }
+        };
+        N
+    }};
+}
+
+/// A nibble array from a hex string literal, built at compile time. Each
+/// digit becomes one nibble; `packed` puts two digits per byte, high nibble
+/// first, and needs an even number of digits.
+///
+/// ```
+/// use u4::{nibbles, sbox::SBox4, U4};
+///
+/// const PRESENT: [U4; 16] = nibbles!("c56b90ad3ef84712");
+/// assert_eq!(SBox4::new(PRESENT).unwrap(), SBox4::PRESENT);
+/// assert_eq!(nibbles!(packed "deadbeef"), [0xde, 0xad, 0xbe, 0xef]);
+/// ```
+///
+/// Non-hex characters fail to compile:
+///
+/// ```compile_fail
+/// let _ = u4::nibbles!("12g4");
+/// ```
+#[macro_export]
+macro_rules! nibbles {
+    ($s:literal) => {{
+        const S: &str = $s;
+        const OUT: [$crate::U4; S.len()] = $crate::macros::hex_nibbles(S);
+        OUT
+    }};
+    (packed $s:literal) => {{
+        const S: &str = $s;
+        const OUT: [u8; S.len() / 2] = $crate::macros::packed_hex(S);
+        OUT
+    }};
+}
+
+/// Tabulates a function over all 16 nibbles at compile time, giving the
+/// `[U4; 16]` whose entry `i` is the body evaluated with the parameter bound
+/// to `U4::from_u8(i)`. The body must be a constant expression; for
+/// closures that are not, use [`U4::table`](crate::U4::table).
+///
+/// ```
+/// use u4::{u4_table, sbox::SBox4, U4};
+///
+/// const GRAY: [U4; 16] = u4_table!(|n| U4::from_u8(n.to_u8() ^ (n.to_u8() >> 1)));
+/// const ROTATE: [U4; 16] = u4_table!(|n| n.rotate_left(1));
+/// assert_eq!(GRAY[3], U4::n(2));
+/// assert_eq!(SBox4::new(ROTATE).unwrap().apply(U4::n(0b1000)), U4::n(0b0001));
+/// ```
+#[macro_export]
+macro_rules! u4_table {
+    (|$n:ident| $body:expr) => {{
+        const TABLE: [$crate::U4; 16] = {
+            let mut table = [$crate::U4::MIN; 16];
+            let mut i = 0;
+            while i < 16 {
+                let $n = $crate::U4::from_u8(i as u8);
+                table[i] = $body;
+                i += 1;
+            }
+            table
+        };
+        TABLE
+    }};
+}
+
+const fn hex_digit(c: u8) -> u8 {
+    match U4::from_hex_digit(c) {
+        Some(n) => n.to_u8(),
+        None => panic!("nibbles! expects only hex digits"),
+    }
+}
+
+#[doc(hidden)]
+pub const fn hex_nibbles<const N: usize>(s: &str) -> [U4; N] {
+    match U4::from_hex_array(s) {
+        Ok(nibbles) => nibbles,
+        Err(_) => panic!("nibbles! expects only hex digits"),
+    }
+}
+
+#[doc(hidden)]
+pub const fn packed_hex<const N: usize>(s: &str) -> [u8; N] {
+    let bytes = s.as_bytes();
+    assert!(
+        bytes.len() == 2 * N,
+        "packed nibbles! needs an even number of digits"
+    );
+    let mut out = [0; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = (hex_digit(bytes[2 * i]) << 4) | hex_digit(bytes[2 * i + 1]);
+        i += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::U4;
+
+    const BASE: u8 = 7;
+
+    #[test]
+    fn literals() {
+        assert_eq!(u4!(0), U4::MIN);
+        assert_eq!(u4!(0xF), U4::MAX);
+        assert_eq!(u4!(BASE + 1), U4::n(8));
+        const C: U4 = u4!(0b1010);
+        assert_eq!(C.to_u8(), 10);
+    }
+
+    #[test]
+    fn signed_literals() {
+        use crate::i4::I4;
+
+        assert_eq!(i4!(-8), I4::MIN);
+        assert_eq!(i4!(7), I4::MAX);
+        assert_eq!(i4!(BASE as i8 - 10).to_i8(), -3);
+        const C: I4 = i4!(-0b11);
+        assert_eq!(C.cast_unsigned(), U4::n(0xd));
+    }
+
+    #[test]
+    fn tables() {
+        const NOT: [U4; 16] = u4_table!(|n| U4::from_u8(!n.to_u8()));
+        for n in (0..16).map(U4::n) {
+            assert_eq!(NOT[n.to_u8() as usize], !n);
+        }
+        assert_eq!(U4::table(|n| !n), NOT);
+    }
+
+    #[test]
+    fn hex_arrays() {
+        assert_eq!(nibbles!("0aF"), [U4::n(0), U4::n(10), U4::n(15)]);
+        assert_eq!(nibbles!(""), [U4::MIN; 0]);
+        assert_eq!(nibbles!(packed "0aF1"), [0x0a, 0xf1]);
+    }
+}
@@ -0,0 +1,98 @@
+//! MIDI status bytes: a message-type nibble followed by a channel nibble.
+
+use crate::U4;
+
+/// The high nibble of a status byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageType {
+    NoteOff,
+    NoteOn,
+    PolyPressure,
+    ControlChange,
+    ProgramChange,
+    ChannelPressure,
+    PitchBend,
+    /// System messages. The low nibble selects the message instead of a
+    /// channel.
+    System,
+}
+
+impl MessageType {
+    pub fn from_nibble(n: U4) -> Option<Self> {
+        match n.to_u8() {
+            0x8 => Some(Self::NoteOff),
+            0x9 => Some(Self::NoteOn),
+            0xa => Some(Self::PolyPressure),
+            0xb => Some(Self::ControlChange),
+            0xc => Some(Self::ProgramChange),
+            0xd => Some(Self::ChannelPressure),
+            0xe => Some(Self::PitchBend),
+            0xf => Some(Self::System),
+            _ => None,
+        }
+    }
+
+    pub fn to_nibble(self) -> U4 {
+        U4::from_u8(match self {
+            Self::NoteOff => 0x8,
+            Self::NoteOn => 0x9,
+            Self::PolyPressure => 0xa,
+            Self::ControlChange => 0xb,
+            Self::ProgramChange => 0xc,
+            Self::ChannelPressure => 0xd,
+            Self::PitchBend => 0xe,
+            Self::System => 0xf,
+        })
+    }
+
+    /// Number of data bytes following the status byte, `None` for system
+    /// messages whose length depends on the low nibble.
+    pub fn data_len(self) -> Option<usize> {
+        match self {
+            Self::ProgramChange | Self::ChannelPressure => Some(1),
+            Self::System => None,
+            _ => Some(2),
+        }
+    }
+}
+
+/// A MIDI channel, `0..=15` on the wire (shown as 1–16 to users).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Channel(pub U4);
+
+/// Splits a status byte, `None` for data bytes (high bit clear).
+pub fn split_status(status: u8) -> Option<(MessageType, Channel)> {
+    let kind = MessageType::from_nibble(U4::from_u8(status >> 4))?;
+    Some((kind, Channel(U4::from_u8(status))))
+}
+
+pub fn join_status(kind: MessageType, channel: Channel) -> u8 {
+    (kind.to_nibble().to_u8() << 4) | channel.0.to_u8()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split() {
+        assert_eq!(
+            split_status(0x93),
+            Some((MessageType::NoteOn, Channel(U4::n(3))))
+        );
+        assert_eq!(
+            split_status(0xfe),
+            Some((MessageType::System, Channel(U4::n(0xe))))
+        );
+        assert_eq!(split_status(0x40), None);
+    }
+
+    #[test]
+    fn round_trip() {
+        for status in 0x80..=0xff {
+            let (kind, channel) = split_status(status).unwrap();
+            assert_eq!(join_status(kind, channel), status);
+        }
+        assert_eq!(MessageType::ProgramChange.data_len(), Some(1));
+    }
+}
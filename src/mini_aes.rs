@@ -0,0 +1,134 @@
+//! Building blocks of Mini-AES (R. C.-W. Phan, Cryptologia 2002).
+//!
+//! The 16-bit block `p0 p1 p2 p3` (`p0` most significant) is laid out column
+//! by column as the matrix `[[p0, p2], [p1, p3]]`, stored as `[p0, p1, p2, p3]`.
+
+use crate::{gf16, sbox::SBox4, U4};
+
+pub type State = [U4; 4];
+
+pub const SBOX: SBox4 = SBox4::from_u8_table([
+    0xe, 0x4, 0xd, 0x1, 0x2, 0xf, 0xb, 0x8, 0x3, 0xa, 0x6, 0xc, 0x5, 0x9, 0x0, 0x7,
+]);
+pub const SBOX_INV: SBox4 = SBox4::from_u8_table([
+    0xe, 0x3, 0x4, 0x8, 0x1, 0xc, 0xa, 0xf, 0x7, 0xd, 0x9, 0x6, 0xb, 0x2, 0x0, 0x5,
+]);
+
+/// The MixColumn matrix `[[3, 2], [2, 3]]`. It is its own inverse.
+pub const MIX: [[U4; 2]; 2] = [
+    [U4::from_u8(3), U4::from_u8(2)],
+    [U4::from_u8(2), U4::from_u8(3)],
+];
+
+pub fn state_from_u16(block: u16) -> State {
+    [12, 8, 4, 0].map(|shift| U4::from_u8((block >> shift) as u8))
+}
+
+pub fn state_to_u16(state: State) -> u16 {
+    state.iter().fold(0, |acc, n| (acc << 4) | n.to_u8() as u16)
+}
+
+pub fn nibble_sub(state: State, sbox: &SBox4) -> State {
+    state.map(|n| sbox.apply(n))
+}
+
+/// Rotates the second row, swapping `a1` and `a3`.
+pub fn shift_row(state: State) -> State {
+    [state[0], state[3], state[2], state[1]]
+}
+
+/// Multiplies a column by `matrix` over GF(16) with x^4 + x + 1.
+pub fn mix_column_with(column: [U4; 2], matrix: &[[U4; 2]; 2]) -> [U4; 2] {
+    matrix.map(|row| gf16::mul(row[0], column[0]) ^ gf16::mul(row[1], column[1]))
+}
+
+pub fn mix_column(state: State) -> State {
+    let [c0, c1] = mix_column_with([state[0], state[1]], &MIX);
+    let [c2, c3] = mix_column_with([state[2], state[3]], &MIX);
+    [c0, c1, c2, c3]
+}
+
+pub fn key_addition(state: State, key: State) -> State {
+    [0, 1, 2, 3].map(|i| state[i] ^ key[i])
+}
+
+/// Expands the key into the three round keys `K0`, `K1`, `K2`.
+pub fn key_schedule(key: State) -> [State; 3] {
+    let round = |prev: State, rcon: u8| {
+        let w0 = prev[0] ^ SBOX.apply(prev[3]) ^ U4::from_u8(rcon);
+        let w1 = prev[1] ^ w0;
+        let w2 = prev[2] ^ w1;
+        let w3 = prev[3] ^ w2;
+        [w0, w1, w2, w3]
+    };
+    let k1 = round(key, 1);
+    let k2 = round(k1, 2);
+    [key, k1, k2]
+}
+
+/// An expanded key. With the `zeroize` feature the round keys are wiped
+/// when it is dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundKeys(pub(crate) [State; 3]);
+
+impl RoundKeys {
+    pub fn new(key: u16) -> Self {
+        Self(key_schedule(state_from_u16(key)))
+    }
+
+    pub fn encrypt(&self, block: u16) -> u16 {
+        let [k0, k1, k2] = self.0;
+        let state = key_addition(state_from_u16(block), k0);
+        let state = mix_column(shift_row(nibble_sub(state, &SBOX)));
+        let state = key_addition(state, k1);
+        let state = shift_row(nibble_sub(state, &SBOX));
+        state_to_u16(key_addition(state, k2))
+    }
+
+    pub fn decrypt(&self, block: u16) -> u16 {
+        let [k0, k1, k2] = self.0;
+        let state = key_addition(state_from_u16(block), k2);
+        let state = nibble_sub(shift_row(state), &SBOX_INV);
+        let state = mix_column(key_addition(state, k1));
+        let state = nibble_sub(shift_row(state), &SBOX_INV);
+        state_to_u16(key_addition(state, k0))
+    }
+}
+
+pub fn encrypt(block: u16, key: u16) -> u16 {
+    RoundKeys::new(key).encrypt(block)
+}
+
+pub fn decrypt(block: u16, key: u16) -> u16 {
+    RoundKeys::new(key).decrypt(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector() {
+        // Worked example from Phan's paper.
+        assert_eq!(encrypt(0x9c63, 0xc3f0), 0x72c6);
+        assert_eq!(decrypt(0x72c6, 0xc3f0), 0x9c63);
+        let keys = RoundKeys::new(0xc3f0);
+        assert_eq!(keys.decrypt(keys.encrypt(0x1234)), 0x1234);
+    }
+
+    #[test]
+    fn components() {
+        assert_eq!(SBOX.inverse(), SBOX_INV);
+        let state = state_from_u16(0x1234);
+        assert_eq!(state_to_u16(state), 0x1234);
+        assert_eq!(mix_column(mix_column(state)), state);
+        assert_eq!(state_to_u16(shift_row(state)), 0x1432);
+    }
+
+    #[test]
+    fn key_schedule_vector() {
+        let [_, k1, k2] = key_schedule(state_from_u16(0xc3f0));
+        assert_eq!(state_to_u16(k1), 0x30ff);
+        assert_eq!(state_to_u16(k2), 0x6696);
+    }
+}
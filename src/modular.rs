@@ -0,0 +1,111 @@
+use crate::U4;
+
+fn check_modulus(modulus: u8) {
+    assert!(
+        (1..=16).contains(&modulus),
+        "modulus must be in 1..=16, got {modulus}"
+    );
+}
+
+impl U4 {
+    /// `self * rhs mod 16`.
+    pub fn mul_mod(self, rhs: Self) -> Self {
+        Self::from_u8(self.to_u8().wrapping_mul(rhs.to_u8()))
+    }
+
+    /// `self ^ exp mod 16`.
+    pub fn pow_mod(self, exp: u32) -> Self {
+        self.pow_mod_n(exp, 16)
+    }
+
+    /// Multiplicative inverse mod 16, which exists only for odd values.
+    pub fn inverse_mod16(self) -> Option<Self> {
+        self.inverse_mod_n(16)
+    }
+
+    /// `self + rhs mod modulus`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is not in `1..=16`.
+    pub fn add_mod_n(self, rhs: Self, modulus: u8) -> Self {
+        check_modulus(modulus);
+        Self::from_u8((self.to_u8() + rhs.to_u8()) % modulus)
+    }
+
+    /// `self * rhs mod modulus`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is not in `1..=16`.
+    pub fn mul_mod_n(self, rhs: Self, modulus: u8) -> Self {
+        check_modulus(modulus);
+        Self::from_u8((self.to_u8() * rhs.to_u8()) % modulus)
+    }
+
+    /// `self ^ exp mod modulus` by square-and-multiply.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is not in `1..=16`.
+    pub fn pow_mod_n(self, mut exp: u32, modulus: u8) -> Self {
+        check_modulus(modulus);
+        let mut base = self.to_u8() % modulus;
+        let mut acc = 1 % modulus;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc * base % modulus;
+            }
+            base = base * base % modulus;
+            exp >>= 1;
+        }
+        Self::from_u8(acc)
+    }
+
+    /// Multiplicative inverse mod `modulus`, `None` unless `self` is coprime
+    /// to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is not in `1..=16`.
+    pub fn inverse_mod_n(self, modulus: u8) -> Option<Self> {
+        check_modulus(modulus);
+        let a = self.to_u8() % modulus;
+        (0..modulus)
+            .find(|&x| (a * x) % modulus == 1 % modulus)
+            .map(Self::from_u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::U4;
+
+    #[test]
+    fn mod16() {
+        assert_eq!(U4::n(7).mul_mod(U4::n(5)), U4::n(3));
+        assert_eq!(U4::n(3).pow_mod(3), U4::n(11));
+        assert_eq!(U4::n(3).pow_mod(0), U4::n(1));
+        assert_eq!(U4::n(4).inverse_mod16(), None);
+        for i in (1..16).step_by(2) {
+            let a = U4::n(i);
+            assert_eq!(a.mul_mod(a.inverse_mod16().unwrap()), U4::n(1));
+        }
+    }
+
+    #[test]
+    fn small_moduli() {
+        assert_eq!(U4::n(9).add_mod_n(U4::n(9), 10), U4::n(8));
+        assert_eq!(U4::n(4).mul_mod_n(U4::n(5), 7), U4::n(6));
+        assert_eq!(U4::n(2).pow_mod_n(10, 11), U4::n(1));
+        assert_eq!(U4::n(3).inverse_mod_n(7), Some(U4::n(5)));
+        assert_eq!(U4::n(6).inverse_mod_n(9), None);
+        assert_eq!(U4::n(6).pow_mod_n(0, 1), U4::n(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_modulus() {
+        U4::n(1).mul_mod_n(U4::n(1), 0);
+    }
+}
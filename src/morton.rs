@@ -0,0 +1,121 @@
+//! Morton (Z-order) encoding of nibble pairs.
+//!
+//! Bit `i` of `x` lands in bit `2i` of the code and bit `i` of `y` in bit
+//! `2i + 1`, so codes enumerate a 16×16 grid in Z order.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::U4;
+
+fn spread(n: u8) -> u8 {
+    let n = (n | (n << 2)) & 0b0011_0011;
+    (n | (n << 1)) & 0b0101_0101
+}
+
+fn compact(n: u8) -> u8 {
+    let n = n & 0b0101_0101;
+    let n = (n | (n >> 1)) & 0b0011_0011;
+    (n | (n >> 2)) & 0b0000_1111
+}
+
+pub fn interleave(x: U4, y: U4) -> u8 {
+    spread(x.to_u8()) | (spread(y.to_u8()) << 1)
+}
+
+pub fn deinterleave(code: u8) -> (U4, U4) {
+    (U4::from_u8(compact(code)), U4::from_u8(compact(code >> 1)))
+}
+
+/// Interleaves `xs` and `ys` pairwise into `out`.
+///
+/// # Panics
+///
+/// Panics if the three slices differ in length.
+pub fn interleave_into(xs: &[U4], ys: &[U4], out: &mut [u8]) {
+    assert!(
+        xs.len() == ys.len() && xs.len() == out.len(),
+        "interleave_into requires slices of equal length"
+    );
+    for ((&x, &y), code) in xs.iter().zip(ys).zip(out) {
+        *code = interleave(x, y);
+    }
+}
+
+/// Splits `codes` back into their coordinates.
+///
+/// # Panics
+///
+/// Panics if the three slices differ in length.
+pub fn deinterleave_into(codes: &[u8], xs: &mut [U4], ys: &mut [U4]) {
+    assert!(
+        codes.len() == xs.len() && codes.len() == ys.len(),
+        "deinterleave_into requires slices of equal length"
+    );
+    for ((&code, x), y) in codes.iter().zip(xs).zip(ys) {
+        (*x, *y) = deinterleave(code);
+    }
+}
+
+/// Interleaves `xs` and `ys` pairwise into a new vector.
+///
+/// # Panics
+///
+/// Panics if `xs` and `ys` differ in length.
+#[cfg(feature = "alloc")]
+pub fn interleave_all(xs: &[U4], ys: &[U4]) -> Vec<u8> {
+    assert_eq!(
+        xs.len(),
+        ys.len(),
+        "interleave_all requires slices of equal length"
+    );
+    xs.iter().zip(ys).map(|(&x, &y)| interleave(x, y)).collect()
+}
+
+#[cfg(feature = "alloc")]
+pub fn deinterleave_all(codes: &[u8]) -> (Vec<U4>, Vec<U4>) {
+    codes.iter().map(|&code| deinterleave(code)).unzip()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interleave_bits() {
+        assert_eq!(interleave(U4::n(0b1111), U4::n(0)), 0b0101_0101);
+        assert_eq!(interleave(U4::n(0), U4::n(0b1111)), 0b1010_1010);
+        assert_eq!(interleave(U4::n(0b0011), U4::n(0b0001)), 0b0000_0111);
+    }
+
+    #[test]
+    fn round_trip() {
+        for code in 0..=255 {
+            let (x, y) = deinterleave(code);
+            assert_eq!(interleave(x, y), code);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn bulk() {
+        let xs = [U4::n(1), U4::n(2), U4::n(15)];
+        let ys = [U4::n(3), U4::n(0), U4::n(15)];
+        let codes = interleave_all(&xs, &ys);
+        assert_eq!(codes, vec![0b0000_1011, 0b0000_0100, 0xff]);
+        assert_eq!(deinterleave_all(&codes), (xs.to_vec(), ys.to_vec()));
+
+        let mut out = [0; 3];
+        interleave_into(&xs, &ys, &mut out);
+        let (mut dx, mut dy) = ([U4::MIN; 3], [U4::MIN; 3]);
+        deinterleave_into(&out, &mut dx, &mut dy);
+        assert_eq!((dx, dy), (xs, ys));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic]
+    fn interleave_all_mismatch() {
+        interleave_all(&[U4::MIN; 2], &[U4::MIN; 3]);
+    }
+}
@@ -0,0 +1,195 @@
+//! Packed saturating 4-bit counters, also usable as HyperLogLog-style
+//! max registers.
+
+use alloc::vec::Vec;
+
+use crate::U4;
+
+/// An array of counters that saturate at 0 and 15, two per byte.
+///
+/// ```
+/// use u4::{nibble_counters::NibbleCounters, U4};
+///
+/// let mut hits = NibbleCounters::new(3);
+/// for _ in 0..20 {
+///     hits.increment(1);
+/// }
+/// hits.halve();
+/// assert_eq!(hits.get(1), U4::n(7));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NibbleCounters {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl NibbleCounters {
+    /// `len` counters at zero.
+    pub fn new(len: usize) -> Self {
+        Self {
+            bytes: alloc::vec![0; len.div_ceil(2)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The packed counters, counter `2 * i` in the high nibble of byte `i`.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn shift(index: usize) -> u32 {
+        if index.is_multiple_of(2) {
+            4
+        } else {
+            0
+        }
+    }
+
+    fn check(&self, index: usize) {
+        assert!(index < self.len, "counter index out of bounds");
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds, as do the other per-counter
+    /// methods.
+    pub fn get(&self, index: usize) -> U4 {
+        self.check(index);
+        U4::from_u8(self.bytes[index / 2] >> Self::shift(index))
+    }
+
+    pub fn set(&mut self, index: usize, value: U4) {
+        self.check(index);
+        let shift = Self::shift(index);
+        let byte = &mut self.bytes[index / 2];
+        *byte = (*byte & !(0xf << shift)) | (value.to_u8() << shift);
+    }
+
+    /// Adds one unless the counter is at 15, returning whether it changed.
+    pub fn increment(&mut self, index: usize) -> bool {
+        let c = self.get(index);
+        let changed = c != U4::MAX;
+        if changed {
+            self.set(index, c + U4::from_u8(1));
+        }
+        changed
+    }
+
+    /// Subtracts one unless the counter is at 0, returning whether it
+    /// changed.
+    pub fn decrement(&mut self, index: usize) -> bool {
+        let c = self.get(index);
+        let changed = c != U4::MIN;
+        if changed {
+            self.set(index, c - U4::from_u8(1));
+        }
+        changed
+    }
+
+    /// Raises the counter to `value` if that is larger, returning whether
+    /// it changed, as a HyperLogLog register update does.
+    pub fn update_max(&mut self, index: usize, value: U4) -> bool {
+        let changed = value > self.get(index);
+        if changed {
+            self.set(index, value);
+        }
+        changed
+    }
+
+    /// Sets each counter to the larger of itself and the one at the same
+    /// index in `other`, merging two sketches.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    pub fn merge_max(&mut self, other: &Self) {
+        assert_eq!(
+            self.len, other.len,
+            "merging counter arrays of different lengths"
+        );
+        for (a, &b) in self.bytes.iter_mut().zip(&other.bytes) {
+            let hi = (*a & 0xf0).max(b & 0xf0);
+            let lo = (*a & 0x0f).max(b & 0x0f);
+            *a = hi | lo;
+        }
+    }
+
+    /// Halves every counter, rounding down, to age old counts.
+    pub fn halve(&mut self) {
+        for b in &mut self.bytes {
+            *b = (*b >> 1) & 0x77;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bytes.fill(0);
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = U4> + ExactSizeIterator + '_ {
+        (0..self.len).map(|i| self.get(i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saturation() {
+        let mut c = NibbleCounters::new(3);
+        assert!(!c.decrement(0));
+        for _ in 0..15 {
+            assert!(c.increment(2));
+        }
+        assert!(!c.increment(2));
+        assert!(c.decrement(2));
+        assert_eq!(c.iter().map(|n| n.to_u8()).collect::<Vec<_>>(), [0, 0, 14]);
+        assert_eq!(c.as_bytes(), [0x00, 0xe0]);
+    }
+
+    #[test]
+    fn halve() {
+        let mut c = NibbleCounters::new(4);
+        for (i, v) in [15, 1, 8, 7].into_iter().enumerate() {
+            c.set(i, U4::n(v));
+        }
+        c.halve();
+        assert_eq!(
+            c.iter().map(|n| n.to_u8()).collect::<Vec<_>>(),
+            [7, 0, 4, 3]
+        );
+        c.clear();
+        assert!(c.iter().all(|n| n == U4::MIN));
+    }
+
+    #[test]
+    fn max_registers() {
+        let mut a = NibbleCounters::new(5);
+        assert!(a.update_max(0, U4::n(3)));
+        assert!(!a.update_max(0, U4::n(2)));
+        a.set(3, U4::n(9));
+        let mut b = NibbleCounters::new(5);
+        b.set(0, U4::n(1));
+        b.set(1, U4::n(12));
+        b.set(4, U4::MAX);
+        a.merge_max(&b);
+        assert_eq!(
+            a.iter().map(|n| n.to_u8()).collect::<Vec<_>>(),
+            [3, 12, 0, 9, 15]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn padding_is_out_of_bounds() {
+        NibbleCounters::new(3).get(3);
+    }
+}
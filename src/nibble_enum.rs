@@ -0,0 +1,190 @@
+//! Fieldless enums with one variant per nibble value.
+//!
+//! ```
+//! use u4::{nibble_enum, U4};
+//!
+//! nibble_enum! {
+//!     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+//!     pub enum Command {
+//!         Nop = 0x0,
+//!         Read = 0x1,
+//!         Write = 0x2,
+//!         Reset = 0xf,
+//!     }
+//! }
+//!
+//! assert_eq!(U4::from(Command::Reset), U4::MAX);
+//! assert_eq!(Command::try_from(U4::n(2)), Ok(Command::Write));
+//! assert!(Command::try_from(U4::n(3)).is_err());
+//! ```
+
+use core::fmt::{Display, Formatter};
+
+/// The nibble matches no variant of the enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownVariant(pub u8);
+
+impl Display for UnknownVariant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "no variant for nibble {:#x}", self.0)
+    }
+}
+
+impl core::error::Error for UnknownVariant {}
+
+/// Declares a `#[repr(u8)]` enum whose variants are assigned distinct
+/// nibble values, with `From<Enum> for U4` and `TryFrom<U4> for Enum`.
+///
+/// Prefix `enum` with `exhaustive` to require all sixteen values to be
+/// covered; the enum then implements `From<U4>` instead, so conversion
+/// cannot fail. Values above 15, duplicates and, for exhaustive enums,
+/// gaps are compile errors.
+///
+/// ```
+/// use u4::{nibble_enum, U4};
+///
+/// nibble_enum! {
+///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///     pub exhaustive enum Digit {
+///         D0 = 0, D1 = 1, D2 = 2, D3 = 3, D4 = 4, D5 = 5, D6 = 6, D7 = 7,
+///         D8 = 8, D9 = 9, A = 10, B = 11, C = 12, D = 13, E = 14, F = 15,
+///     }
+/// }
+///
+/// assert_eq!(Digit::from(U4::n(11)), Digit::B);
+/// ```
+///
+/// ```compile_fail
+/// u4::nibble_enum! {
+///     pub exhaustive enum Sparse {
+///         A = 0,
+///         B = 1,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! nibble_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis exhaustive enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal),+ $(,)?
+        }
+    ) => {
+        $crate::nibble_enum! {
+            @define $(#[$meta])* $vis $name true { $($(#[$variant_meta])* $variant = $value),+ }
+        }
+
+        impl ::core::convert::From<$crate::U4> for $name {
+            fn from(n: $crate::U4) -> Self {
+                match Self::from_u4(n) {
+                    Some(v) => v,
+                    None => unreachable!(),
+                }
+            }
+        }
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal),+ $(,)?
+        }
+    ) => {
+        $crate::nibble_enum! {
+            @define $(#[$meta])* $vis $name false { $($(#[$variant_meta])* $variant = $value),+ }
+        }
+
+        impl ::core::convert::TryFrom<$crate::U4> for $name {
+            type Error = $crate::nibble_enum::UnknownVariant;
+
+            fn try_from(n: $crate::U4) -> Result<Self, Self::Error> {
+                Self::from_u4(n).ok_or($crate::nibble_enum::UnknownVariant(n.to_u8()))
+            }
+        }
+    };
+    (
+        @define $(#[$meta:meta])* $vis:vis $name:ident $exhaustive:literal {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal),+
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr(u8)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant = $value,)+
+        }
+
+        const _: () = {
+            let mut used: u16 = 0;
+            $(
+                assert!(
+                    $value <= 15,
+                    concat!("`", stringify!($variant), "` is not a nibble value")
+                );
+                assert!(
+                    used & (1 << $value) == 0,
+                    concat!("`", stringify!($variant), "` repeats a nibble value")
+                );
+                used |= 1 << $value;
+            )+
+            assert!(
+                !$exhaustive || used == 0xffff,
+                concat!("`", stringify!($name), "` does not cover all 16 nibble values")
+            );
+        };
+
+        impl $name {
+            pub const fn to_u4(self) -> $crate::U4 {
+                $crate::U4::from_u8(self as u8)
+            }
+
+            pub const fn from_u4(n: $crate::U4) -> Option<Self> {
+                match n.to_u8() {
+                    $($value => Some(Self::$variant),)+
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+        }
+
+        impl ::core::convert::From<$name> for $crate::U4 {
+            fn from(v: $name) -> Self {
+                v.to_u4()
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::U4;
+
+    nibble_enum! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Sparse {
+            Low = 1,
+            High = 0xe,
+        }
+    }
+
+    nibble_enum! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        exhaustive enum Full {
+            A = 0, B = 1, C = 2, D = 3, E = 4, F = 5, G = 6, H = 7,
+            I = 8, J = 9, K = 10, L = 11, M = 12, N = 13, O = 14, P = 15,
+        }
+    }
+
+    #[test]
+    fn sparse() {
+        assert_eq!(U4::from(Sparse::High), U4::n(0xe));
+        assert_eq!(Sparse::try_from(U4::n(1)), Ok(Sparse::Low));
+        let err = Sparse::try_from(U4::n(2)).unwrap_err();
+        assert_eq!(err.to_string(), "no variant for nibble 0x2");
+    }
+
+    #[test]
+    fn exhaustive_round_trip() {
+        for n in (0..16).map(U4::n) {
+            assert_eq!(U4::from(Full::from(n)), n);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! Packed nibble sequences for trie keys, and the hex-prefix (compact)
+//! encoding Ethereum's Merkle-Patricia trie uses to store them.
+
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display, Formatter};
+
+use crate::U4;
+
+/// A sequence of nibbles packed two per byte, high nibble first, of any
+/// length.
+///
+/// Paths order lexicographically by nibble, with a path sorting before its
+/// extensions.
+///
+/// ```
+/// use u4::{nibble_path::NibblePath, U4};
+///
+/// let a: NibblePath = "a1b2c".parse().unwrap();
+/// let b: NibblePath = "a1f".parse().unwrap();
+/// assert_eq!(a.common_prefix_len(&b), 2);
+///
+/// let (head, tail) = a.split_at(2);
+/// assert!(a.starts_with(&head));
+/// assert_eq!(tail.to_string(), "b2c");
+/// ```
+// Field order matters for the derived ordering, and the unused half of an
+// odd-length path's last byte is kept zero so derived equality holds.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NibblePath {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl NibblePath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The nibbles of `bytes`, high nibble first.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            bytes: bytes.to_vec(),
+            len: bytes.len() * 2,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The packed bytes. For odd lengths the low nibble of the last byte is
+    /// zero.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn get(&self, index: usize) -> Option<U4> {
+        if index >= self.len {
+            return None;
+        }
+        let byte = self.bytes[index / 2];
+        Some(U4::from_u8(if index.is_multiple_of(2) {
+            byte >> 4
+        } else {
+            byte
+        }))
+    }
+
+    pub fn push(&mut self, n: U4) {
+        if self.len.is_multiple_of(2) {
+            self.bytes.push(n.to_u8() << 4);
+        } else {
+            *self.bytes.last_mut().unwrap() |= n.to_u8();
+        }
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<U4> {
+        let last = self.get(self.len.checked_sub(1)?)?;
+        self.len -= 1;
+        if self.len.is_multiple_of(2) {
+            self.bytes.pop();
+        } else {
+            *self.bytes.last_mut().unwrap() &= 0xf0;
+        }
+        Some(last)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = U4> + ExactSizeIterator + '_ {
+        (0..self.len).map(|i| self.get(i).unwrap())
+    }
+
+    /// The number of leading nibbles the two paths share.
+    pub fn common_prefix_len(&self, other: &Self) -> usize {
+        let bytes = self
+            .bytes
+            .iter()
+            .zip(&other.bytes)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let mut n = bytes * 2;
+        if let (Some(a), Some(b)) = (self.bytes.get(bytes), other.bytes.get(bytes)) {
+            if a >> 4 == b >> 4 {
+                n += 1;
+            }
+        }
+        n.min(self.len).min(other.len)
+    }
+
+    pub fn starts_with(&self, prefix: &Self) -> bool {
+        prefix.len <= self.len && self.common_prefix_len(prefix) == prefix.len
+    }
+
+    /// Splits into the first `mid` nibbles and the rest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > self.len()`.
+    pub fn split_at(&self, mid: usize) -> (Self, Self) {
+        assert!(mid <= self.len, "split index out of bounds");
+        (
+            self.iter().take(mid).collect(),
+            self.iter().skip(mid).collect(),
+        )
+    }
+}
+
+impl FromIterator<U4> for NibblePath {
+    fn from_iter<I: IntoIterator<Item = U4>>(iter: I) -> Self {
+        let mut path = Self::new();
+        path.extend(iter);
+        path
+    }
+}
+
+impl Extend<U4> for NibblePath {
+    fn extend<I: IntoIterator<Item = U4>>(&mut self, iter: I) {
+        for n in iter {
+            self.push(n);
+        }
+    }
+}
+
+impl From<&[U4]> for NibblePath {
+    fn from(nibbles: &[U4]) -> Self {
+        nibbles.iter().copied().collect()
+    }
+}
+
+impl core::str::FromStr for NibblePath {
+    type Err = crate::ParseHexError;
+
+    /// One hex digit per nibble.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.bytes()
+            .enumerate()
+            .map(|(index, byte)| {
+                U4::from_hex_digit(byte).ok_or(crate::ParseHexError::InvalidDigit { index, byte })
+            })
+            .collect()
+    }
+}
+
+impl Display for NibblePath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for n in self.iter() {
+            write!(f, "{:x}", n.to_u8())?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for NibblePath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "NibblePath(\"{self}\")")
+    }
+}
+
+/// The input is not a valid hex-prefix encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexPrefixError {
+    Empty,
+    /// The flag nibble is above 3.
+    InvalidFlag(u8),
+    /// An even-length path's padding nibble is not zero.
+    NonZeroPadding(u8),
+}
+
+impl Display for HexPrefixError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty hex-prefix encoding"),
+            Self::InvalidFlag(n) => write!(f, "invalid hex-prefix flag {n:#x}"),
+            Self::NonZeroPadding(n) => write!(f, "non-zero hex-prefix padding {n:#x}"),
+        }
+    }
+}
+
+impl core::error::Error for HexPrefixError {}
+
+/// Hex-prefix encodes `path`: a flag nibble (2 for leaves, plus 1 for odd
+/// lengths), a zero padding nibble for even lengths, then the path.
+///
+/// ```
+/// use u4::nibble_path::{hp_decode, hp_encode, NibblePath};
+///
+/// let path: NibblePath = "12345".parse().unwrap();
+/// assert_eq!(hp_encode(&path, false), [0x11, 0x23, 0x45]);
+/// assert_eq!(hp_decode(&[0x20, 0x0f, 0x1c]), Ok(("0f1c".parse().unwrap(), true)));
+/// ```
+pub fn hp_encode(path: &NibblePath, is_leaf: bool) -> Vec<u8> {
+    let odd = path.len() % 2;
+    let flag = (is_leaf as u8) << 1 | odd as u8;
+    let mut out = Vec::with_capacity(path.len() / 2 + 1);
+    if odd == 1 {
+        out.push(flag << 4 | path.get(0).unwrap().to_u8());
+        out.extend(path.iter().skip(1).collect::<NibblePath>().as_bytes());
+    } else {
+        out.push(flag << 4);
+        out.extend(path.as_bytes());
+    }
+    out
+}
+
+/// Decodes a hex-prefix encoding into the path and its leaf flag.
+pub fn hp_decode(bytes: &[u8]) -> Result<(NibblePath, bool), HexPrefixError> {
+    let (&first, rest) = bytes.split_first().ok_or(HexPrefixError::Empty)?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(HexPrefixError::InvalidFlag(flag));
+    }
+    let mut path = NibblePath::new();
+    if flag & 1 == 1 {
+        path.push(U4::from_u8(first));
+    } else if first & 0xf != 0 {
+        return Err(HexPrefixError::NonZeroPadding(first & 0xf));
+    }
+    path.extend(
+        rest.iter()
+            .flat_map(|&b| [U4::from_u8(b >> 4), U4::from_u8(b)]),
+    );
+    Ok((path, flag & 2 == 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(s: &str) -> NibblePath {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn push_pop() {
+        let mut p = NibblePath::new();
+        p.extend([1, 2, 3].map(U4::n));
+        assert_eq!(p.as_bytes(), [0x12, 0x30]);
+        assert_eq!(p.pop(), Some(U4::n(3)));
+        assert_eq!(p.pop(), Some(U4::n(2)));
+        assert_eq!(p.as_bytes(), [0x10]);
+        assert_eq!(p, path("1"));
+        assert_eq!(NibblePath::from_bytes(&[0xab]), path("ab"));
+        assert_eq!(format!("{p:?}"), "NibblePath(\"1\")");
+        assert!("1g".parse::<NibblePath>().is_err());
+    }
+
+    #[test]
+    fn prefixes() {
+        assert_eq!(path("abcd").common_prefix_len(&path("abce")), 3);
+        assert_eq!(path("abc").common_prefix_len(&path("abcd")), 3);
+        assert_eq!(path("ab").common_prefix_len(&path("b")), 0);
+        assert!(path("abc").starts_with(&path("ab")));
+        assert!(path("abc").starts_with(&NibblePath::new()));
+        assert!(!path("ab").starts_with(&path("abc")));
+        // The padding nibble of "a" must not match the "0" of "a0".
+        assert!(!path("a").starts_with(&path("a0")));
+
+        let (head, tail) = path("12345").split_at(3);
+        assert_eq!((head, tail), (path("123"), path("45")));
+    }
+
+    #[test]
+    fn hex_prefix_round_trip() {
+        // Every path of up to four nibbles.
+        for len in 0..=4 {
+            for v in 0..16_u32.pow(len as u32) {
+                let p: NibblePath = (0..len)
+                    .rev()
+                    .map(|i| U4::from_u8((v >> (4 * i)) as u8))
+                    .collect();
+                for leaf in [false, true] {
+                    let encoded = hp_encode(&p, leaf);
+                    assert_eq!(encoded.len(), len / 2 + 1);
+                    assert_eq!(hp_decode(&encoded), Ok((p.clone(), leaf)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hex_prefix_vectors() {
+        // From the Ethereum yellow paper appendix C.
+        assert_eq!(hp_encode(&path("12345"), false), [0x11, 0x23, 0x45]);
+        assert_eq!(hp_encode(&path("012345"), false), [0x00, 0x01, 0x23, 0x45]);
+        assert_eq!(hp_encode(&path("0f1cb8"), true), [0x20, 0x0f, 0x1c, 0xb8]);
+        assert_eq!(hp_encode(&path("f1cb8"), true), [0x3f, 0x1c, 0xb8]);
+
+        assert_eq!(hp_decode(&[]), Err(HexPrefixError::Empty));
+        assert_eq!(hp_decode(&[0x40]), Err(HexPrefixError::InvalidFlag(4)));
+        assert_eq!(
+            hp_decode(&[0x21, 0x00]),
+            Err(HexPrefixError::NonZeroPadding(1))
+        );
+    }
+
+    #[test]
+    fn ordering() {
+        let mut paths = ["1", "0f", "10", "", "1f", "100"].map(path);
+        paths.sort();
+        assert_eq!(
+            paths.map(|p| p.to_string()),
+            ["", "0f", "1", "10", "100", "1f"]
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use num_traits::{
+    Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedRem, CheckedSub, FromPrimitive, Num,
+    NumCast, One, PrimInt, Saturating, SaturatingAdd, SaturatingMul, SaturatingSub, ToPrimitive,
+    Unsigned, WrappingAdd, WrappingMul, WrappingSub, Zero,
+};
+
+use core::{
+    fmt::{self, Display, Formatter},
+    num::{IntErrorKind, ParseIntError},
+};
+
+use crate::U4;
+
+/// Error from [`Num::from_str_radix`] for [`U4`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FromStrRadixError {
+    /// The string is not an integer in the radix.
+    Parse(ParseIntError),
+    /// The integer is above 15.
+    Overflow,
+}
+
+impl Display for FromStrRadixError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => e.fmt(f),
+            Self::Overflow => write!(f, "number too large to fit in a nibble"),
+        }
+    }
+}
+
+impl core::error::Error for FromStrRadixError {}
+
+impl Zero for U4 {
+    fn zero() -> Self {
+        Self::MIN
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::MIN
+    }
+}
+
+impl One for U4 {
+    fn one() -> Self {
+        Self::from_u8(1)
+    }
+}
+
+impl Num for U4 {
+    type FromStrRadixErr = FromStrRadixError;
+
+    /// Parses like `u8::from_str_radix`, rejecting values above 15.
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        match u8::from_str_radix(str, radix) {
+            Ok(n) if n <= 0xf => Ok(Self::from_u8(n)),
+            Ok(_) => Err(FromStrRadixError::Overflow),
+            Err(e) if *e.kind() == IntErrorKind::PosOverflow => Err(FromStrRadixError::Overflow),
+            Err(e) => Err(FromStrRadixError::Parse(e)),
+        }
+    }
+}
+
+impl Bounded for U4 {
+    fn min_value() -> Self {
+        Self::MIN
+    }
+
+    fn max_value() -> Self {
+        Self::MAX
+    }
+}
+
+impl Unsigned for U4 {}
+
+impl ToPrimitive for U4 {
+    fn to_i64(&self) -> Option<i64> {
+        Some(self.to_u8() as i64)
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        Some(self.to_u8() as u64)
+    }
+}
+
+impl FromPrimitive for U4 {
+    /// `None` outside `0..=15`.
+    fn from_i64(n: i64) -> Option<Self> {
+        u64::try_from(n).ok().and_then(Self::from_u64)
+    }
+
+    /// `None` above 15.
+    fn from_u64(n: u64) -> Option<Self> {
+        (n <= 0xf).then(|| Self::from_u8(n as u8))
+    }
+}
+
+impl NumCast for U4 {
+    fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+        n.to_u64().and_then(Self::from_u64)
+    }
+}
+
+macro_rules! impl_checked {
+    ($($trait:ident::$method:ident => $op:ident),*) => {
+        $(
+            impl $trait for U4 {
+                fn $method(&self, v: &Self) -> Option<Self> {
+                    self.to_u8()
+                        .$op(v.to_u8())
+                        .and_then(<Self as FromPrimitive>::from_u8)
+                }
+            }
+        )*
+    };
+}
+
+impl_checked!(
+    CheckedAdd::checked_add => checked_add,
+    CheckedSub::checked_sub => checked_sub,
+    CheckedMul::checked_mul => checked_mul,
+    CheckedDiv::checked_div => checked_div,
+    CheckedRem::checked_rem => checked_rem
+);
+
+impl WrappingAdd for U4 {
+    fn wrapping_add(&self, v: &Self) -> Self {
+        Self::from_u8(self.to_u8() + v.to_u8())
+    }
+}
+
+impl WrappingSub for U4 {
+    fn wrapping_sub(&self, v: &Self) -> Self {
+        U4::wrapping_sub(*self, *v)
+    }
+}
+
+impl WrappingMul for U4 {
+    fn wrapping_mul(&self, v: &Self) -> Self {
+        *self * *v
+    }
+}
+
+impl Saturating for U4 {
+    fn saturating_add(self, v: Self) -> Self {
+        Self::from_u8((self.to_u8() + v.to_u8()).min(0xf))
+    }
+
+    fn saturating_sub(self, v: Self) -> Self {
+        Self::from_u8(self.to_u8().saturating_sub(v.to_u8()))
+    }
+}
+
+impl SaturatingAdd for U4 {
+    fn saturating_add(&self, v: &Self) -> Self {
+        Self::from_u8((self.to_u8() + v.to_u8()).min(0xf))
+    }
+}
+
+impl SaturatingSub for U4 {
+    fn saturating_sub(&self, v: &Self) -> Self {
+        Self::from_u8(self.to_u8().saturating_sub(v.to_u8()))
+    }
+}
+
+impl SaturatingMul for U4 {
+    fn saturating_mul(&self, v: &Self) -> Self {
+        Self::from_u8((self.to_u8() * v.to_u8()).min(0xf))
+    }
+}
+
+/// A nibble has no byte order, so the byte-order methods are identities.
+impl PrimInt for U4 {
+    fn count_ones(self) -> u32 {
+        self.to_u8().count_ones()
+    }
+
+    fn count_zeros(self) -> u32 {
+        4 - self.count_ones()
+    }
+
+    fn leading_zeros(self) -> u32 {
+        self.to_u8().leading_zeros() - 4
+    }
+
+    fn trailing_zeros(self) -> u32 {
+        self.to_u8().trailing_zeros().min(4)
+    }
+
+    fn rotate_left(self, n: u32) -> Self {
+        U4::rotate_left(self, n)
+    }
+
+    fn rotate_right(self, n: u32) -> Self {
+        U4::rotate_right(self, n)
+    }
+
+    fn signed_shl(self, n: u32) -> Self {
+        self << n as usize
+    }
+
+    /// Arithmetic shift, copying bit 3 into the vacated bits.
+    fn signed_shr(self, n: u32) -> Self {
+        let signed = ((self.to_u8() << 4) as i8) >> n.min(7);
+        Self::from_u8((signed >> 4) as u8)
+    }
+
+    fn unsigned_shl(self, n: u32) -> Self {
+        self << n as usize
+    }
+
+    fn unsigned_shr(self, n: u32) -> Self {
+        self >> n as usize
+    }
+
+    fn swap_bytes(self) -> Self {
+        self
+    }
+
+    fn from_be(x: Self) -> Self {
+        x
+    }
+
+    fn from_le(x: Self) -> Self {
+        x
+    }
+
+    fn to_be(self) -> Self {
+        self
+    }
+
+    fn to_le(self) -> Self {
+        self
+    }
+
+    /// Wrapping exponentiation.
+    fn pow(self, mut exp: u32) -> Self {
+        let (mut base, mut acc) = (self, Self::from_u8(1));
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc * base;
+            }
+            base = base * base;
+            exp >>= 1;
+        }
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum<T: PrimInt>(xs: &[T]) -> Option<T> {
+        xs.iter().try_fold(T::zero(), |acc, x| acc.checked_add(x))
+    }
+
+    #[test]
+    fn generic() {
+        assert_eq!(sum(&[U4::n(3), U4::n(4)]), Some(U4::n(7)));
+        assert_eq!(sum(&[U4::n(9), U4::n(7)]), None);
+        assert_eq!(<U4 as Bounded>::max_value(), U4::MAX);
+        assert_eq!(<U4 as NumCast>::from(12_i32), Some(U4::n(12)));
+        assert_eq!(<U4 as NumCast>::from(16_u32), None);
+        assert_eq!(<U4 as NumCast>::from(-1_i8), None);
+        assert_eq!(U4::n(0).checked_sub(&U4::n(1)), None);
+        assert_eq!(U4::n(4).checked_div(&U4::n(0)), None);
+    }
+
+    #[test]
+    fn from_str_radix() {
+        assert_eq!(U4::from_str_radix("f", 16), Ok(U4::MAX));
+        assert_eq!(U4::from_str_radix("15", 10), Ok(U4::MAX));
+        assert_eq!(
+            U4::from_str_radix("20", 10),
+            Err(FromStrRadixError::Overflow)
+        );
+        assert!(matches!(
+            U4::from_str_radix("x", 10),
+            Err(FromStrRadixError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn saturating() {
+        assert_eq!(SaturatingAdd::saturating_add(&U4::n(9), &U4::n(9)), U4::MAX);
+        assert_eq!(SaturatingSub::saturating_sub(&U4::n(2), &U4::n(9)), U4::MIN);
+        assert_eq!(U4::n(5).saturating_mul(&U4::n(5)), U4::MAX);
+        assert_eq!(WrappingAdd::wrapping_add(&U4::n(9), &U4::n(9)), U4::n(2));
+        assert_eq!(WrappingSub::wrapping_sub(&U4::n(2), &U4::n(9)), U4::n(9));
+    }
+
+    #[test]
+    fn prim_int() {
+        assert_eq!(PrimInt::leading_zeros(U4::n(1)), 3);
+        assert_eq!(PrimInt::leading_zeros(U4::MIN), 4);
+        assert_eq!(PrimInt::trailing_zeros(U4::MIN), 4);
+        assert_eq!(PrimInt::trailing_zeros(U4::n(0b1000)), 3);
+        assert_eq!(U4::n(0b1000).signed_shr(2), U4::n(0b1110));
+        assert_eq!(U4::n(0b1000).unsigned_shr(2), U4::n(0b0010));
+        assert_eq!(PrimInt::pow(U4::n(3), 2), U4::n(9));
+        assert_eq!(PrimInt::pow(U4::n(3), 3), U4::n(27 % 16));
+        assert_eq!(PrimInt::pow(U4::n(5), 0), U4::n(1));
+        assert_eq!(PrimInt::pow(U4::n(3), u32::MAX), U4::n(11));
+        assert_eq!(PrimInt::pow(U4::n(2), u32::MAX), U4::MIN);
+    }
+}
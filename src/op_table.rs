@@ -0,0 +1,126 @@
+//! A dispatch table indexed by a 4-bit opcode.
+//!
+//! ```
+//! use u4::{op_table::OpTable, U4};
+//!
+//! struct Cpu {
+//!     acc: u8,
+//! }
+//!
+//! let table = OpTable::<fn(&mut Cpu, U4)>::new(|_, _| {})
+//!     .on(U4::n(0x1), |cpu, arg| cpu.acc += arg.to_u8())
+//!     .on(U4::n(0x2), |cpu, _| cpu.acc = 0);
+//!
+//! let mut cpu = Cpu { acc: 0 };
+//! for byte in [0x13_u8, 0x14, 0xf0] {
+//!     table[U4::n(byte >> 4)](&mut cpu, U4::n(byte & 0xf));
+//! }
+//! assert_eq!(cpu.acc, 7);
+//! ```
+
+use core::ops::Index;
+
+use crate::U4;
+
+/// One handler per opcode, every slot filled, so dispatch is a single
+/// indexed load. Use `fn` pointers or boxed closures for `H` to mix
+/// handlers of different types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpTable<H> {
+    handlers: [H; 16],
+    registered: u16,
+}
+
+impl<H: Clone> OpTable<H> {
+    /// A table with `default` in every slot.
+    pub fn new(default: H) -> Self {
+        Self {
+            handlers: core::array::from_fn(|_| default.clone()),
+            registered: 0,
+        }
+    }
+}
+
+impl<H> OpTable<H> {
+    /// A table from one handler per opcode, all counted as registered.
+    pub fn from_handlers(handlers: [H; 16]) -> Self {
+        Self {
+            handlers,
+            registered: u16::MAX,
+        }
+    }
+
+    /// Registers `handler` for `op`, replacing any earlier one.
+    pub fn on(mut self, op: U4, handler: H) -> Self {
+        self.set(op, handler);
+        self
+    }
+
+    /// Registers `handler` for `op`, returning the handler it replaces.
+    pub fn set(&mut self, op: U4, handler: H) -> H {
+        self.registered |= 1 << op.to_u8();
+        core::mem::replace(&mut self.handlers[op.to_u8() as usize], handler)
+    }
+
+    pub fn get(&self, op: U4) -> &H {
+        &self.handlers[op.to_u8() as usize]
+    }
+
+    /// Whether `op` has its own handler rather than the default.
+    pub fn is_registered(&self, op: U4) -> bool {
+        self.registered & (1 << op.to_u8()) != 0
+    }
+
+    /// Calls the handler for `op` with `args`.
+    pub fn dispatch<A, R>(&self, op: U4, args: A) -> R
+    where
+        H: Fn(A) -> R,
+    {
+        self.get(op)(args)
+    }
+}
+
+impl<H> Index<U4> for OpTable<H> {
+    type Output = H;
+
+    fn index(&self, op: U4) -> &H {
+        self.get(op)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registration() {
+        let mut table = OpTable::new(0).on(U4::n(3), 30).on(U4::n(3), 33);
+        assert_eq!(table[U4::n(3)], 33);
+        assert_eq!(*table.get(U4::n(4)), 0);
+        assert!(table.is_registered(U4::n(3)));
+        assert!(!table.is_registered(U4::n(4)));
+        assert_eq!(table.set(U4::n(4), 44), 0);
+        assert!(table.is_registered(U4::n(4)));
+
+        let all = OpTable::from_handlers(core::array::from_fn(|i| i));
+        assert!((0..16).map(U4::n).all(|n| all.is_registered(n)));
+    }
+
+    #[test]
+    fn dispatch() {
+        let table = OpTable::<fn(u8) -> u8>::new(|x| x)
+            .on(U4::n(0), |x| x + 1)
+            .on(U4::n(1), |x| x * 2);
+        assert_eq!(table.dispatch(U4::n(0), 5), 6);
+        assert_eq!(table.dispatch(U4::n(1), 5), 10);
+        assert_eq!(table.dispatch(U4::n(9), 5), 5);
+
+        // Boxed closures are not Clone, so start from a full table.
+        let offset = 7;
+        let boxed = OpTable::from_handlers(core::array::from_fn(|_| {
+            Box::new(|x: u8| x) as Box<dyn Fn(u8) -> u8>
+        }))
+        .on(U4::n(2), Box::new(move |x| x + offset));
+        assert_eq!(boxed.dispatch(U4::n(2), 1), 8);
+    }
+}
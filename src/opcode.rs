@@ -0,0 +1,104 @@
+//! Field access for 16-bit opcodes made of four nibbles, as used by CHIP-8.
+//!
+//! Field names follow the CHIP-8 convention for an opcode `OXYN`:
+//! `nnn` is the low 12 bits, `kk` the low byte, `x` and `y` the second and
+//! third nibble and `n` the lowest nibble.
+
+use crate::U4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Opcode(pub u16);
+
+impl Opcode {
+    pub fn from_nibbles(nibbles: (U4, U4, U4, U4)) -> Self {
+        let (a, b, c, d) = nibbles;
+        Self(
+            (a.to_u8() as u16) << 12
+                | (b.to_u8() as u16) << 8
+                | (c.to_u8() as u16) << 4
+                | d.to_u8() as u16,
+        )
+    }
+
+    /// The four nibbles, most significant first.
+    pub fn nibbles(self) -> (U4, U4, U4, U4) {
+        (self.op(), self.x(), self.y(), self.n())
+    }
+
+    /// The four nibbles as plain integers, most significant first, for
+    /// matching against literal patterns.
+    pub fn nibbles_u8(self) -> (u8, u8, u8, u8) {
+        let (a, b, c, d) = self.nibbles();
+        (a.to_u8(), b.to_u8(), c.to_u8(), d.to_u8())
+    }
+
+    /// The most significant nibble, selecting the instruction group.
+    pub fn op(self) -> U4 {
+        U4::from_u8((self.0 >> 12) as u8)
+    }
+
+    pub fn x(self) -> U4 {
+        U4::from_u8((self.0 >> 8) as u8)
+    }
+
+    pub fn y(self) -> U4 {
+        U4::from_u8((self.0 >> 4) as u8)
+    }
+
+    pub fn n(self) -> U4 {
+        U4::from_u8(self.0 as u8)
+    }
+
+    /// The low byte.
+    pub fn kk(self) -> u8 {
+        self.0 as u8
+    }
+
+    /// The low 12 bits, usually an address.
+    pub fn nnn(self) -> u16 {
+        self.0 & 0x0fff
+    }
+}
+
+impl From<u16> for Opcode {
+    fn from(op: u16) -> Self {
+        Self(op)
+    }
+}
+
+impl From<Opcode> for u16 {
+    fn from(op: Opcode) -> Self {
+        op.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields() {
+        let op = Opcode(0xd12f);
+        assert_eq!(op.nibbles(), (U4::n(0xd), U4::n(1), U4::n(2), U4::n(0xf)));
+        assert_eq!(op.x(), U4::n(1));
+        assert_eq!(op.y(), U4::n(2));
+        assert_eq!(op.n(), U4::n(0xf));
+        assert_eq!(op.kk(), 0x2f);
+        assert_eq!(op.nnn(), 0x12f);
+        assert_eq!(Opcode::from_nibbles(op.nibbles()), op);
+    }
+
+    #[test]
+    fn pattern_match() {
+        let decode = |op: Opcode| match op.nibbles_u8() {
+            (0x0, 0x0, 0xe, 0x0) => "cls",
+            (0x1, ..) => "jp",
+            (0x8, _, _, 0x4) => "add",
+            _ => "unknown",
+        };
+        assert_eq!(decode(Opcode(0x00e0)), "cls");
+        assert_eq!(decode(Opcode(0x1234)), "jp");
+        assert_eq!(decode(Opcode(0x8ab4)), "add");
+        assert_eq!(decode(Opcode(0xffff)), "unknown");
+    }
+}
@@ -0,0 +1,105 @@
+//! Packing fixed nibble arrays into wider integers and back.
+//!
+//! The `_be` functions treat element 0 as the most significant nibble, so
+//! the array reads like the integer written in hex; the `_le` functions
+//! treat element 0 as the least significant.
+//!
+//! ```
+//! use u4::{nibbles, pack};
+//!
+//! assert_eq!(pack::u16_from_be(nibbles!("beef")), 0xbeef);
+//! assert_eq!(pack::u16_from_le(nibbles!("beef")), 0xfeeb);
+//! assert_eq!(pack::u16_to_be(0xbeef), nibbles!("beef"));
+//! ```
+
+use crate::U4;
+
+macro_rules! pack {
+    ($($int:ty, $n:literal, $from_be:ident, $from_le:ident, $to_be:ident, $to_le:ident;)*) => {$(
+        #[doc = concat!("Packs ", stringify!($n), " nibbles into a `", stringify!($int), "`, most significant first.")]
+        pub const fn $from_be(nibbles: [U4; $n]) -> $int {
+            let mut out = 0;
+            let mut i = 0;
+            while i < $n {
+                out = out << 4 | nibbles[i].to_u8() as $int;
+                i += 1;
+            }
+            out
+        }
+
+        #[doc = concat!("Packs ", stringify!($n), " nibbles into a `", stringify!($int), "`, least significant first.")]
+        pub const fn $from_le(nibbles: [U4; $n]) -> $int {
+            let mut out = 0;
+            let mut i = $n;
+            while i > 0 {
+                i -= 1;
+                out = out << 4 | nibbles[i].to_u8() as $int;
+            }
+            out
+        }
+
+        #[doc = concat!("Splits a `", stringify!($int), "` into nibbles, most significant first.")]
+        pub const fn $to_be(v: $int) -> [U4; $n] {
+            let mut out = [U4::MIN; $n];
+            let mut i = 0;
+            while i < $n {
+                out[i] = U4::from_u8((v >> (4 * ($n - 1 - i))) as u8);
+                i += 1;
+            }
+            out
+        }
+
+        #[doc = concat!("Splits a `", stringify!($int), "` into nibbles, least significant first.")]
+        pub const fn $to_le(v: $int) -> [U4; $n] {
+            let mut out = [U4::MIN; $n];
+            let mut i = 0;
+            while i < $n {
+                out[i] = U4::from_u8((v >> (4 * i)) as u8);
+                i += 1;
+            }
+            out
+        }
+    )*};
+}
+
+pack! {
+    u8, 2, u8_from_be, u8_from_le, u8_to_be, u8_to_le;
+    u16, 4, u16_from_be, u16_from_le, u16_to_be, u16_to_le;
+    u32, 8, u32_from_be, u32_from_le, u32_to_be, u32_to_le;
+    u64, 16, u64_from_be, u64_from_le, u64_to_be, u64_to_le;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nibbles;
+
+    #[test]
+    fn known_values() {
+        assert_eq!(u8_from_be([U4::n(0xa), U4::n(5)]), 0xa5);
+        assert_eq!(u8_from_le([U4::n(0xa), U4::n(5)]), 0x5a);
+        assert_eq!(u32_to_be(0x1234_5678), nibbles!("12345678"));
+        assert_eq!(u32_to_le(0x1234_5678), nibbles!("87654321"));
+        assert_eq!(
+            u64_from_be(nibbles!("0123456789abcdef")),
+            0x0123_4567_89ab_cdef
+        );
+        const PACKED: u16 = u16_from_le(nibbles!("1000"));
+        assert_eq!(PACKED, 1);
+    }
+
+    #[test]
+    fn roundtrip() {
+        for v in (0..=u64::MAX).step_by(0x0f1e_2d3c_4b5a_6978) {
+            assert_eq!(u64_from_be(u64_to_be(v)), v);
+            assert_eq!(u64_from_le(u64_to_le(v)), v);
+            let mut reversed = u64_to_be(v);
+            reversed.reverse();
+            assert_eq!(u64_to_le(v), reversed);
+        }
+        for v in 0..=u16::MAX {
+            assert_eq!(u16_from_be(u16_to_be(v)), v);
+            assert_eq!(u16_from_le(u16_to_le(v)), v);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{
+    sbox::{is_permutation, NotBijective},
+    U4,
+};
+
+/// A permutation of the 16 positions `0..16`.
+///
+/// Applying it moves the element at position `i` to position `p(i)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Perm16 {
+    table: [U4; 16],
+}
+
+impl Perm16 {
+    pub const IDENTITY: Perm16 =
+        Perm16::from_u8_table([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    pub fn new(table: [U4; 16]) -> Result<Self, NotBijective> {
+        Self::try_from_u8_table(table.map(|n| n.to_u8()))
+    }
+
+    pub fn try_from_u8_table(table: [u8; 16]) -> Result<Self, NotBijective> {
+        if is_permutation(&table) {
+            Ok(Self::from_u8_table(table))
+        } else {
+            Err(NotBijective)
+        }
+    }
+
+    /// Builds a permutation in const context, panicking (a compile error in a
+    /// `const` item) if `table` is not a permutation of `0..16`.
+    pub const fn from_u8_table(table: [u8; 16]) -> Self {
+        assert!(is_permutation(&table), "table is not a permutation");
+        let mut out = [U4::MIN; 16];
+        let mut i = 0;
+        while i < 16 {
+            out[i] = U4::from_u8(table[i]);
+            i += 1;
+        }
+        Self { table: out }
+    }
+
+    pub fn identity() -> Self {
+        Self::IDENTITY
+    }
+
+    /// The position that `i` is moved to.
+    pub fn image(&self, i: U4) -> U4 {
+        self.table[i.to_u8() as usize]
+    }
+
+    pub fn inverse(&self) -> Self {
+        let mut table = [U4::MIN; 16];
+        for (i, n) in self.table.iter().enumerate() {
+            table[n.to_u8() as usize] = U4::from_u8(i as u8);
+        }
+        Self { table }
+    }
+
+    /// The permutation `i -> self(other(i))`.
+    pub fn compose(&self, other: &Self) -> Self {
+        Self {
+            table: other.table.map(|n| self.image(n)),
+        }
+    }
+
+    /// The permutation `i -> other(self(i))`.
+    pub fn then(&self, other: &Self) -> Self {
+        other.compose(self)
+    }
+
+    pub fn apply<T: Copy>(&self, src: [T; 16]) -> [T; 16] {
+        let mut out = src;
+        for (i, &x) in src.iter().enumerate() {
+            out[self.table[i].to_u8() as usize] = x;
+        }
+        out
+    }
+
+    /// Permutes `data` in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data.len() != 16`.
+    pub fn apply_to_slice<T: Copy>(&self, data: &mut [T]) {
+        let src: [T; 16] = data
+            .try_into()
+            .expect("Perm16 applied to a slice of length != 16");
+        data.copy_from_slice(&self.apply(src));
+    }
+
+    /// Cycle decomposition, including fixed points, each cycle starting at its
+    /// smallest element.
+    #[cfg(feature = "alloc")]
+    pub fn cycles(&self) -> Vec<Vec<U4>> {
+        let mut seen = [false; 16];
+        let mut cycles = Vec::new();
+        for start in 0..16 {
+            if seen[start] {
+                continue;
+            }
+            let mut cycle = Vec::new();
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                cycle.push(U4::from_u8(i as u8));
+                i = self.table[i].to_u8() as usize;
+            }
+            cycles.push(cycle);
+        }
+        cycles
+    }
+
+    /// Number of cycles, including fixed points.
+    pub fn cycle_count(&self) -> usize {
+        let mut seen = [false; 16];
+        let mut count = 0;
+        for start in 0..16 {
+            if seen[start] {
+                continue;
+            }
+            count += 1;
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                i = self.table[i].to_u8() as usize;
+            }
+        }
+        count
+    }
+
+    pub fn is_even(&self) -> bool {
+        (16 - self.cycle_count()).is_multiple_of(2)
+    }
+
+    /// `1` for even permutations, `-1` for odd ones.
+    pub fn sign(&self) -> i8 {
+        if self.is_even() {
+            1
+        } else {
+            -1
+        }
+    }
+
+    pub fn table(&self) -> &[U4; 16] {
+        &self.table
+    }
+}
+
+impl TryFrom<[U4; 16]> for Perm16 {
+    type Error = NotBijective;
+
+    fn try_from(table: [U4; 16]) -> Result<Self, Self::Error> {
+        Self::new(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Midori cell permutation
+    const P: Perm16 = Perm16::from_u8_table([0, 7, 14, 9, 5, 2, 11, 12, 15, 8, 1, 6, 10, 13, 4, 3]);
+
+    #[test]
+    fn inverse() {
+        assert_eq!(P.compose(&P.inverse()), Perm16::IDENTITY);
+        assert_eq!(P.inverse().then(&P), Perm16::IDENTITY);
+        assert_eq!(Perm16::try_from_u8_table([1; 16]), Err(NotBijective));
+    }
+
+    #[test]
+    fn apply() {
+        let data: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let moved = P.apply(data);
+        for i in 0..16 {
+            assert_eq!(moved[P.image(U4::n(i)).to_u8() as usize], i);
+        }
+
+        let mut slice = data.to_vec();
+        P.apply_to_slice(&mut slice);
+        P.inverse().apply_to_slice(&mut slice);
+        assert_eq!(slice, data);
+    }
+
+    #[test]
+    fn cycles() {
+        let swap = Perm16::from_u8_table([1, 0, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(swap.cycle_count(), 15);
+        #[cfg(feature = "alloc")]
+        assert_eq!(swap.cycles()[0], vec![U4::n(0), U4::n(1)]);
+        assert!(!swap.is_even());
+        assert_eq!(swap.sign(), -1);
+        assert!(swap.then(&swap).is_even());
+        assert_eq!(Perm16::IDENTITY.sign(), 1);
+    }
+}
@@ -0,0 +1,138 @@
+//! Splitting buffers into a plane of high nibbles and a plane of low
+//! nibbles, and interleaving them back.
+//!
+//! Each plane packs its nibbles two to a byte in buffer order, so byte `i`
+//! of the high plane holds the high nibbles of input bytes `2 * i` and
+//! `2 * i + 1`.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Splits `bytes` into its high and low nibble planes. For an odd length
+/// the last byte of each plane is padded with a zero nibble.
+#[cfg(feature = "alloc")]
+pub fn split_planes(bytes: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let pack = |f: fn(u8) -> u8| -> Vec<u8> {
+        bytes
+            .chunks(2)
+            .map(|c| f(c[0]) << 4 | c.get(1).map_or(0, |&b| f(b)))
+            .collect()
+    };
+    (pack(|b| b >> 4), pack(|b| b & 0xf))
+}
+
+/// The inverse of [`split_planes`], giving two bytes per plane byte; drop
+/// the last byte to undo the padding of an odd-length split.
+///
+/// # Panics
+///
+/// If the planes have different lengths.
+#[cfg(feature = "alloc")]
+pub fn interleave_planes(high: &[u8], low: &[u8]) -> Vec<u8> {
+    assert_eq!(high.len(), low.len(), "planes have different lengths");
+    high.iter()
+        .zip(low)
+        .flat_map(|(&h, &l)| [h & 0xf0 | l >> 4, h << 4 | l & 0xf])
+        .collect()
+}
+
+/// Swaps nibbles within each byte pair so that the first byte holds both
+/// high nibbles and the second both low nibbles. It is its own inverse.
+fn transpose_pairs(buf: &mut [u8]) {
+    for pair in buf.chunks_exact_mut(2) {
+        let (a, b) = (pair[0], pair[1]);
+        pair[0] = a & 0xf0 | b >> 4;
+        pair[1] = a << 4 | b & 0xf;
+    }
+}
+
+/// Moves the even-indexed bytes to the front half and the odd-indexed ones
+/// to the back, in O(n log n) time without allocating.
+fn unshuffle(buf: &mut [u8]) {
+    let pairs = buf.len() / 2;
+    if pairs < 2 {
+        return;
+    }
+    let left = pairs / 2;
+    let (l, r) = buf.split_at_mut(left * 2);
+    unshuffle(l);
+    unshuffle(r);
+    buf[left..pairs + left].rotate_left(left);
+}
+
+/// The inverse of [`unshuffle`].
+fn shuffle(buf: &mut [u8]) {
+    let pairs = buf.len() / 2;
+    if pairs < 2 {
+        return;
+    }
+    let left = pairs / 2;
+    buf[left..pairs + left].rotate_right(left);
+    let (l, r) = buf.split_at_mut(left * 2);
+    shuffle(l);
+    shuffle(r);
+}
+
+/// Rearranges `buf` in place into its high plane followed by its low
+/// plane.
+///
+/// # Panics
+///
+/// If `buf` has an odd length.
+pub fn split_planes_in_place(buf: &mut [u8]) {
+    assert!(buf.len().is_multiple_of(2), "buffer length is odd");
+    transpose_pairs(buf);
+    unshuffle(buf);
+}
+
+/// The inverse of [`split_planes_in_place`]: interleaves a buffer holding
+/// the high plane followed by the low plane.
+///
+/// # Panics
+///
+/// If `buf` has an odd length.
+pub fn interleave_planes_in_place(buf: &mut [u8]) {
+    assert!(buf.len().is_multiple_of(2), "buffer length is odd");
+    shuffle(buf);
+    transpose_pairs(buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_place() {
+        let mut buf = [0x12, 0x34, 0x56, 0x78];
+        split_planes_in_place(&mut buf);
+        assert_eq!(buf, [0x13, 0x57, 0x24, 0x68]);
+        interleave_planes_in_place(&mut buf);
+        assert_eq!(buf, [0x12, 0x34, 0x56, 0x78]);
+
+        for len in (0..40).step_by(2) {
+            let data: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(37)).collect();
+            let mut buf = data.clone();
+            split_planes_in_place(&mut buf);
+            let high: Vec<u8> = data.chunks(2).map(|c| c[0] & 0xf0 | c[1] >> 4).collect();
+            assert_eq!(buf[..len / 2], high);
+            interleave_planes_in_place(&mut buf);
+            assert_eq!(buf, data);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn split_and_interleave() {
+        let (high, low) = split_planes(&[0x12, 0x34, 0x56]);
+        assert_eq!(high, [0x13, 0x50]);
+        assert_eq!(low, [0x24, 0x60]);
+        assert_eq!(interleave_planes(&high, &low), [0x12, 0x34, 0x56, 0x00]);
+
+        let data: Vec<u8> = (0..=255).collect();
+        let (high, low) = split_planes(&data);
+        let mut buf = data.clone();
+        split_planes_in_place(&mut buf);
+        assert_eq!([high.as_slice(), &low].concat(), buf);
+        assert_eq!(interleave_planes(&high, &low), data);
+    }
+}
@@ -0,0 +1,123 @@
+//! 4-bit parallel ports.
+//!
+//! The traits follow the shape of `embedded-hal`'s digital traits: a shared
+//! [`ErrorType`] and separate input and output capabilities, with
+//! [`NibblePort`] for buses that can do both. With the `embedded-hal`
+//! feature, [`PinPort`] implements them over four individual pins.
+
+use crate::U4;
+
+pub trait ErrorType {
+    type Error;
+}
+
+pub trait NibbleOutput: ErrorType {
+    fn write(&mut self, value: U4) -> Result<(), Self::Error>;
+}
+
+pub trait NibbleInput: ErrorType {
+    fn read(&mut self) -> Result<U4, Self::Error>;
+}
+
+/// A bidirectional 4-bit port.
+pub trait NibblePort: NibbleInput + NibbleOutput {}
+
+impl<T: NibbleInput + NibbleOutput> NibblePort for T {}
+
+/// Four `embedded-hal` pins forming a port, bit 0 first.
+#[cfg(feature = "embedded-hal")]
+#[derive(Debug)]
+pub struct PinPort<P> {
+    pins: [P; 4],
+}
+
+#[cfg(feature = "embedded-hal")]
+impl<P> PinPort<P> {
+    /// `pins[i]` carries bit `i` of the nibble.
+    pub fn new(pins: [P; 4]) -> Self {
+        Self { pins }
+    }
+
+    pub fn into_pins(self) -> [P; 4] {
+        self.pins
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+impl<P: embedded_hal::digital::ErrorType> ErrorType for PinPort<P> {
+    type Error = P::Error;
+}
+
+#[cfg(feature = "embedded-hal")]
+impl<P: embedded_hal::digital::OutputPin> NibbleOutput for PinPort<P> {
+    fn write(&mut self, value: U4) -> Result<(), Self::Error> {
+        let value = value.to_u8();
+        for (i, pin) in self.pins.iter_mut().enumerate() {
+            pin.set_state((value & (1 << i) != 0).into())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+impl<P: embedded_hal::digital::InputPin> NibbleInput for PinPort<P> {
+    fn read(&mut self) -> Result<U4, Self::Error> {
+        let mut value = 0;
+        for (i, pin) in self.pins.iter_mut().enumerate() {
+            if pin.is_high()? {
+                value |= 1 << i;
+            }
+        }
+        Ok(U4::from_u8(value))
+    }
+}
+
+#[cfg(all(test, feature = "embedded-hal"))]
+mod tests {
+    use core::{cell::Cell, convert::Infallible};
+
+    use embedded_hal::digital::{self, InputPin, OutputPin};
+
+    use super::*;
+
+    struct Pin<'a>(&'a Cell<bool>);
+
+    impl digital::ErrorType for Pin<'_> {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for Pin<'_> {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.0.set(false);
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            self.0.set(true);
+            Ok(())
+        }
+    }
+
+    impl InputPin for Pin<'_> {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            Ok(self.0.get())
+        }
+
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            Ok(!self.0.get())
+        }
+    }
+
+    #[test]
+    fn write_read() {
+        let lines: [Cell<bool>; 4] = Default::default();
+        let mut port = PinPort::new([0, 1, 2, 3].map(|i| Pin(&lines[i])));
+
+        port.write(U4::n(0b1010)).unwrap();
+        let levels: Vec<bool> = lines.iter().map(Cell::get).collect();
+        assert_eq!(levels, vec![false, true, false, true]);
+
+        lines[0].set(true);
+        assert_eq!(port.read().unwrap(), U4::n(0b1011));
+    }
+}
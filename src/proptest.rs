@@ -0,0 +1,67 @@
+//! proptest support.
+//!
+//! `any::<U4>()` generates nibbles directly. [`nibbles`] and
+//! [`nibble_array`] build collections of them.
+
+use alloc::vec::Vec;
+
+use ::proptest::{
+    arbitrary::Arbitrary,
+    collection::{self, SizeRange},
+    prelude::*,
+    strategy::Map,
+};
+
+use crate::{bcd::BcdDigit, U4};
+
+impl Arbitrary for U4 {
+    type Parameters = ();
+    type Strategy = Map<core::ops::RangeInclusive<u8>, fn(u8) -> Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (0..=0xf_u8).prop_map(U4::from_u8)
+    }
+}
+
+impl Arbitrary for BcdDigit {
+    type Parameters = ();
+    type Strategy = Map<core::ops::RangeInclusive<u8>, fn(u8) -> Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (0..=9_u8).prop_map(|d| BcdDigit::from_u8(d).unwrap())
+    }
+}
+
+/// Vectors of nibbles with a length in `len`, e.g. `nibbles(0..32)`.
+pub fn nibbles(len: impl Into<SizeRange>) -> impl Strategy<Value = Vec<U4>> {
+    collection::vec(any::<U4>(), len)
+}
+
+/// Fixed-size nibble arrays.
+pub fn nibble_array<const N: usize>() -> impl Strategy<Value = [U4; N]> {
+    collection::vec(any::<U4>(), N).prop_map(|v| v.try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn in_range(n in any::<U4>(), d in any::<BcdDigit>()) {
+            prop_assert!(n.to_u8() <= 0xf);
+            prop_assert!(d.to_u8() <= 9);
+        }
+
+        #[test]
+        fn lengths(v in nibbles(3..7), a in nibble_array::<5>()) {
+            prop_assert!((3..7).contains(&v.len()));
+            prop_assert_eq!(a.len(), 5);
+        }
+
+        #[test]
+        fn wrapping_add_matches_u8(a in any::<U4>(), b in any::<U4>()) {
+            prop_assert_eq!(a.wrapping_add(b).to_u8(), (a.to_u8() + b.to_u8()) & 0xf);
+        }
+    }
+}
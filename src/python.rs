@@ -0,0 +1,391 @@
+//! Python bindings.
+//!
+//! Build the extension module as a shared library with
+//! `cargo rustc --release --features python --crate-type cdylib` and copy
+//! `target/release/libu4.so` to `u4.so` on the Python path, then:
+//!
+//! ```python
+//! from u4 import U4, Nibbles
+//!
+//! n = U4(9) + U4(9)            # wraps to U4(2)
+//! data = Nibbles.from_hex("1f2")
+//! bytes(memoryview(data))      # b'\x1f\x20', packed high nibble first
+//! ```
+
+use core::{
+    ffi::{c_int, c_void},
+    ptr,
+};
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use pyo3::{
+    exceptions::{PyBufferError, PyIndexError, PyTypeError, PyValueError, PyZeroDivisionError},
+    ffi,
+    prelude::*,
+    types::PyBytes,
+};
+
+use crate::{line4b5b, U4};
+
+/// A 4-bit unsigned integer. Arithmetic wraps modulo 16.
+#[pyclass(name = "U4", module = "u4", frozen, eq, ord, hash, from_py_object)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PyU4(pub U4);
+
+fn check_nibble(value: i64) -> PyResult<U4> {
+    if !(0..=15).contains(&value) {
+        return Err(PyValueError::new_err(format!(
+            "{value} is out of range for U4 (0..=15)"
+        )));
+    }
+    Ok(U4::from_u8(value as u8))
+}
+
+/// Accepts a `U4` or an `int` in `0..=15` as the other operand.
+fn operand(other: &Bound<'_, PyAny>) -> PyResult<U4> {
+    if let Ok(n) = other.extract::<PyU4>() {
+        return Ok(n.0);
+    }
+    match other.extract::<i64>() {
+        Ok(value) => check_nibble(value),
+        Err(_) => Err(PyTypeError::new_err("expected U4 or int")),
+    }
+}
+
+fn nonzero(n: U4) -> PyResult<U4> {
+    if n == U4::MIN {
+        return Err(PyZeroDivisionError::new_err("U4 division by zero"));
+    }
+    Ok(n)
+}
+
+#[pymethods]
+impl PyU4 {
+    #[new]
+    fn new(value: i64) -> PyResult<Self> {
+        check_nibble(value).map(Self)
+    }
+
+    /// Parses a single hex digit.
+    #[staticmethod]
+    fn from_hex(s: &str) -> PyResult<Self> {
+        let mut chars = s.chars();
+        match (chars.next().and_then(|c| c.to_digit(16)), chars.next()) {
+            (Some(d), None) => Ok(Self(U4::from_u8(d as u8))),
+            _ => Err(PyValueError::new_err(format!("invalid hex digit {s:?}"))),
+        }
+    }
+
+    fn hex(&self) -> String {
+        format!("{:x}", self.0.to_u8())
+    }
+
+    fn __int__(&self) -> u8 {
+        self.0.to_u8()
+    }
+
+    fn __index__(&self) -> u8 {
+        self.0.to_u8()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("U4({})", self.0)
+    }
+
+    fn __str__(&self) -> String {
+        format!("{}", self.0)
+    }
+
+    fn __add__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self(self.0.wrapping_add(operand(other)?)))
+    }
+
+    fn __sub__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self(self.0.wrapping_sub(operand(other)?)))
+    }
+
+    fn __mul__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self(self.0 * operand(other)?))
+    }
+
+    fn __floordiv__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self(self.0 / nonzero(operand(other)?)?))
+    }
+
+    fn __mod__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self(self.0 % nonzero(operand(other)?)?))
+    }
+
+    fn __and__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self(self.0 & operand(other)?))
+    }
+
+    fn __or__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self(self.0 | operand(other)?))
+    }
+
+    fn __xor__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self(self.0 ^ operand(other)?))
+    }
+
+    fn __lshift__(&self, n: usize) -> Self {
+        Self(self.0 << n)
+    }
+
+    fn __rshift__(&self, n: usize) -> Self {
+        Self(self.0 >> n)
+    }
+
+    fn __invert__(&self) -> Self {
+        Self(!self.0)
+    }
+}
+
+/// An immutable nibble sequence stored packed two per byte, high nibble
+/// first. Supports `len()`, indexing and the buffer protocol over the
+/// packed bytes.
+#[pyclass(module = "u4", frozen, skip_from_py_object)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nibbles {
+    packed: Vec<u8>,
+    len: usize,
+}
+
+impl Nibbles {
+    pub fn from_nibbles(nibbles: &[U4]) -> Self {
+        let packed = nibbles
+            .chunks(2)
+            .map(|pair| (pair[0].to_u8() << 4) | pair.get(1).map_or(0, U4::to_u8))
+            .collect();
+        Self {
+            packed,
+            len: nibbles.len(),
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<U4> {
+        (index < self.len).then(|| {
+            let byte = self.packed[index / 2];
+            U4::from_u8(if index.is_multiple_of(2) {
+                byte >> 4
+            } else {
+                byte
+            })
+        })
+    }
+
+    pub fn to_vec(&self) -> Vec<U4> {
+        (0..self.len).filter_map(|i| self.get(i)).collect()
+    }
+}
+
+#[pymethods]
+impl Nibbles {
+    #[new]
+    fn new(values: Vec<i64>) -> PyResult<Self> {
+        let nibbles = values
+            .into_iter()
+            .map(check_nibble)
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Self::from_nibbles(&nibbles))
+    }
+
+    /// One nibble per hex digit.
+    #[staticmethod]
+    fn from_hex(s: &str) -> PyResult<Self> {
+        let nibbles = s
+            .chars()
+            .map(|c| match c.to_digit(16) {
+                Some(d) => Ok(U4::from_u8(d as u8)),
+                None => Err(PyValueError::new_err(format!("invalid hex digit {c:?}"))),
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Self::from_nibbles(&nibbles))
+    }
+
+    /// Unpacks `data`, keeping the first `len` nibbles (all of them by
+    /// default).
+    #[staticmethod]
+    #[pyo3(signature = (data, len = None))]
+    fn from_bytes(data: Vec<u8>, len: Option<usize>) -> PyResult<Self> {
+        let len = len.unwrap_or(data.len() * 2);
+        if len.div_ceil(2) != data.len() {
+            return Err(PyValueError::new_err("length does not match the data"));
+        }
+        let mut packed = data;
+        if !len.is_multiple_of(2) {
+            *packed.last_mut().unwrap() &= 0xf0;
+        }
+        Ok(Self { packed, len })
+    }
+
+    fn hex(&self) -> String {
+        self.to_vec()
+            .iter()
+            .map(|n| format!("{:x}", n.to_u8()))
+            .collect()
+    }
+
+    fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.packed)
+    }
+
+    fn __len__(&self) -> usize {
+        self.len
+    }
+
+    fn __getitem__(&self, index: isize) -> PyResult<PyU4> {
+        let i = if index < 0 {
+            index + self.len as isize
+        } else {
+            index
+        };
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| self.get(i))
+            .map(PyU4)
+            .ok_or_else(|| PyIndexError::new_err("Nibbles index out of range"))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Nibbles.from_hex({:?})", self.hex())
+    }
+
+    /// # Safety
+    ///
+    /// Called by Python with a valid `Py_buffer` to fill.
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("view is null"));
+        }
+        if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("Nibbles is read-only"));
+        }
+        // `frozen` keeps `packed` from moving while the buffer is exported,
+        // and `obj` holds a reference that keeps `slf` alive.
+        let data = slf.get().packed.as_ptr();
+        let len = slf.get().packed.len();
+        unsafe {
+            (*view).obj = slf.into_any().into_ptr();
+            (*view).buf = data as *mut c_void;
+            (*view).len = len as isize;
+            (*view).readonly = 1;
+            (*view).itemsize = 1;
+            (*view).format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+                c"B".as_ptr() as *mut _
+            } else {
+                ptr::null_mut()
+            };
+            (*view).ndim = 1;
+            (*view).shape = if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+                &mut (*view).len
+            } else {
+                ptr::null_mut()
+            };
+            (*view).strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+                &mut (*view).itemsize
+            } else {
+                ptr::null_mut()
+            };
+            (*view).suboffsets = ptr::null_mut();
+            (*view).internal = ptr::null_mut();
+        }
+        Ok(())
+    }
+}
+
+/// 4B/5B-encodes data nibbles into 5-bit code groups.
+#[pyfunction]
+fn encode_4b5b(nibbles: &Nibbles) -> Vec<u8> {
+    line4b5b::encode_iter(nibbles.to_vec()).collect()
+}
+
+/// Decodes 5-bit code groups that must all carry data.
+#[pyfunction]
+fn decode_4b5b(codes: Vec<u8>) -> PyResult<Nibbles> {
+    let nibbles = codes
+        .into_iter()
+        .map(line4b5b::decode_data)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(Nibbles::from_nibbles(&nibbles))
+}
+
+/// The `u4` Python module.
+#[pymodule]
+#[pyo3(name = "u4")]
+pub fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyU4>()?;
+    m.add_class::<Nibbles>()?;
+    m.add_function(wrap_pyfunction!(encode_4b5b, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_4b5b, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::types::PyDict;
+
+    use super::*;
+
+    fn run(code: &core::ffi::CStr) {
+        Python::initialize();
+        Python::attach(|py| {
+            let m = PyModule::new(py, "u4").unwrap();
+            python_module(&m).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("u4", m).unwrap();
+            py.run(code, Some(&globals), None).unwrap();
+        });
+    }
+
+    #[test]
+    fn u4_dunders() {
+        run(c"
+U4 = u4.U4
+assert U4(9) + U4(9) == U4(2)
+assert U4(1) - 2 == U4(15)
+assert int(U4(12)) == 12 and [0, 1, 2][U4(2)] == 2
+assert ~U4(0) == U4(15) and U4(0b1011) << 2 == U4(0b1100)
+assert repr(U4(3)) == 'U4(3)' and U4.from_hex('a').hex() == 'a'
+assert U4(3) < U4(4) and len({U4(3), U4(3)}) == 1
+for bad in (lambda: U4(16), lambda: U4(1) + 99):
+    try:
+        bad()
+        raise AssertionError
+    except ValueError:
+        pass
+try:
+    U4(1) // 0
+    raise AssertionError
+except ZeroDivisionError:
+    pass
+");
+    }
+
+    #[test]
+    fn nibbles_buffer() {
+        run(c"
+n = u4.Nibbles.from_hex('1f2')
+assert len(n) == 3 and n[1] == u4.U4(15) and n[-1] == u4.U4(2)
+assert bytes(memoryview(n)) == b'\\x1f\\x20'
+assert bytes(n) == b'\\x1f\\x20'
+assert u4.Nibbles.from_bytes(b'\\x1f\\x2f', 3) == n
+assert list(u4.Nibbles([1, 2])) == [u4.U4(1), u4.U4(2)]
+assert u4.decode_4b5b(u4.encode_4b5b(n)) == n
+");
+    }
+}
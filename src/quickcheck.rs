@@ -0,0 +1,53 @@
+use alloc::boxed::Box;
+
+use ::quickcheck::{Arbitrary, Gen};
+
+use crate::{bcd::BcdDigit, U4};
+
+impl Arbitrary for U4 {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Self::from_u8(u8::arbitrary(g))
+    }
+
+    /// Shrinks toward zero.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.to_u8().shrink().map(Self::from_u8))
+    }
+}
+
+impl Arbitrary for BcdDigit {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Self::from_u8(u8::arbitrary(g) % 10).unwrap()
+    }
+
+    /// Shrinks toward zero.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.to_u8().shrink().filter_map(Self::from_u8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::quickcheck::quickcheck;
+
+    use super::*;
+
+    #[test]
+    fn shrinks_toward_zero() {
+        let shrunk: Vec<U4> = U4::n(12).shrink().collect();
+        assert_eq!(shrunk.first(), Some(&U4::MIN));
+        assert!(shrunk.iter().all(|n| n.to_u8() < 12));
+        assert_eq!(U4::MIN.shrink().count(), 0);
+        assert!(BcdDigit::NINE.shrink().all(|d| d.to_u8() < 9));
+    }
+
+    quickcheck! {
+        fn double_not(n: U4) -> bool {
+            !!n == n
+        }
+
+        fn add_commutes(a: U4, b: U4) -> bool {
+            a + b == b + a
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! rand support.
+//!
+//! `rng.random::<U4>()` draws uniformly from `0..=15` and
+//! `rng.random_range(U4::n(2)..U4::n(9))` from a range. [`SBox4::random`]
+//! and [`Perm16::random`] draw uniformly from all 16! tables, and
+//! `rng.fill(&mut nibbles)` fills a `[U4]` sixteen nibbles per `u64` drawn.
+
+use ::rand::{
+    distr::{
+        uniform::{Error, SampleBorrow, SampleUniform, UniformInt, UniformSampler},
+        Distribution, StandardUniform,
+    },
+    seq::SliceRandom,
+    Fill, Rng,
+};
+
+use crate::{alias::AliasTable, perm::Perm16, sbox::SBox4, U4};
+
+impl Distribution<U4> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> U4 {
+        // The high bits are the better ones for some generators.
+        U4::from_u8((rng.next_u32() >> 28) as u8)
+    }
+}
+
+/// The [`UniformSampler`] behind `random_range` for [`U4`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UniformU4(UniformInt<u8>);
+
+impl UniformSampler for UniformU4 {
+    type X = U4;
+
+    fn new<B1, B2>(low: B1, high: B2) -> Result<Self, Error>
+    where
+        B1: SampleBorrow<U4> + Sized,
+        B2: SampleBorrow<U4> + Sized,
+    {
+        UniformInt::new(low.borrow().to_u8(), high.borrow().to_u8()).map(Self)
+    }
+
+    fn new_inclusive<B1, B2>(low: B1, high: B2) -> Result<Self, Error>
+    where
+        B1: SampleBorrow<U4> + Sized,
+        B2: SampleBorrow<U4> + Sized,
+    {
+        UniformInt::new_inclusive(low.borrow().to_u8(), high.borrow().to_u8()).map(Self)
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> U4 {
+        U4::from_u8(self.0.sample(rng))
+    }
+}
+
+impl SampleUniform for U4 {
+    type Sampler = UniformU4;
+}
+
+impl Fill for U4 {
+    fn fill_slice<R: Rng + ?Sized>(this: &mut [Self], rng: &mut R) {
+        for chunk in this.chunks_mut(16) {
+            let mut bits = rng.next_u64();
+            for n in chunk {
+                *n = U4::from_u8(bits as u8);
+                bits >>= 4;
+            }
+        }
+    }
+}
+
+impl Distribution<U4> for AliasTable {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> U4 {
+        self.sample_bits(rng.next_u64())
+    }
+}
+
+fn random_table<R: Rng + ?Sized>(rng: &mut R) -> [U4; 16] {
+    let mut table: [U4; 16] = core::array::from_fn(|i| U4::from_u8(i as u8));
+    table.shuffle(rng);
+    table
+}
+
+impl SBox4 {
+    /// A uniformly random bijective S-box.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new(random_table(rng)).unwrap()
+    }
+}
+
+impl Perm16 {
+    /// A uniformly random permutation.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new(random_table(rng)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rand::{rngs::SmallRng, RngExt, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn standard_covers_range() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let mut seen = [false; 16];
+        for _ in 0..1000 {
+            seen[rng.random::<U4>().to_u8() as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn ranges() {
+        let mut rng = SmallRng::seed_from_u64(2);
+        for _ in 0..1000 {
+            let n = rng.random_range(U4::n(2)..U4::n(9));
+            assert!((2..9).contains(&n.to_u8()));
+            let m = rng.random_range(U4::n(14)..=U4::MAX);
+            assert!(m.to_u8() >= 14);
+        }
+    }
+
+    #[test]
+    fn fill() {
+        let mut rng = SmallRng::seed_from_u64(4);
+        let mut buf = [U4::MIN; 1000];
+        rng.fill(&mut buf);
+        let mut counts = [0; 16];
+        for n in buf {
+            counts[n.to_u8() as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| (30..100).contains(&c)), "{counts:?}");
+
+        let mut short = [U4::MIN; 3];
+        rng.fill(&mut short);
+        let mut again = SmallRng::seed_from_u64(5);
+        let mut a = [U4::MIN; 20];
+        again.fill(&mut a);
+        let mut again = SmallRng::seed_from_u64(5);
+        let (x, y) = (again.next_u64(), again.next_u64());
+        assert_eq!(a[0], U4::from_u8(x as u8));
+        assert_eq!(a[15], U4::from_u8((x >> 60) as u8));
+        assert_eq!(a[16], U4::from_u8(y as u8));
+    }
+
+    #[test]
+    fn alias_table() {
+        let mut weights = [0; 16];
+        weights[1] = 1;
+        weights[14] = 3;
+        let table = AliasTable::from_weights(weights).unwrap();
+        let mut rng = SmallRng::seed_from_u64(6);
+        let draws: Vec<U4> = (&table).sample_iter(&mut rng).take(4000).collect();
+        assert!(draws.iter().all(|&n| n == U4::n(1) || n == U4::n(14)));
+        let ones = draws.iter().filter(|&&n| n == U4::n(1)).count();
+        assert!((900..1100).contains(&ones), "{ones}");
+    }
+
+    #[test]
+    fn random_sbox() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let sbox = SBox4::random(&mut rng);
+        assert_eq!(sbox.compose(&sbox.inverse()), SBox4::IDENTITY);
+        assert_ne!(Perm16::random(&mut rng), Perm16::random(&mut rng));
+    }
+}
@@ -0,0 +1,160 @@
+//! Rank and select over an immutable nibble sequence.
+
+use alloc::vec::Vec;
+
+use crate::U4;
+
+/// Nibbles between stored cumulative counts.
+const BLOCK: usize = 64;
+
+/// An immutable nibble sequence, packed two per byte, answering rank and
+/// select queries for each of the 16 values.
+///
+/// Cumulative counts are sampled every 64 nibbles, adding 64 bytes per 32
+/// bytes of data; queries scan at most one block.
+///
+/// ```
+/// use u4::{rank_select::RankSelect, U4};
+///
+/// let seq: RankSelect = [3, 1, 3, 3, 0].map(U4::n).into_iter().collect();
+/// assert_eq!(seq.rank(U4::n(3), 3), 2);
+/// assert_eq!(seq.select(U4::n(3), 2), Some(3));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankSelect {
+    packed: Vec<u8>,
+    len: usize,
+    /// `blocks[b][v]` counts `v` among the first `b * BLOCK` nibbles.
+    blocks: Vec<[u32; 16]>,
+}
+
+impl RankSelect {
+    pub fn new(nibbles: &[U4]) -> Self {
+        nibbles.iter().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<U4> {
+        (index < self.len).then(|| self.nibble(index))
+    }
+
+    fn nibble(&self, index: usize) -> U4 {
+        let byte = self.packed[index / 2];
+        U4::from_u8(if index.is_multiple_of(2) {
+            byte >> 4
+        } else {
+            byte
+        })
+    }
+
+    /// The number of occurrences of `value` in the first `index` nibbles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > self.len()`.
+    pub fn rank(&self, value: U4, index: usize) -> usize {
+        assert!(index <= self.len, "rank index out of bounds");
+        let block = index / BLOCK;
+        let base = self.blocks[block][value.to_u8() as usize] as usize;
+        base + (block * BLOCK..index)
+            .filter(|&i| self.nibble(i) == value)
+            .count()
+    }
+
+    /// The position of occurrence `k` (counting from zero) of `value`.
+    pub fn select(&self, value: U4, k: usize) -> Option<usize> {
+        let v = value.to_u8() as usize;
+        // The last block starting with at most `k` earlier occurrences.
+        let block = self
+            .blocks
+            .partition_point(|counts| counts[v] as usize <= k)
+            - 1;
+        let mut seen = self.blocks[block][v] as usize;
+        for i in block * BLOCK..self.len.min((block + 1) * BLOCK) {
+            if self.nibble(i) == value {
+                if seen == k {
+                    return Some(i);
+                }
+                seen += 1;
+            }
+        }
+        None
+    }
+
+    /// The number of occurrences of `value`.
+    pub fn count(&self, value: U4) -> usize {
+        self.rank(value, self.len)
+    }
+}
+
+impl Default for RankSelect {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+impl FromIterator<U4> for RankSelect {
+    fn from_iter<I: IntoIterator<Item = U4>>(iter: I) -> Self {
+        let mut seq = Self {
+            packed: Vec::new(),
+            len: 0,
+            blocks: Vec::from([[0; 16]]),
+        };
+        let mut counts = [0; 16];
+        for n in iter {
+            if seq.len.is_multiple_of(2) {
+                seq.packed.push(n.to_u8() << 4);
+            } else {
+                *seq.packed.last_mut().unwrap() |= n.to_u8();
+            }
+            counts[n.to_u8() as usize] += 1;
+            seq.len += 1;
+            if seq.len.is_multiple_of(BLOCK) {
+                seq.blocks.push(counts);
+            }
+        }
+        seq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_naive() {
+        // Long enough to span several blocks, with a skewed distribution.
+        let nibbles: Vec<U4> = (0..1000_u32)
+            .map(|i| U4::from_u8((i * i % 23 % 16) as u8))
+            .collect();
+        let seq = RankSelect::new(&nibbles);
+        assert_eq!(seq.len(), 1000);
+        for v in (0..16).map(U4::n) {
+            let positions: Vec<usize> = (0..nibbles.len()).filter(|&i| nibbles[i] == v).collect();
+            for i in (0..=nibbles.len()).step_by(7).chain([64, 128, 1000]) {
+                assert_eq!(seq.rank(v, i), positions.iter().filter(|&&p| p < i).count());
+            }
+            for (k, &p) in positions.iter().enumerate() {
+                assert_eq!(seq.select(v, k), Some(p));
+            }
+            assert_eq!(seq.select(v, positions.len()), None);
+            assert_eq!(seq.count(v), positions.len());
+        }
+        assert_eq!(seq.get(999), Some(nibbles[999]));
+        assert_eq!(seq.get(1000), None);
+    }
+
+    #[test]
+    fn empty() {
+        let seq = RankSelect::default();
+        assert_eq!(seq.rank(U4::MIN, 0), 0);
+        assert_eq!(seq.select(U4::MIN, 0), None);
+    }
+}
@@ -0,0 +1,343 @@
+//! Hardware registers laid out as 4-bit fields.
+//!
+//! ```
+//! use u4::{register::{NibbleField, Register}, U4};
+//!
+//! const MODE: NibbleField<u16> = NibbleField::new(0);
+//! const PRESCALER: NibbleField<u16> = NibbleField::new(2);
+//!
+//! let mut ctrl = Register::new(0x0000_u16).with(MODE, U4::n(3));
+//! ctrl.modify(PRESCALER, |p| p + U4::n(1));
+//! assert_eq!(ctrl.value(), 0x0103);
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::{i4::I4, U4};
+
+/// Integer types that can back a nibble-field register.
+pub trait NibbleStorage: Copy {
+    /// Number of nibbles in the type.
+    const NIBBLES: u32;
+
+    /// Nibble `index`, counted from the least significant nibble.
+    fn nibble(self, index: u32) -> U4;
+
+    /// Replaces nibble `index`, counted from the least significant nibble.
+    fn with_nibble(self, index: u32, value: U4) -> Self;
+}
+
+macro_rules! impl_nibble_storage {
+    ($($t:ty),*) => {
+        $(
+            impl NibbleStorage for $t {
+                const NIBBLES: u32 = <$t>::BITS / 4;
+
+                fn nibble(self, index: u32) -> U4 {
+                    U4::from_u8((self >> (index * 4)) as u8)
+                }
+
+                fn with_nibble(self, index: u32, value: U4) -> Self {
+                    let shift = index * 4;
+                    (self & !(0xf << shift)) | ((value.to_u8() as $t) << shift)
+                }
+            }
+        )*
+    };
+}
+
+impl_nibble_storage!(u8, u16, u32, u64, u128);
+
+/// A named 4-bit field at nibble position `index` of a `T` register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NibbleField<T> {
+    index: u32,
+    _storage: PhantomData<T>,
+}
+
+impl<T: NibbleStorage> NibbleField<T> {
+    /// # Panics
+    ///
+    /// Panics (at compile time in a `const`) if `index` is out of range for `T`.
+    pub const fn new(index: u32) -> Self {
+        assert!(index < T::NIBBLES, "nibble field out of range");
+        Self {
+            index,
+            _storage: PhantomData,
+        }
+    }
+
+    pub const fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+/// A register value accessed through [`NibbleField`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Register<T> {
+    value: T,
+}
+
+impl<T: NibbleStorage> Register<T> {
+    pub fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    pub fn get(&self, field: NibbleField<T>) -> U4 {
+        self.value.nibble(field.index)
+    }
+
+    pub fn set(&mut self, field: NibbleField<T>, value: U4) {
+        self.value = self.value.with_nibble(field.index, value);
+    }
+
+    /// Builder-style [`set`](Self::set).
+    pub fn with(mut self, field: NibbleField<T>, value: U4) -> Self {
+        self.set(field, value);
+        self
+    }
+
+    /// Replaces a field with `f` applied to its current value.
+    pub fn modify(&mut self, field: NibbleField<T>, f: impl FnOnce(U4) -> U4) {
+        self.set(field, f(self.get(field)));
+    }
+}
+
+impl<T: NibbleStorage> From<T> for Register<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Reads a register through `read`, lets `f` edit its fields and writes the
+/// result back through `write`, e.g. around volatile MMIO accesses.
+pub fn read_modify_write<T: NibbleStorage>(
+    read: impl FnOnce() -> T,
+    write: impl FnOnce(T),
+    f: impl FnOnce(&mut Register<T>),
+) {
+    let mut reg = Register::new(read());
+    f(&mut reg);
+    write(reg.value());
+}
+
+/// Field types that [`nibble_fields!`](crate::nibble_fields) can pack.
+pub trait PackedField: Copy {
+    /// Width in bits.
+    const WIDTH: u32;
+
+    fn to_raw(self) -> u8;
+
+    /// Builds the field from the low [`WIDTH`](Self::WIDTH) bits of `raw`.
+    fn from_raw(raw: u8) -> Self;
+}
+
+impl PackedField for U4 {
+    const WIDTH: u32 = 4;
+
+    fn to_raw(self) -> u8 {
+        self.to_u8()
+    }
+
+    fn from_raw(raw: u8) -> Self {
+        U4::from_u8(raw)
+    }
+}
+
+/// Stored as the two's complement bits.
+impl PackedField for I4 {
+    const WIDTH: u32 = 4;
+
+    fn to_raw(self) -> u8 {
+        self.cast_unsigned().to_u8()
+    }
+
+    fn from_raw(raw: u8) -> Self {
+        U4::from_u8(raw).cast_signed()
+    }
+}
+
+impl PackedField for bool {
+    const WIDTH: u32 = 1;
+
+    fn to_raw(self) -> u8 {
+        self as u8
+    }
+
+    fn from_raw(raw: u8) -> Self {
+        raw & 1 != 0
+    }
+}
+
+/// Declares a struct of [`U4`](crate::U4), [`I4`](crate::i4::I4) and `bool`
+/// fields packed into an integer, each at a given bit offset, with `pack`
+/// and `unpack` and `From` conversions both ways. Overlapping or out-of-range fields fail to compile.
+///
+/// ```
+/// use u4::{nibble_fields, U4};
+///
+/// nibble_fields! {
+///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///     pub struct Header: u16 {
+///         pub version @ 12: U4,
+///         pub kind @ 8: U4,
+///         pub urgent @ 7: bool,
+///         pub channel @ 0: U4,
+///     }
+/// }
+///
+/// let h = Header::unpack(0x4283);
+/// assert_eq!(h.version, U4::n(4));
+/// assert!(h.urgent);
+/// assert_eq!(h.pack(), 0x4283);
+/// ```
+///
+/// ```compile_fail
+/// u4::nibble_fields! {
+///     struct Overlap: u8 {
+///         high @ 4: u4::U4,
+///         flag @ 5: bool,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! nibble_fields {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident: $repr:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident @ $offset:literal: $ty:ty),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty,)+
+        }
+
+        const _: () = {
+            let mut used: u128 = 0;
+            $(
+                let width = <$ty as $crate::register::PackedField>::WIDTH;
+                assert!(
+                    $offset + width <= <$repr>::BITS,
+                    concat!("field `", stringify!($field), "` does not fit")
+                );
+                let mask = ((1_u128 << width) - 1) << $offset;
+                assert!(
+                    used & mask == 0,
+                    concat!("field `", stringify!($field), "` overlaps another field")
+                );
+                used |= mask;
+            )+
+            let _ = used;
+        };
+
+        impl $name {
+            pub fn pack(&self) -> $repr {
+                let mut raw: $repr = 0;
+                $(
+                    raw |= (<$ty as $crate::register::PackedField>::to_raw(self.$field) as $repr)
+                        << $offset;
+                )+
+                raw
+            }
+
+            /// Bits not covered by a field are ignored.
+            pub fn unpack(raw: $repr) -> Self {
+                Self {
+                    $(
+                        $field: <$ty as $crate::register::PackedField>::from_raw(
+                            (raw >> $offset) as u8,
+                        ),
+                    )+
+                }
+            }
+        }
+
+        impl ::core::convert::From<$repr> for $name {
+            fn from(raw: $repr) -> Self {
+                Self::unpack(raw)
+            }
+        }
+
+        impl ::core::convert::From<$name> for $repr {
+            fn from(fields: $name) -> Self {
+                fields.pack()
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOW: NibbleField<u32> = NibbleField::new(0);
+    const TOP: NibbleField<u32> = NibbleField::new(7);
+
+    #[test]
+    fn get_set() {
+        let mut reg = Register::new(0xa000_0005_u32);
+        assert_eq!(reg.get(LOW), U4::n(5));
+        assert_eq!(reg.get(TOP), U4::n(0xa));
+
+        reg.set(TOP, U4::n(1));
+        reg.modify(LOW, |n| n + U4::n(1));
+        assert_eq!(reg.value(), 0x1000_0006);
+    }
+
+    #[test]
+    fn rmw() {
+        let hw = core::cell::Cell::new(0x12_u8);
+        read_modify_write(
+            || hw.get(),
+            |v| hw.set(v),
+            |reg| reg.set(NibbleField::new(1), U4::n(0xf)),
+        );
+        assert_eq!(hw.get(), 0xf2);
+    }
+
+    nibble_fields! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        struct Status: u8 {
+            mode @ 4: U4,
+            ready @ 3: bool,
+            error @ 0: bool,
+        }
+    }
+
+    nibble_fields! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        struct Offsets: u8 {
+            x @ 4: I4,
+            y @ 0: I4,
+        }
+    }
+
+    #[test]
+    fn packed_fields() {
+        let s = Status {
+            mode: U4::n(0xa),
+            ready: true,
+            error: false,
+        };
+        assert_eq!(s.pack(), 0xa8);
+        assert_eq!(Status::unpack(0xa8), s);
+        // Bits 1 and 2 belong to no field.
+        assert_eq!(Status::from(0x57_u8), Status::unpack(0x51));
+        assert_eq!(u8::from(Status::unpack(0x57)), 0x51);
+
+        let o = Offsets::unpack(0x8f);
+        assert_eq!((o.x, o.y), (I4::MIN, I4::new(-1).unwrap()));
+        assert_eq!(o.pack(), 0x8f);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range() {
+        NibbleField::<u8>::new(2);
+    }
+}
@@ -0,0 +1,124 @@
+//! rkyv support.
+//!
+//! A `U4` archives as a single byte, so a `Vec<U4>` archives as a plain byte
+//! array that can be accessed in place, e.g. from a memory-mapped file.
+//! Validation rejects bytes above `0xf`.
+
+use core::fmt;
+
+use ::rkyv::{
+    bytecheck::CheckBytes,
+    rancor::{fail, Fallible, Source},
+    traits::NoUndef,
+    Archive, Deserialize, Place, Portable, Serialize,
+};
+
+use crate::U4;
+
+/// The archived form of a [`U4`]: one byte holding the value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct ArchivedU4(u8);
+
+impl ArchivedU4 {
+    pub fn get(self) -> U4 {
+        U4::from_u8(self.0)
+    }
+}
+
+impl From<ArchivedU4> for U4 {
+    fn from(n: ArchivedU4) -> Self {
+        n.get()
+    }
+}
+
+impl PartialEq<U4> for ArchivedU4 {
+    fn eq(&self, other: &U4) -> bool {
+        self.0 == other.to_u8()
+    }
+}
+
+// SAFETY: `ArchivedU4` is a transparent wrapper around `u8`, which has no
+// padding, alignment 1 and the same layout on every platform.
+unsafe impl Portable for ArchivedU4 {}
+
+// SAFETY: a single `u8` has no padding bytes.
+unsafe impl NoUndef for ArchivedU4 {}
+
+#[derive(Debug)]
+struct OutOfRange(u8);
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "archived U4 byte {:#04x} is out of range", self.0)
+    }
+}
+
+impl core::error::Error for OutOfRange {}
+
+// SAFETY: every in-range byte is a valid `ArchivedU4`, and out-of-range
+// bytes are rejected.
+unsafe impl<C> CheckBytes<C> for ArchivedU4
+where
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    unsafe fn check_bytes(value: *const Self, _: &mut C) -> Result<(), C::Error> {
+        // SAFETY: the caller guarantees `value` points to one initialized byte.
+        let byte = unsafe { (*value).0 };
+        if byte > 0xf {
+            fail!(OutOfRange(byte));
+        }
+        Ok(())
+    }
+}
+
+impl Archive for U4 {
+    type Archived = ArchivedU4;
+    type Resolver = ();
+
+    fn resolve(&self, _: (), out: Place<ArchivedU4>) {
+        out.write(ArchivedU4(self.to_u8()));
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for U4 {
+    fn serialize(&self, _: &mut S) -> Result<(), S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<U4, D> for ArchivedU4 {
+    fn deserialize(&self, _: &mut D) -> Result<U4, D::Error> {
+        Ok(self.get())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use ::rkyv::{rancor::Error, vec::ArchivedVec};
+
+    use super::*;
+
+    #[test]
+    fn zero_copy() {
+        let nibbles: Vec<U4> = (0..16).map(U4::n).collect();
+        let bytes = ::rkyv::to_bytes::<Error>(&nibbles).unwrap();
+        let archived = ::rkyv::access::<ArchivedVec<ArchivedU4>, Error>(&bytes).unwrap();
+        assert_eq!(archived.len(), 16);
+        assert_eq!(archived[10], U4::n(10));
+        let back: Vec<U4> = ::rkyv::deserialize::<_, Error>(archived).unwrap();
+        assert_eq!(back, nibbles);
+    }
+
+    #[test]
+    fn rejects_out_of_range() {
+        let mut bytes = ::rkyv::to_bytes::<Error>(&U4::n(3)).unwrap();
+        assert_eq!(
+            ::rkyv::access::<ArchivedU4, Error>(&bytes).unwrap().get(),
+            U4::n(3)
+        );
+        bytes[0] = 0x13;
+        assert!(::rkyv::access::<ArchivedU4, Error>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! BMP 4-bit run-length encoding (`BI_RLE4`).
+//!
+//! A stream is a sequence of two-byte groups. A non-zero first byte `n`
+//! encodes a run of `n` pixels alternating between the high and low nibble
+//! of the second byte. A zero first byte is an escape: `0` ends a line, `1`
+//! ends the bitmap, `2` is followed by a horizontal and vertical offset to
+//! skip, and `3..=255` is a count of literal pixels packed two per byte and
+//! padded to a 16-bit boundary.
+//!
+//! Rows are handled in stream order; BMP stores them bottom-up, which is up
+//! to the caller.
+
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use crate::U4;
+
+/// The stream could not be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rle4Error {
+    /// The input ended inside a group.
+    Truncated,
+    /// A run, literal or offset went past the edge of the bitmap.
+    OutOfBounds,
+}
+
+impl Display for Rle4Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => f.write_str("truncated RLE4 stream"),
+            Self::OutOfBounds => f.write_str("RLE4 stream writes outside the bitmap"),
+        }
+    }
+}
+
+impl core::error::Error for Rle4Error {}
+
+/// Decodes into `width * height` pixels in stream row order. Pixels the
+/// stream skips over are left zero, and decoding stops at the end-of-bitmap
+/// marker or the end of the input.
+pub fn decode(data: &[u8], width: usize, height: usize) -> Result<Vec<U4>, Rle4Error> {
+    let mut pixels = Vec::from_iter(core::iter::repeat_n(U4::MIN, width * height));
+    let (mut x, mut y) = (0, 0);
+    let mut put = |x: &mut usize, y: usize, n: U4| {
+        if *x >= width || y >= height {
+            return Err(Rle4Error::OutOfBounds);
+        }
+        pixels[y * width + *x] = n;
+        *x += 1;
+        Ok(())
+    };
+
+    let mut bytes = data.iter().copied();
+    while let Some(count) = bytes.next() {
+        let second = bytes.next().ok_or(Rle4Error::Truncated)?;
+        match (count, second) {
+            (0, 0) => {
+                x = 0;
+                y += 1;
+            }
+            (0, 1) => break,
+            (0, 2) => {
+                let dx = bytes.next().ok_or(Rle4Error::Truncated)?;
+                let dy = bytes.next().ok_or(Rle4Error::Truncated)?;
+                x += dx as usize;
+                y += dy as usize;
+            }
+            (0, n) => {
+                let packed = (n as usize).div_ceil(2);
+                for i in 0..packed.next_multiple_of(2) {
+                    let b = bytes.next().ok_or(Rle4Error::Truncated)?;
+                    if i < packed {
+                        put(&mut x, y, U4::from_u8(b >> 4))?;
+                        if 2 * i + 1 < n as usize {
+                            put(&mut x, y, U4::from_u8(b))?;
+                        }
+                    }
+                }
+            }
+            (n, pair) => {
+                for i in 0..n {
+                    let shift = if i % 2 == 0 { 4 } else { 0 };
+                    put(&mut x, y, U4::from_u8(pair >> shift))?;
+                }
+            }
+        }
+    }
+    Ok(pixels)
+}
+
+/// How many pixels from `start` alternate between `row[start]` and
+/// `row[start + 1]`.
+fn run_len(row: &[U4], start: usize) -> usize {
+    row[start..]
+        .iter()
+        .enumerate()
+        .take_while(|&(i, &p)| p == row[start + i % 2])
+        .count()
+}
+
+/// Encodes rows of `width` pixels, ending each row with an end-of-line
+/// marker and the last with end-of-bitmap.
+///
+/// # Panics
+///
+/// Panics if `width` is zero or does not divide `pixels.len()`.
+pub fn encode(pixels: &[U4], width: usize) -> Vec<u8> {
+    assert!(
+        width > 0 && pixels.len().is_multiple_of(width),
+        "pixels must be whole rows"
+    );
+    let mut out = Vec::new();
+    for row in pixels.chunks(width) {
+        let mut x = 0;
+        while x < row.len() {
+            let run = run_len(row, x).min(255);
+            if run >= 4 || row.len() - x <= 2 {
+                x += push_run(&mut out, &row[x..x + run]);
+                continue;
+            }
+            let mut end = x;
+            while end < row.len() && end - x < 255 && run_len(row, end) < 4 {
+                end += 1;
+            }
+            if end - x < 3 {
+                // Too short for absolute mode.
+                x += push_run(&mut out, &row[x..x + run.min(end - x)]);
+                continue;
+            }
+            out.extend([0, (end - x) as u8]);
+            let start = out.len();
+            out.extend(
+                row[x..end]
+                    .chunks(2)
+                    .map(|p| (p[0].to_u8() << 4) | p.get(1).map_or(0, U4::to_u8)),
+            );
+            if (out.len() - start) % 2 == 1 {
+                out.push(0);
+            }
+            x = end;
+        }
+        out.extend([0, 0]);
+    }
+    match out.last_mut() {
+        Some(last) => *last = 1,
+        None => out.extend([0, 1]),
+    }
+    out
+}
+
+/// Emits `run` as one encoded group, returning its length.
+fn push_run(out: &mut Vec<u8>, run: &[U4]) -> usize {
+    let lo = run.get(1).map_or(0, U4::to_u8);
+    out.extend([run.len() as u8, (run[0].to_u8() << 4) | lo]);
+    run.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nibbles(s: &str) -> Vec<U4> {
+        s.bytes().map(|c| U4::from_hex_digit(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn decode_reference() {
+        // The example from the BMP documentation.
+        let data = [
+            0x03, 0x04, 0x05, 0x06, 0x00, 0x06, 0x45, 0x56, 0x67, 0x00, 0x04, 0x78, 0x00, 0x02,
+            0x05, 0x01, 0x04, 0x78, 0x00, 0x00, 0x09, 0x1e, 0x00, 0x01,
+        ];
+        let pixels = decode(&data, 32, 3).unwrap();
+        assert_eq!(pixels[..18], nibbles("040060604556677878"));
+        assert_eq!(pixels[32 + 23..32 + 27], nibbles("7878"));
+        assert_eq!(pixels[64..73], nibbles("1e1e1e1e1"));
+        assert!(pixels[73..].iter().all(|&p| p == U4::MIN));
+    }
+
+    #[test]
+    fn round_trip() {
+        let rows = [
+            "0000000000",
+            "0123456789",
+            "abababab12",
+            "1122334455",
+            "fedcba9878",
+            "7777777770",
+        ];
+        let pixels: Vec<U4> = rows.iter().flat_map(|r| nibbles(r)).collect();
+        let encoded = encode(&pixels, 10);
+        assert_eq!(encoded[encoded.len() - 2..], [0, 1]);
+        assert!(encoded.len() < pixels.len());
+        assert_eq!(decode(&encoded, 10, 6).unwrap(), pixels);
+
+        let odd = nibbles("123");
+        assert_eq!(encode(&odd, 3), [0, 3, 0x12, 0x30, 0, 1]);
+        assert_eq!(decode(&encode(&odd, 3), 3, 1).unwrap(), odd);
+        assert_eq!(encode(&[], 4), [0, 1]);
+
+        // Long literals and runs split at 255 pixels, across many widths.
+        let mut state = 1_u32;
+        let noise: Vec<U4> = (0..600)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                U4::from_u8((state >> 16) as u8)
+            })
+            .collect();
+        for width in [1, 2, 3, 5, 40, 300, 600] {
+            assert_eq!(
+                decode(&encode(&noise, width), width, 600 / width).unwrap(),
+                noise
+            );
+        }
+        let flat = [U4::n(4); 600];
+        assert_eq!(decode(&encode(&flat, 600), 600, 1).unwrap(), flat);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(decode(&[5], 8, 1), Err(Rle4Error::Truncated));
+        assert_eq!(decode(&[0, 3, 0x12], 8, 1), Err(Rle4Error::Truncated));
+        assert_eq!(decode(&[9, 0x11], 8, 1), Err(Rle4Error::OutOfBounds));
+        assert_eq!(decode(&[0, 0, 1, 0x11], 8, 1), Err(Rle4Error::OutOfBounds));
+    }
+}
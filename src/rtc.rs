@@ -0,0 +1,222 @@
+//! Time registers of DS1307 and PCF8563 class real-time clocks.
+//!
+//! Both chips keep each field as packed BCD, tens in the high nibble, with
+//! control bits in the bits a field never uses.
+
+use core::fmt::{self, Display, Formatter};
+
+use crate::bcd::{pack_byte, unpack_byte, InvalidDigit};
+
+/// A register could not be converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtcError {
+    /// A nibble of a BCD field is not a decimal digit.
+    InvalidDigit(u8),
+    /// A field is outside its range, e.g. minute 60 or month 0.
+    OutOfRange,
+}
+
+impl Display for RtcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDigit(n) => write!(f, "{n:#x} is not a BCD digit"),
+            Self::OutOfRange => f.write_str("RTC field out of range"),
+        }
+    }
+}
+
+impl core::error::Error for RtcError {}
+
+impl From<InvalidDigit> for RtcError {
+    fn from(e: InvalidDigit) -> Self {
+        Self::InvalidDigit(e.0)
+    }
+}
+
+/// How the hours register counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HourMode {
+    #[default]
+    H24,
+    /// 1 to 12 with a PM flag in bit 5 and bit 6 set.
+    H12,
+}
+
+fn encode(value: u8, range: core::ops::RangeInclusive<u8>) -> Result<u8, RtcError> {
+    if !range.contains(&value) {
+        return Err(RtcError::OutOfRange);
+    }
+    pack_byte(value).ok_or(RtcError::OutOfRange)
+}
+
+fn decode(reg: u8, mask: u8, range: core::ops::RangeInclusive<u8>) -> Result<u8, RtcError> {
+    let value = unpack_byte(reg & mask)?;
+    if range.contains(&value) {
+        Ok(value)
+    } else {
+        Err(RtcError::OutOfRange)
+    }
+}
+
+/// Encodes `hour` (`0..=23`) in either mode.
+pub fn encode_hours(hour: u8, mode: HourMode) -> Result<u8, RtcError> {
+    match mode {
+        HourMode::H24 => encode(hour, 0..=23),
+        HourMode::H12 => {
+            let pm = match hour {
+                0..=11 => 0,
+                12..=23 => 0x20,
+                _ => return Err(RtcError::OutOfRange),
+            };
+            let h12 = match hour % 12 {
+                0 => 12,
+                h => h,
+            };
+            Ok(0x40 | pm | encode(h12, 1..=12)?)
+        }
+    }
+}
+
+/// Decodes an hours register in either mode to `0..=23`.
+pub fn decode_hours(reg: u8) -> Result<u8, RtcError> {
+    if reg & 0x40 == 0 {
+        return decode(reg, 0x3f, 0..=23);
+    }
+    let h12 = decode(reg, 0x1f, 1..=12)?;
+    let pm = reg & 0x20 != 0;
+    Ok(h12 % 12 + if pm { 12 } else { 0 })
+}
+
+/// A calendar time as the clock chips hold it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateTime {
+    /// Year within the century, `0..=99`.
+    pub year: u8,
+    /// `1..=12`.
+    pub month: u8,
+    /// Day of the month, `1..=31`.
+    pub day: u8,
+    /// Day of the week, `0..=6`.
+    pub weekday: u8,
+    /// `0..=23`.
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl DateTime {
+    /// DS1307 registers `0x00..=0x06`, with the clock-halt bit clear.
+    pub fn to_ds1307(&self, mode: HourMode) -> Result<[u8; 7], RtcError> {
+        Ok([
+            encode(self.second, 0..=59)?,
+            encode(self.minute, 0..=59)?,
+            encode_hours(self.hour, mode)?,
+            encode(self.weekday.saturating_add(1), 1..=7)?,
+            encode(self.day, 1..=31)?,
+            encode(self.month, 1..=12)?,
+            encode(self.year, 0..=99)?,
+        ])
+    }
+
+    /// Reads DS1307 registers `0x00..=0x06`, ignoring the clock-halt bit.
+    pub fn from_ds1307(regs: [u8; 7]) -> Result<Self, RtcError> {
+        Ok(Self {
+            second: decode(regs[0], 0x7f, 0..=59)?,
+            minute: decode(regs[1], 0x7f, 0..=59)?,
+            hour: decode_hours(regs[2])?,
+            weekday: decode(regs[3], 0x07, 1..=7)? - 1,
+            day: decode(regs[4], 0x3f, 1..=31)?,
+            month: decode(regs[5], 0x1f, 1..=12)?,
+            year: decode(regs[6], 0xff, 0..=99)?,
+        })
+    }
+
+    /// PCF8563 registers `0x02..=0x08`, with the voltage-low and century
+    /// bits clear.
+    pub fn to_pcf8563(&self) -> Result<[u8; 7], RtcError> {
+        Ok([
+            encode(self.second, 0..=59)?,
+            encode(self.minute, 0..=59)?,
+            encode(self.hour, 0..=23)?,
+            encode(self.day, 1..=31)?,
+            encode(self.weekday, 0..=6)?,
+            encode(self.month, 1..=12)?,
+            encode(self.year, 0..=99)?,
+        ])
+    }
+
+    /// Reads PCF8563 registers `0x02..=0x08`, ignoring the voltage-low and
+    /// century bits.
+    pub fn from_pcf8563(regs: [u8; 7]) -> Result<Self, RtcError> {
+        Ok(Self {
+            second: decode(regs[0], 0x7f, 0..=59)?,
+            minute: decode(regs[1], 0x7f, 0..=59)?,
+            hour: decode(regs[2], 0x3f, 0..=23)?,
+            day: decode(regs[3], 0x3f, 1..=31)?,
+            weekday: decode(regs[4], 0x07, 0..=6)?,
+            month: decode(regs[5], 0x1f, 1..=12)?,
+            year: decode(regs[6], 0xff, 0..=99)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T: DateTime = DateTime {
+        year: 24,
+        month: 2,
+        day: 29,
+        weekday: 4,
+        hour: 23,
+        minute: 59,
+        second: 7,
+    };
+
+    #[test]
+    fn hours() {
+        assert_eq!(encode_hours(0, HourMode::H12), Ok(0x52));
+        assert_eq!(encode_hours(12, HourMode::H12), Ok(0x72));
+        assert_eq!(encode_hours(23, HourMode::H12), Ok(0x71));
+        assert_eq!(encode_hours(24, HourMode::H24), Err(RtcError::OutOfRange));
+        for h in 0..24 {
+            for mode in [HourMode::H24, HourMode::H12] {
+                assert_eq!(decode_hours(encode_hours(h, mode).unwrap()), Ok(h));
+            }
+        }
+    }
+
+    #[test]
+    fn ds1307() {
+        let regs = T.to_ds1307(HourMode::H24).unwrap();
+        assert_eq!(regs, [0x07, 0x59, 0x23, 0x05, 0x29, 0x02, 0x24]);
+        // The clock-halt bit does not affect the seconds.
+        let mut halted = regs;
+        halted[0] |= 0x80;
+        assert_eq!(DateTime::from_ds1307(halted), Ok(T));
+        assert_eq!(
+            DateTime::from_ds1307(T.to_ds1307(HourMode::H12).unwrap()),
+            Ok(T)
+        );
+    }
+
+    #[test]
+    fn pcf8563() {
+        let mut regs = T.to_pcf8563().unwrap();
+        assert_eq!(regs, [0x07, 0x59, 0x23, 0x29, 0x04, 0x02, 0x24]);
+        regs[0] |= 0x80;
+        regs[5] |= 0x80;
+        assert_eq!(DateTime::from_pcf8563(regs), Ok(T));
+
+        regs[1] = 0x5a;
+        assert_eq!(
+            DateTime::from_pcf8563(regs),
+            Err(RtcError::InvalidDigit(0xa))
+        );
+        regs[1] = 0x60;
+        assert_eq!(DateTime::from_pcf8563(regs), Err(RtcError::OutOfRange));
+        let bad = DateTime { month: 13, ..T };
+        assert_eq!(bad.to_pcf8563(), Err(RtcError::OutOfRange));
+    }
+}
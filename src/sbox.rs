@@ -0,0 +1,353 @@
+use core::fmt::{Display, Formatter};
+
+use crate::U4;
+
+/// A bijective 4-bit S-box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SBox4 {
+    table: [U4; 16],
+}
+
+/// The table given to an [`SBox4`] constructor is not a permutation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotBijective;
+
+impl Display for NotBijective {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "table is not a permutation of 0..16")
+    }
+}
+
+impl core::error::Error for NotBijective {}
+
+pub(crate) const fn is_permutation(table: &[u8; 16]) -> bool {
+    let mut seen = 0_u16;
+    let mut i = 0;
+    while i < 16 {
+        if table[i] > 15 {
+            return false;
+        }
+        seen |= 1 << table[i];
+        i += 1;
+    }
+    seen == u16::MAX
+}
+
+impl SBox4 {
+    pub const IDENTITY: SBox4 =
+        SBox4::from_u8_table([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    /// PRESENT (Bogdanov et al., CHES 2007).
+    pub const PRESENT: SBox4 = SBox4::from_u8_table([
+        0xc, 0x5, 0x6, 0xb, 0x9, 0x0, 0xa, 0xd, 0x3, 0xe, 0xf, 0x8, 0x4, 0x7, 0x1, 0x2,
+    ]);
+    pub const PRESENT_INV: SBox4 = SBox4::from_u8_table([
+        0x5, 0xe, 0xf, 0x8, 0xc, 0x1, 0x2, 0xd, 0xb, 0x4, 0x6, 0x3, 0x0, 0x7, 0x9, 0xa,
+    ]);
+
+    /// GIFT (Banik et al., CHES 2017).
+    pub const GIFT: SBox4 = SBox4::from_u8_table([
+        0x1, 0xa, 0x4, 0xc, 0x6, 0xf, 0x3, 0x9, 0x2, 0xd, 0xb, 0x7, 0x5, 0x0, 0x8, 0xe,
+    ]);
+    pub const GIFT_INV: SBox4 = SBox4::from_u8_table([
+        0xd, 0x0, 0x8, 0x6, 0x2, 0xc, 0x4, 0xb, 0xe, 0x7, 0x1, 0xa, 0x3, 0x9, 0xf, 0x5,
+    ]);
+
+    /// Piccolo (Shibutani et al., CHES 2011).
+    pub const PICCOLO: SBox4 = SBox4::from_u8_table([
+        0xe, 0x4, 0xb, 0x2, 0x3, 0x8, 0x0, 0x9, 0x1, 0xa, 0x7, 0xf, 0x6, 0xc, 0x5, 0xd,
+    ]);
+    pub const PICCOLO_INV: SBox4 = SBox4::from_u8_table([
+        0x6, 0x8, 0x3, 0x4, 0x1, 0xe, 0xc, 0xa, 0x5, 0x7, 0x9, 0x2, 0xd, 0xf, 0x0, 0xb,
+    ]);
+
+    /// The 4-bit S-box of SKINNY-64 (Beierle et al., CRYPTO 2016).
+    pub const SKINNY: SBox4 = SBox4::from_u8_table([
+        0xc, 0x6, 0x9, 0x0, 0x1, 0xa, 0x2, 0xb, 0x3, 0x8, 0x5, 0xd, 0x4, 0xe, 0x7, 0xf,
+    ]);
+    pub const SKINNY_INV: SBox4 = SBox4::from_u8_table([
+        0x3, 0x4, 0x6, 0x8, 0xc, 0xa, 0x1, 0xe, 0x9, 0x2, 0x5, 0x7, 0x0, 0xb, 0xd, 0xf,
+    ]);
+
+    /// PRINCE (Borghoff et al., ASIACRYPT 2012).
+    pub const PRINCE: SBox4 = SBox4::from_u8_table([
+        0xb, 0xf, 0x3, 0x2, 0xa, 0xc, 0x9, 0x1, 0x6, 0x7, 0x8, 0x0, 0xe, 0x5, 0xd, 0x4,
+    ]);
+    pub const PRINCE_INV: SBox4 = SBox4::from_u8_table([
+        0xb, 0x7, 0x3, 0x2, 0xf, 0xd, 0x8, 0x9, 0xa, 0x6, 0x4, 0x0, 0x5, 0xe, 0xc, 0x1,
+    ]);
+
+    /// Midori Sb0 (Banik et al., ASIACRYPT 2015). An involution.
+    pub const MIDORI_SB0: SBox4 = SBox4::from_u8_table([
+        0xc, 0xa, 0xd, 0x3, 0xe, 0xb, 0xf, 0x7, 0x8, 0x9, 0x1, 0x5, 0x0, 0x2, 0x4, 0x6,
+    ]);
+    pub const MIDORI_SB0_INV: SBox4 = SBox4::MIDORI_SB0;
+
+    /// Midori Sb1. An involution.
+    pub const MIDORI_SB1: SBox4 = SBox4::from_u8_table([
+        0x1, 0x0, 0x5, 0x3, 0xe, 0x2, 0xf, 0x7, 0xd, 0xa, 0x9, 0xb, 0xc, 0x8, 0x4, 0x6,
+    ]);
+    pub const MIDORI_SB1_INV: SBox4 = SBox4::MIDORI_SB1;
+
+    pub fn new(table: [U4; 16]) -> Result<Self, NotBijective> {
+        Self::try_from_u8_table(table.map(|n| n.to_u8()))
+    }
+
+    pub fn try_from_u8_table(table: [u8; 16]) -> Result<Self, NotBijective> {
+        if is_permutation(&table) {
+            Ok(Self::from_u8_table(table))
+        } else {
+            Err(NotBijective)
+        }
+    }
+
+    /// Builds an S-box in const context, panicking (a compile error in a
+    /// `const` item) if `table` is not a permutation of `0..16`.
+    pub const fn from_u8_table(table: [u8; 16]) -> Self {
+        assert!(is_permutation(&table), "S-box table is not a permutation");
+        let mut out = [U4::MIN; 16];
+        let mut i = 0;
+        while i < 16 {
+            out[i] = U4::from_u8(table[i]);
+            i += 1;
+        }
+        Self { table: out }
+    }
+
+    pub fn identity() -> Self {
+        Self::IDENTITY
+    }
+
+    pub fn apply(&self, n: U4) -> U4 {
+        self.table[n.to_u8() as usize]
+    }
+
+    pub fn inverse(&self) -> Self {
+        let mut table = [U4::MIN; 16];
+        for (i, n) in self.table.iter().enumerate() {
+            table[n.to_u8() as usize] = U4::from_u8(i as u8);
+        }
+        Self { table }
+    }
+
+    /// The S-box `x -> self(other(x))`.
+    pub fn compose(&self, other: &Self) -> Self {
+        Self {
+            table: other.table.map(|n| self.apply(n)),
+        }
+    }
+
+    /// The S-box `x -> other(self(x))`.
+    pub fn then(&self, other: &Self) -> Self {
+        other.compose(self)
+    }
+
+    pub fn table(&self) -> &[U4; 16] {
+        &self.table
+    }
+
+    pub fn to_u8_table(&self) -> [u8; 16] {
+        self.table.map(|n| n.to_u8())
+    }
+
+    /// Difference distribution table: `ddt[a][b]` counts the inputs `x` with
+    /// `S(x) ^ S(x ^ a) == b`.
+    pub fn ddt(&self) -> [[u8; 16]; 16] {
+        let s = self.to_u8_table();
+        let mut ddt = [[0; 16]; 16];
+        for (a, row) in ddt.iter_mut().enumerate() {
+            for x in 0..16 {
+                row[(s[x] ^ s[x ^ a]) as usize] += 1;
+            }
+        }
+        ddt
+    }
+
+    /// Linear approximation table in bias form: `lat[a][b]` is the number of
+    /// inputs `x` with `a·x == b·S(x)`, minus 8.
+    pub fn lat(&self) -> [[i8; 16]; 16] {
+        let s = self.to_u8_table();
+        let mut lat = [[0; 16]; 16];
+        for (a, row) in lat.iter_mut().enumerate() {
+            for (b, entry) in row.iter_mut().enumerate() {
+                let matches = (0..16)
+                    .filter(|&x| (x & a).count_ones() % 2 == (s[x] as usize & b).count_ones() % 2)
+                    .count();
+                *entry = matches as i8 - 8;
+            }
+        }
+        lat
+    }
+
+    /// Largest DDT entry over all non-zero input differences.
+    pub fn differential_uniformity(&self) -> u8 {
+        self.ddt()[1..]
+            .iter()
+            .flat_map(|row| row.iter().copied())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Largest absolute LAT bias over all non-zero output masks.
+    pub fn linearity(&self) -> u8 {
+        self.lat()
+            .iter()
+            .flat_map(|row| row[1..].iter().map(|b| b.unsigned_abs()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Algebraic normal form of output bit `bit`: bit `m` of the result is the
+    /// coefficient of the monomial whose variables are the set bits of `m`.
+    pub fn anf(&self, bit: u32) -> u16 {
+        let s = self.to_u8_table();
+        let mut coeffs: [u8; 16] = s.map(|y| (y >> bit) & 1);
+        for step in [1, 2, 4, 8] {
+            for m in 0..16 {
+                if m & step != 0 {
+                    coeffs[m] ^= coeffs[m ^ step];
+                }
+            }
+        }
+        coeffs
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (m, &c)| acc | (c as u16) << m)
+    }
+
+    /// Highest algebraic degree over the four coordinate functions.
+    pub fn algebraic_degree(&self) -> u32 {
+        (0..U4::BITS as u32)
+            .flat_map(|bit| {
+                let anf = self.anf(bit);
+                (0..16_u32).filter(move |m| anf & (1 << m) != 0)
+            })
+            .map(|m| m.count_ones())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// `min(wt(a ^ b) + wt(S(a) ^ S(b)))` over all distinct inputs `a`, `b`.
+    pub fn differential_branch_number(&self) -> u32 {
+        let s = self.to_u8_table();
+        let mut branch = u32::MAX;
+        for a in 0..16_usize {
+            for b in 0..16_usize {
+                if a != b {
+                    let w = (a ^ b).count_ones() + (s[a] ^ s[b]).count_ones();
+                    branch = branch.min(w);
+                }
+            }
+        }
+        branch
+    }
+
+    /// `min(wt(a) + wt(b))` over all masks with a non-zero LAT entry and `b != 0`.
+    pub fn linear_branch_number(&self) -> u32 {
+        let lat = self.lat();
+        let mut branch = u32::MAX;
+        for (a, row) in lat.iter().enumerate() {
+            for (b, &bias) in row.iter().enumerate().skip(1) {
+                if bias != 0 {
+                    branch = branch.min(a.count_ones() + b.count_ones());
+                }
+            }
+        }
+        branch
+    }
+}
+
+impl TryFrom<[U4; 16]> for SBox4 {
+    type Error = NotBijective;
+
+    fn try_from(table: [U4; 16]) -> Result<Self, Self::Error> {
+        Self::new(table)
+    }
+}
+
+impl TryFrom<[u8; 16]> for SBox4 {
+    type Error = NotBijective;
+
+    fn try_from(table: [u8; 16]) -> Result<Self, Self::Error> {
+        Self::try_from_u8_table(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const S: SBox4 = SBox4::PRESENT;
+
+    #[test]
+    fn validates() {
+        assert_eq!(SBox4::try_from([0; 16]), Err(NotBijective));
+        assert_eq!(SBox4::try_from([16; 16]), Err(NotBijective));
+        assert_eq!(SBox4::try_from(S.to_u8_table()), Ok(S));
+    }
+
+    #[test]
+    fn inverse() {
+        let inv = S.inverse();
+        for i in 0..16 {
+            let n = U4::n(i);
+            assert_eq!(inv.apply(S.apply(n)), n);
+        }
+        assert_eq!(S.compose(&inv), SBox4::identity());
+    }
+
+    #[test]
+    fn compose() {
+        let twice = S.then(&S);
+        for i in 0..16 {
+            let n = U4::n(i);
+            assert_eq!(twice.apply(n), S.apply(S.apply(n)));
+        }
+    }
+
+    #[test]
+    fn analysis() {
+        assert_eq!(S.differential_uniformity(), 4);
+        assert_eq!(S.linearity(), 4);
+        assert_eq!(S.algebraic_degree(), 3);
+        assert_eq!(S.differential_branch_number(), 3);
+        assert_eq!(S.linear_branch_number(), 2);
+
+        let ddt = S.ddt();
+        assert_eq!(ddt[0][0], 16);
+        assert!(ddt
+            .iter()
+            .all(|row| row.iter().map(|&c| c as u32).sum::<u32>() == 16));
+        assert_eq!(S.lat()[0][0], 8);
+    }
+
+    #[test]
+    fn identity_analysis() {
+        let id = SBox4::identity();
+        assert_eq!(id.differential_uniformity(), 16);
+        assert_eq!(id.linearity(), 8);
+        assert_eq!(id.algebraic_degree(), 1);
+        assert_eq!(id.anf(0), 0b10);
+    }
+
+    #[test]
+    fn known_inverses() {
+        let known = [
+            (SBox4::PRESENT, SBox4::PRESENT_INV),
+            (SBox4::GIFT, SBox4::GIFT_INV),
+            (SBox4::PICCOLO, SBox4::PICCOLO_INV),
+            (SBox4::SKINNY, SBox4::SKINNY_INV),
+            (SBox4::PRINCE, SBox4::PRINCE_INV),
+            (SBox4::MIDORI_SB0, SBox4::MIDORI_SB0_INV),
+            (SBox4::MIDORI_SB1, SBox4::MIDORI_SB1_INV),
+        ];
+        for (s, inv) in known {
+            assert_eq!(s.inverse(), inv);
+            assert_eq!(s.linearity(), 4);
+        }
+        // GIFT trades differential uniformity for a cheaper circuit.
+        assert_eq!(SBox4::GIFT.differential_uniformity(), 6);
+        assert_eq!(SBox4::PRINCE.differential_uniformity(), 4);
+        assert_eq!(SBox4::MIDORI_SB0.then(&SBox4::MIDORI_SB0), SBox4::IDENTITY);
+    }
+}
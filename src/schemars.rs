@@ -0,0 +1,73 @@
+use alloc::borrow::Cow;
+
+use ::schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::{bcd::BcdDigit, U4};
+
+/// Matches the default serde representation, an integer in `0..=15`.
+impl JsonSchema for U4 {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "U4".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "u4::U4".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "integer",
+            "minimum": 0,
+            "maximum": 15,
+        })
+    }
+}
+
+impl JsonSchema for BcdDigit {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "BcdDigit".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "u4::bcd::BcdDigit".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "integer",
+            "minimum": 0,
+            "maximum": 9,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::schemars::schema_for;
+
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let schema = schema_for!(U4);
+        assert_eq!(schema.get("type").unwrap(), "integer");
+        assert_eq!(schema.get("minimum").unwrap(), 0);
+        assert_eq!(schema.get("maximum").unwrap(), 15);
+        assert_eq!(schema_for!(BcdDigit).get("maximum").unwrap(), 9);
+    }
+
+    #[test]
+    fn collections() {
+        let schema = schema_for!(Vec<U4>);
+        assert_eq!(schema.get("type").unwrap(), "array");
+        assert_eq!(schema.get("items").unwrap()["maximum"], 15);
+    }
+}
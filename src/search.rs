@@ -0,0 +1,96 @@
+//! Searching packed nibble buffers a word at a time.
+//!
+//! Nibbles are indexed high nibble first: nibble `2 * i` is the high half of
+//! byte `i` and nibble `2 * i + 1` the low half.
+
+use crate::U4;
+
+const LOW: u64 = 0x7777_7777_7777_7777;
+const HIGH: u64 = 0x8888_8888_8888_8888;
+pub(crate) const ONES: u64 = 0x1111_1111_1111_1111;
+
+/// Sets the high bit of every zero nibble of `x`, and no other bits. Adding
+/// 7 to the low three bits cannot carry into the next nibble, so unlike the
+/// byte-wise `haszero` trick this has no false positives.
+pub(crate) fn zero_nibbles(x: u64) -> u64 {
+    !(((x & LOW) + LOW) | x) & HIGH
+}
+
+fn matches(chunk: &[u8], needle: U4) -> u64 {
+    let word = u64::from_be_bytes(chunk.try_into().unwrap());
+    zero_nibbles(word ^ (needle.to_u8() as u64 * ONES))
+}
+
+fn nibbles(bytes: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    bytes.iter().flat_map(|b| [b >> 4, b & 0xf])
+}
+
+/// The index of the first nibble of `haystack` equal to `needle`.
+pub fn find_nibble(haystack: &[u8], needle: U4) -> Option<usize> {
+    let chunks = haystack.chunks_exact(8);
+    let tail = chunks.remainder();
+    for (i, chunk) in chunks.enumerate() {
+        let m = matches(chunk, needle);
+        if m != 0 {
+            return Some(i * 16 + m.leading_zeros() as usize / 4);
+        }
+    }
+    let offset = (haystack.len() - tail.len()) * 2;
+    nibbles(tail)
+        .position(|n| n == needle.to_u8())
+        .map(|i| offset + i)
+}
+
+/// The number of nibbles of `haystack` equal to `needle`.
+pub fn count_nibble(haystack: &[u8], needle: U4) -> usize {
+    let chunks = haystack.chunks_exact(8);
+    let tail = chunks.remainder();
+    let words: usize = chunks
+        .map(|chunk| matches(chunk, needle).count_ones() as usize)
+        .sum();
+    words + nibbles(tail).filter(|&n| n == needle.to_u8()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive(haystack: &[u8]) -> Vec<u8> {
+        nibbles(haystack).collect()
+    }
+
+    #[test]
+    fn zero_nibbles_exact() {
+        for x in [0, u64::MAX, HIGH, LOW, ONES, 0x0f00_f0f0_0000_1000] {
+            let expected = (0..16)
+                .filter(|i| (x >> (i * 4)) & 0xf == 0)
+                .fold(0, |m, i| m | 8 << (i * 4));
+            assert_eq!(zero_nibbles(x), expected, "{x:#x}");
+        }
+    }
+
+    #[test]
+    fn find_and_count() {
+        let haystack: Vec<u8> = (0..37_u32).map(|i| (i * 73 % 251) as u8).collect();
+        let all = naive(&haystack);
+        for needle in (0..16).map(U4::n) {
+            for start in [0, 1, 8, 9, 16, 30] {
+                let h = &haystack[start..];
+                let expected = all[start * 2..].iter().position(|&n| n == needle.to_u8());
+                assert_eq!(find_nibble(h, needle), expected);
+                let count = all[start * 2..]
+                    .iter()
+                    .filter(|&&n| n == needle.to_u8())
+                    .count();
+                assert_eq!(count_nibble(h, needle), count);
+            }
+        }
+        assert_eq!(find_nibble(&[], U4::n(0)), None);
+        assert_eq!(find_nibble(&[0x12; 8], U4::n(2)), Some(1));
+        assert_eq!(find_nibble(&[0x11; 9], U4::n(1)), Some(0));
+        let mut tail_only = [0x11; 9];
+        tail_only[8] = 0x1a;
+        assert_eq!(find_nibble(&tail_only, U4::n(0xa)), Some(17));
+        assert_eq!(count_nibble(&[0xff; 20], U4::MAX), 40);
+    }
+}
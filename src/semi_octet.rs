@@ -0,0 +1,105 @@
+//! GSM semi-octet numbers (3GPP TS 23.040).
+//!
+//! Phone numbers in SMS PDUs are BCD with the nibbles of each byte swapped:
+//! the first digit is in the low nibble. An odd-length number is padded
+//! with `F` in the last high nibble. Besides `0..=9`, the nibbles `A..=E`
+//! stand for `*`, `#`, `a`, `b` and `c`.
+//!
+//! ```
+//! use u4::semi_octet;
+//!
+//! let encoded = semi_octet::encode("12345").unwrap();
+//! assert_eq!(encoded, [0x21, 0x43, 0xf5]);
+//! assert_eq!(semi_octet::decode(&encoded).unwrap(), "12345");
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+const DIGITS: &[u8; 15] = b"0123456789*#abc";
+
+/// The number or encoding is not valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemiOctetError {
+    /// The character has no semi-octet code.
+    InvalidChar(char),
+    /// An `F` filler appears before the last nibble.
+    MisplacedFiller { index: usize },
+}
+
+impl Display for SemiOctetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidChar(c) => write!(f, "{c:?} has no semi-octet code"),
+            Self::MisplacedFiller { index } => write!(f, "filler nibble at index {index}"),
+        }
+    }
+}
+
+impl core::error::Error for SemiOctetError {}
+
+fn code(c: char) -> Result<u8, SemiOctetError> {
+    DIGITS
+        .iter()
+        .position(|&d| d as char == c.to_ascii_lowercase() && c.is_ascii())
+        .map(|i| i as u8)
+        .ok_or(SemiOctetError::InvalidChar(c))
+}
+
+/// Encodes the digits of `number`, two per byte, low nibble first.
+pub fn encode(number: &str) -> Result<Vec<u8>, SemiOctetError> {
+    let codes = number.chars().map(code).collect::<Result<Vec<u8>, _>>()?;
+    Ok(codes
+        .chunks(2)
+        .map(|pair| pair.get(1).copied().unwrap_or(0xf) << 4 | pair[0])
+        .collect())
+}
+
+/// Decodes semi-octets, stopping at an `F` filler in the final nibble.
+pub fn decode(bytes: &[u8]) -> Result<String, SemiOctetError> {
+    let nibbles = bytes.iter().flat_map(|&b| [b & 0xf, b >> 4]);
+    let last = bytes.len() * 2;
+    let mut number = String::with_capacity(last);
+    for (index, n) in nibbles.enumerate() {
+        match DIGITS.get(n as usize) {
+            Some(&d) => number.push(d as char),
+            None if index + 1 == last => {}
+            None => return Err(SemiOctetError::MisplacedFiller { index }),
+        }
+    }
+    Ok(number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        // The service-centre number from a typical SMS-SUBMIT PDU.
+        assert_eq!(
+            encode("31624000000").unwrap(),
+            [0x13, 0x26, 0x04, 0x00, 0x00, 0xf0]
+        );
+        for number in ["", "1", "12", "*#ab", "0123456789abc", "+"] {
+            match encode(number) {
+                Ok(bytes) => assert_eq!(decode(&bytes).unwrap(), number),
+                Err(e) => assert_eq!(e, SemiOctetError::InvalidChar('+')),
+            }
+        }
+        assert_eq!(decode(&encode("*#AB").unwrap()).unwrap(), "*#ab");
+    }
+
+    #[test]
+    fn filler() {
+        assert_eq!(
+            decode(&[0xf1, 0x32]),
+            Err(SemiOctetError::MisplacedFiller { index: 1 })
+        );
+        assert_eq!(decode(&[0xf1]).unwrap(), "1");
+        assert_eq!(
+            decode(&[0x21, 0xff]),
+            Err(SemiOctetError::MisplacedFiller { index: 2 })
+        );
+    }
+}
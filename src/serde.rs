@@ -0,0 +1,357 @@
+//! Serde support.
+//!
+//! `U4` serializes as a plain integer by default. Pick another representation
+//! per field with `#[serde(with = "...")]`:
+//!
+//! - [`hex`]: a single hex character, `'a'`.
+//! - [`bits`]: four booleans, most significant bit first.
+//!
+//! Deserialization rejects values outside `0..=15` instead of truncating.
+//! [`BcdDigit`] serializes as an integer too, restricted to `0..=9`.
+//!
+//! With the `serde_with` feature, [`Packed`] and [`PackedHex`] store whole
+//! nibble sequences compactly via `#[serde_as(as = "...")]`.
+
+use core::fmt;
+
+use ::serde::{
+    de::{self, Unexpected, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{bcd::BcdDigit, U4};
+
+impl Serialize for U4 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.to_u8())
+    }
+}
+
+struct NumberVisitor;
+
+impl Visitor<'_> for NumberVisitor {
+    type Value = U4;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an integer in 0..=15")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<U4, E> {
+        if v > 15 {
+            return Err(E::invalid_value(Unexpected::Unsigned(v), &self));
+        }
+        Ok(U4::from_u8(v as u8))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<U4, E> {
+        if !(0..=15).contains(&v) {
+            return Err(E::invalid_value(Unexpected::Signed(v), &self));
+        }
+        Ok(U4::from_u8(v as u8))
+    }
+}
+
+impl<'de> Deserialize<'de> for U4 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_u8(NumberVisitor)
+    }
+}
+
+impl Serialize for BcdDigit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.to_u8())
+    }
+}
+
+impl<'de> Deserialize<'de> for BcdDigit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let n = u8::deserialize(deserializer)?;
+        BcdDigit::from_u8(n).ok_or_else(|| {
+            de::Error::invalid_value(Unexpected::Unsigned(n as u64), &"a digit in 0..=9")
+        })
+    }
+}
+
+/// A `U4` as a single lowercase hex character. Either case is accepted when
+/// deserializing.
+pub mod hex {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(n: &U4, serializer: S) -> Result<S::Ok, S::Error> {
+        let c = char::from_digit(n.to_u8() as u32, 16).unwrap();
+        serializer.serialize_char(c)
+    }
+
+    struct HexVisitor;
+
+    impl Visitor<'_> for HexVisitor {
+        type Value = U4;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a single hex digit")
+        }
+
+        fn visit_char<E: de::Error>(self, c: char) -> Result<U4, E> {
+            match c.to_digit(16) {
+                Some(d) => Ok(U4::from_u8(d as u8)),
+                None => Err(E::invalid_value(Unexpected::Char(c), &self)),
+            }
+        }
+
+        fn visit_str<E: de::Error>(self, s: &str) -> Result<U4, E> {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => self.visit_char(c),
+                _ => Err(E::invalid_value(Unexpected::Str(s), &self)),
+            }
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U4, D::Error> {
+        deserializer.deserialize_char(HexVisitor)
+    }
+}
+
+/// A `U4` as `[bool; 4]`, most significant bit first.
+pub mod bits {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(n: &U4, serializer: S) -> Result<S::Ok, S::Error> {
+        n.bits.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U4, D::Error> {
+        let bits = <[bool; 4]>::deserialize(deserializer)?;
+        Ok(U4 { bits })
+    }
+}
+
+/// `serde_with` adapter storing nibbles two per byte, high nibble first, as
+/// a `(nibble_count, bytes)` pair so odd lengths round-trip.
+#[cfg(feature = "serde_with")]
+pub struct Packed;
+
+/// `serde_with` adapter storing nibbles as a lowercase hex string, one
+/// character per nibble.
+#[cfg(feature = "serde_with")]
+pub struct PackedHex;
+
+#[cfg(feature = "serde_with")]
+mod packed {
+    use alloc::{string::String, vec::Vec};
+
+    use ::serde::ser::SerializeTuple;
+    use serde_with::{DeserializeAs, SerializeAs};
+
+    use super::*;
+
+    struct RawBytes<'a>(&'a [u8]);
+
+    impl Serialize for RawBytes<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    struct ByteBuf(Vec<u8>);
+
+    impl<'de> Deserialize<'de> for ByteBuf {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct BytesVisitor;
+
+            impl<'de> Visitor<'de> for BytesVisitor {
+                type Value = ByteBuf;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("a byte array")
+                }
+
+                fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<ByteBuf, E> {
+                    Ok(ByteBuf(v.to_vec()))
+                }
+
+                fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+                    Ok(ByteBuf(v))
+                }
+
+                fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<ByteBuf, A::Error> {
+                    let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                    while let Some(b) = seq.next_element()? {
+                        bytes.push(b);
+                    }
+                    Ok(ByteBuf(bytes))
+                }
+            }
+
+            deserializer.deserialize_byte_buf(BytesVisitor)
+        }
+    }
+
+    impl<T: AsRef<[U4]>> SerializeAs<T> for Packed {
+        fn serialize_as<S: Serializer>(source: &T, serializer: S) -> Result<S::Ok, S::Error> {
+            let nibbles = source.as_ref();
+            let bytes: Vec<u8> = nibbles
+                .chunks(2)
+                .map(|pair| {
+                    let lo = pair.get(1).map_or(0, U4::to_u8);
+                    (pair[0].to_u8() << 4) | lo
+                })
+                .collect();
+            let mut tuple = serializer.serialize_tuple(2)?;
+            tuple.serialize_element(&(nibbles.len() as u64))?;
+            tuple.serialize_element(&RawBytes(&bytes))?;
+            tuple.end()
+        }
+    }
+
+    impl<'de> DeserializeAs<'de, Vec<U4>> for Packed {
+        fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Vec<U4>, D::Error> {
+            let (len, ByteBuf(bytes)) = <(u64, ByteBuf)>::deserialize(deserializer)?;
+            if len.div_ceil(2) != bytes.len() as u64 {
+                return Err(de::Error::invalid_length(
+                    bytes.len(),
+                    &"ceil(nibble_count / 2) bytes",
+                ));
+            }
+            Ok(bytes
+                .iter()
+                .flat_map(|&b| [U4::from_u8(b >> 4), U4::from_u8(b)])
+                .take(len as usize)
+                .collect())
+        }
+    }
+
+    impl<T: AsRef<[U4]>> SerializeAs<T> for PackedHex {
+        fn serialize_as<S: Serializer>(source: &T, serializer: S) -> Result<S::Ok, S::Error> {
+            let s: String = source
+                .as_ref()
+                .iter()
+                .map(|n| char::from_digit(n.to_u8() as u32, 16).unwrap())
+                .collect();
+            serializer.serialize_str(&s)
+        }
+    }
+
+    impl<'de> DeserializeAs<'de, Vec<U4>> for PackedHex {
+        fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Vec<U4>, D::Error> {
+            struct HexStrVisitor;
+
+            impl Visitor<'_> for HexStrVisitor {
+                type Value = Vec<U4>;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("a string of hex digits")
+                }
+
+                fn visit_str<E: de::Error>(self, s: &str) -> Result<Vec<U4>, E> {
+                    s.chars()
+                        .map(|c| match c.to_digit(16) {
+                            Some(d) => Ok(U4::from_u8(d as u8)),
+                            None => Err(E::invalid_value(Unexpected::Char(c), &"a hex digit")),
+                        })
+                        .collect()
+                }
+            }
+
+            deserializer.deserialize_str(HexStrVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::serde::{Deserialize, Serialize};
+
+    use crate::U4;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Reprs {
+        number: U4,
+        #[serde(with = "super::hex")]
+        hex: U4,
+        #[serde(with = "super::bits")]
+        bits: U4,
+    }
+
+    #[test]
+    fn round_trip() {
+        let r = Reprs {
+            number: U4::n(12),
+            hex: U4::n(12),
+            bits: U4::n(12),
+        };
+        let json = serde_json::to_string(&r).unwrap();
+        assert_eq!(
+            json,
+            r#"{"number":12,"hex":"c","bits":[true,true,false,false]}"#
+        );
+        assert_eq!(serde_json::from_str::<Reprs>(&json).unwrap(), r);
+        assert_eq!(
+            serde_json::from_str::<Reprs>(
+                r#"{"number":0,"hex":"F","bits":[false,false,false,true]}"#
+            )
+            .unwrap()
+            .hex,
+            U4::MAX
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range() {
+        let err = serde_json::from_str::<U4>("16").unwrap_err();
+        assert!(err.to_string().contains("0..=15"), "{err}");
+        assert!(serde_json::from_str::<U4>("-1").is_err());
+
+        let hex = |s: &str| {
+            serde_json::from_str::<Reprs>(&format!(
+                r#"{{"number":0,"hex":{s},"bits":[true,true,true,true]}}"#
+            ))
+        };
+        assert!(hex(r#""7""#).is_ok());
+        assert!(hex(r#""g""#).is_err());
+        assert!(hex(r#""ab""#).is_err());
+        assert!(serde_json::from_str::<Reprs>(r#"{"number":0,"hex":"0","bits":[true]}"#).is_err());
+    }
+
+    #[test]
+    fn bcd_digit() {
+        use crate::bcd::BcdDigit;
+
+        assert_eq!(serde_json::to_string(&BcdDigit::NINE).unwrap(), "9");
+        assert_eq!(
+            serde_json::from_str::<BcdDigit>("9").unwrap(),
+            BcdDigit::NINE
+        );
+        let err = serde_json::from_str::<BcdDigit>("10").unwrap_err();
+        assert!(err.to_string().contains("0..=9"), "{err}");
+    }
+
+    #[cfg(feature = "serde_with")]
+    #[test]
+    fn packed() {
+        use serde_with::serde_as;
+
+        use super::{Packed, PackedHex};
+
+        #[serde_as]
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Frame {
+            #[serde_as(as = "Packed")]
+            packed: Vec<U4>,
+            #[serde_as(as = "PackedHex")]
+            hex: Vec<U4>,
+        }
+
+        let nibbles: Vec<U4> = [1, 2, 0xa, 0xb, 0xf].map(U4::n).to_vec();
+        let frame = Frame {
+            packed: nibbles.clone(),
+            hex: nibbles,
+        };
+        let json = serde_json::to_string(&frame).unwrap();
+        assert_eq!(json, r#"{"packed":[5,[18,171,240]],"hex":"12abf"}"#);
+        assert_eq!(serde_json::from_str::<Frame>(&json).unwrap(), frame);
+
+        assert!(serde_json::from_str::<Frame>(r#"{"packed":[4,[18]],"hex":""}"#).is_err());
+        assert!(serde_json::from_str::<Frame>(r#"{"packed":[0,[]],"hex":"x"}"#).is_err());
+    }
+}
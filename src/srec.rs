@@ -0,0 +1,414 @@
+//! Motorola S-record firmware images.
+//!
+//! ```
+//! use u4::srec::{self, AddressWidth, RecordType};
+//!
+//! let text = srec::write(b"boot", 0x1000, &[0xde, 0xad], 0x1000, AddressWidth::Bits16).unwrap();
+//! let records = srec::parse(&text).unwrap();
+//! assert_eq!(records[1].kind, RecordType::S1);
+//! assert_eq!(records[1].data, [0xde, 0xad]);
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Display, Formatter, Write};
+
+use crate::hex::hex_digit;
+
+/// Data bytes per record written by [`write`].
+pub const RECORD_DATA_LEN: usize = 16;
+
+/// The width of the address field, which picks the data, count and
+/// termination record types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressWidth {
+    Bits16,
+    Bits24,
+    Bits32,
+}
+
+impl AddressWidth {
+    fn bytes(self) -> usize {
+        match self {
+            Self::Bits16 => 2,
+            Self::Bits24 => 3,
+            Self::Bits32 => 4,
+        }
+    }
+
+    fn max(self) -> u32 {
+        u32::MAX >> (32 - 8 * self.bytes())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordType {
+    /// Header, usually a module name, at address 0.
+    S0,
+    /// Data at a 16-bit address.
+    S1,
+    /// Data at a 24-bit address.
+    S2,
+    /// Data at a 32-bit address.
+    S3,
+    /// 16-bit count of the preceding data records.
+    S5,
+    /// 24-bit count of the preceding data records.
+    S6,
+    /// Termination with a 32-bit start address.
+    S7,
+    /// Termination with a 24-bit start address.
+    S8,
+    /// Termination with a 16-bit start address.
+    S9,
+}
+
+impl RecordType {
+    pub fn from_digit(d: u8) -> Option<Self> {
+        Some(match d {
+            0 => Self::S0,
+            1 => Self::S1,
+            2 => Self::S2,
+            3 => Self::S3,
+            5 => Self::S5,
+            6 => Self::S6,
+            7 => Self::S7,
+            8 => Self::S8,
+            9 => Self::S9,
+            _ => return None,
+        })
+    }
+
+    pub fn digit(self) -> u8 {
+        match self {
+            Self::S0 => 0,
+            Self::S1 => 1,
+            Self::S2 => 2,
+            Self::S3 => 3,
+            Self::S5 => 5,
+            Self::S6 => 6,
+            Self::S7 => 7,
+            Self::S8 => 8,
+            Self::S9 => 9,
+        }
+    }
+
+    pub fn address_width(self) -> AddressWidth {
+        match self {
+            Self::S0 | Self::S1 | Self::S5 | Self::S9 => AddressWidth::Bits16,
+            Self::S2 | Self::S6 | Self::S8 => AddressWidth::Bits24,
+            Self::S3 | Self::S7 => AddressWidth::Bits32,
+        }
+    }
+
+    pub fn is_data(self) -> bool {
+        matches!(self, Self::S1 | Self::S2 | Self::S3)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SrecError {
+    /// The line does not start with `S`.
+    MissingStart,
+    /// The type digit is not one of `0`–`3` or `5`–`9`.
+    InvalidType(u8),
+    /// The character at `index` is not a hex digit.
+    InvalidDigit { index: usize },
+    /// The byte count disagrees with the length of the line.
+    LengthMismatch,
+    /// The stored checksum is not the computed one.
+    Checksum { expected: u8, found: u8 },
+    /// An address or count does not fit the record's address field, or the
+    /// data does not fit one record.
+    Overflow,
+    /// An S5 or S6 record disagrees with the number of data records before
+    /// it.
+    CountMismatch { expected: u32, found: u32 },
+}
+
+impl Display for SrecError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingStart => write!(f, "record does not start with 'S'"),
+            Self::InvalidType(t) => write!(f, "invalid record type {:?}", *t as char),
+            Self::InvalidDigit { index } => write!(f, "invalid hex digit at index {index}"),
+            Self::LengthMismatch => write!(f, "byte count does not match record length"),
+            Self::Checksum { expected, found } => {
+                write!(f, "checksum {found:02X}, expected {expected:02X}")
+            }
+            Self::Overflow => write!(f, "address or data does not fit the record"),
+            Self::CountMismatch { expected, found } => {
+                write!(f, "record count {found}, expected {expected}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for SrecError {}
+
+/// An [`SrecError`] on a numbered line of a file, counting from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub error: SrecError,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl core::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// One S-record. For count records `address` holds the count, and for
+/// termination records the start address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub kind: RecordType,
+    pub address: u32,
+    pub data: Vec<u8>,
+}
+
+impl Record {
+    pub fn new(kind: RecordType, address: u32, data: Vec<u8>) -> Result<Self, SrecError> {
+        let width = kind.address_width();
+        if address > width.max() || data.len() + width.bytes() + 1 > 0xff {
+            return Err(SrecError::Overflow);
+        }
+        Ok(Self {
+            kind,
+            address,
+            data,
+        })
+    }
+
+    fn address_bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        let bytes = self.kind.address_width().bytes();
+        self.address.to_be_bytes().into_iter().skip(4 - bytes)
+    }
+
+    /// The byte count field: address, data and checksum bytes.
+    pub fn count(&self) -> u8 {
+        (self.kind.address_width().bytes() + self.data.len() + 1) as u8
+    }
+
+    /// The ones' complement of the low byte of the sum of the count,
+    /// address and data bytes.
+    pub fn checksum(&self) -> u8 {
+        let sum = self
+            .address_bytes()
+            .chain(self.data.iter().copied())
+            .fold(self.count(), u8::wrapping_add);
+        !sum
+    }
+
+    pub fn parse(line: &str) -> Result<Self, SrecError> {
+        let line = line.trim_end();
+        let b = line.as_bytes();
+        if b.first() != Some(&b'S') {
+            return Err(SrecError::MissingStart);
+        }
+        let &t = b.get(1).ok_or(SrecError::LengthMismatch)?;
+        let kind = t
+            .checked_sub(b'0')
+            .and_then(RecordType::from_digit)
+            .ok_or(SrecError::InvalidType(t))?;
+        if !b.len().is_multiple_of(2) {
+            return Err(SrecError::LengthMismatch);
+        }
+        let digit = |i: usize| hex_digit(b[i]).ok_or(SrecError::InvalidDigit { index: i });
+        let bytes = (2..b.len())
+            .step_by(2)
+            .map(|i| Ok(digit(i)? << 4 | digit(i + 1)?))
+            .collect::<Result<Vec<u8>, SrecError>>()?;
+
+        let address_len = kind.address_width().bytes();
+        let count = *bytes.first().ok_or(SrecError::LengthMismatch)? as usize;
+        if count != bytes.len() - 1 || count < address_len + 1 {
+            return Err(SrecError::LengthMismatch);
+        }
+        let address = bytes[1..=address_len]
+            .iter()
+            .fold(0, |a, &b| a << 8 | b as u32);
+        let record = Self {
+            kind,
+            address,
+            data: bytes[address_len + 1..count].to_vec(),
+        };
+        let (expected, found) = (record.checksum(), bytes[count]);
+        if expected != found {
+            return Err(SrecError::Checksum { expected, found });
+        }
+        Ok(record)
+    }
+}
+
+/// Formats the record as a line of uppercase hex, without a line ending.
+impl Display for Record {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "S{}{:02X}", self.kind.digit(), self.count())?;
+        for b in self.address_bytes().chain(self.data.iter().copied()) {
+            write!(f, "{b:02X}")?;
+        }
+        write!(f, "{:02X}", self.checksum())
+    }
+}
+
+/// Parses every non-empty line of `text`, checking any S5 or S6 record
+/// against the number of data records before it.
+pub fn parse(text: &str) -> Result<Vec<Record>, ParseError> {
+    let mut records = Vec::new();
+    let mut data_records = 0;
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let err = |error| ParseError { line: i + 1, error };
+        let record = Record::parse(line).map_err(err)?;
+        if record.kind.is_data() {
+            data_records += 1;
+        }
+        if matches!(record.kind, RecordType::S5 | RecordType::S6) && record.address != data_records
+        {
+            return Err(err(SrecError::CountMismatch {
+                expected: data_records,
+                found: record.address,
+            }));
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Writes `data` loaded at `address` as an S-record file: a header record,
+/// data records of up to [`RECORD_DATA_LEN`] bytes, a count record when the
+/// count fits, and a termination record giving `start`.
+pub fn write(
+    header: &[u8],
+    address: u32,
+    data: &[u8],
+    start: u32,
+    width: AddressWidth,
+) -> Result<String, SrecError> {
+    let (data_kind, end_kind) = match width {
+        AddressWidth::Bits16 => (RecordType::S1, RecordType::S9),
+        AddressWidth::Bits24 => (RecordType::S2, RecordType::S8),
+        AddressWidth::Bits32 => (RecordType::S3, RecordType::S7),
+    };
+    let last = (data.len() as u64).saturating_sub(1) + address as u64;
+    if last > width.max() as u64 {
+        return Err(SrecError::Overflow);
+    }
+
+    let mut records = Vec::new();
+    records.push(Record::new(RecordType::S0, 0, header.to_vec())?);
+    for (i, chunk) in data.chunks(RECORD_DATA_LEN).enumerate() {
+        let at = address + (i * RECORD_DATA_LEN) as u32;
+        records.push(Record::new(data_kind, at, chunk.to_vec())?);
+    }
+    let count = records.len() as u32 - 1;
+    if let Ok(r) = Record::new(RecordType::S5, count, Vec::new())
+        .or_else(|_| Record::new(RecordType::S6, count, Vec::new()))
+    {
+        records.push(r);
+    }
+    records.push(Record::new(end_kind, start, Vec::new())?);
+
+    let mut out = String::new();
+    for r in &records {
+        writeln!(out, "{r}").unwrap();
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_records() {
+        // From the srec(5) manual page.
+        let lines = [
+            "S00F000068656C6C6F202020202000003C",
+            "S11F00007C0802A6900100049421FFF07C6C1B787C8C23783C6000003863000026",
+            "S5030003F9",
+            "S9030000FC",
+        ];
+        for line in lines {
+            let r = Record::parse(line).unwrap();
+            assert_eq!(r.to_string(), line);
+        }
+        let header = Record::parse(lines[0]).unwrap();
+        assert_eq!(header.data, b"hello     \0\0");
+        let count = Record::parse(lines[2]).unwrap();
+        assert_eq!((count.kind, count.address), (RecordType::S5, 3));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Record::parse("X9030000FC"), Err(SrecError::MissingStart));
+        assert_eq!(
+            Record::parse("S4030000FC"),
+            Err(SrecError::InvalidType(b'4'))
+        );
+        assert_eq!(
+            Record::parse("S9030000FD"),
+            Err(SrecError::Checksum {
+                expected: 0xfc,
+                found: 0xfd
+            })
+        );
+        assert_eq!(Record::parse("S9040000FC"), Err(SrecError::LengthMismatch));
+        assert_eq!(Record::parse("S9030000F"), Err(SrecError::LengthMismatch));
+        assert_eq!(
+            Record::parse("S90300G0FC"),
+            Err(SrecError::InvalidDigit { index: 6 })
+        );
+        assert_eq!(
+            Record::new(RecordType::S1, 0x1_0000, Vec::new()),
+            Err(SrecError::Overflow)
+        );
+        let err = parse("S9030000FC\nS5030003F9\n").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(
+            err.error,
+            SrecError::CountMismatch {
+                expected: 0,
+                found: 3
+            }
+        );
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let data: Vec<u8> = (0..40).collect();
+        for width in [
+            AddressWidth::Bits16,
+            AddressWidth::Bits24,
+            AddressWidth::Bits32,
+        ] {
+            let text = write(b"test", 0xff00, &data, 0xff00, width).unwrap();
+            let records = parse(&text).unwrap();
+            assert_eq!(records.len(), 6);
+            assert_eq!(records[0].data, b"test");
+            let image: Vec<u8> = records
+                .iter()
+                .filter(|r| r.kind.is_data())
+                .flat_map(|r| r.data.clone())
+                .collect();
+            assert_eq!(image, data);
+            assert_eq!(records[3].address, 0xff20);
+            assert_eq!(records[4].kind, RecordType::S5);
+            assert_eq!(records[5].address, 0xff00);
+            assert_eq!(records[5].kind.address_width(), width);
+        }
+        assert_eq!(
+            write(b"", 0xfff0, &data, 0, AddressWidth::Bits16),
+            Err(SrecError::Overflow)
+        );
+    }
+}
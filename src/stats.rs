@@ -0,0 +1,185 @@
+//! Nibble frequency counting and statistics over byte buffers.
+
+use crate::{
+    search::{zero_nibbles, ONES},
+    U4,
+};
+
+/// Counts every high and low nibble in `bytes`; index `n` of the result is
+/// the number of nibbles equal to `n`, and the counts sum to
+/// `2 * bytes.len()`.
+///
+/// Bytes are read a 64-bit word at a time. Each of the 16 nibble lanes is
+/// compared against every value at once, SWAR style, and the matching lanes
+/// are counted with `count_ones`; the count for 15 is whatever is left.
+///
+/// ```
+/// use u4::stats::nibble_histogram;
+///
+/// let h = nibble_histogram(b"\x12\x21\xff");
+/// assert_eq!((h[1], h[2], h[0xf]), (2, 2, 2));
+/// ```
+pub fn nibble_histogram(bytes: &[u8]) -> [u64; 16] {
+    let mut counts = [0; 16];
+    let mut words = bytes.chunks_exact(8);
+    for word in &mut words {
+        let w = u64::from_le_bytes(word.try_into().unwrap());
+        let mut left = 16;
+        for (n, count) in counts[..15].iter_mut().enumerate() {
+            let c = zero_nibbles(w ^ (n as u64 * ONES)).count_ones() as u64;
+            *count += c;
+            left -= c;
+        }
+        counts[15] += left;
+    }
+    for &b in words.remainder() {
+        counts[(b >> 4) as usize] += 1;
+        counts[(b & 0xf) as usize] += 1;
+    }
+    counts
+}
+
+/// Running nibble counts with summary statistics, fed incrementally.
+///
+/// ```
+/// use u4::{stats::NibbleStats, U4};
+///
+/// let mut stats = NibbleStats::new();
+/// stats.update(b"\x00\x01");
+/// stats.push(U4::n(1));
+/// assert_eq!(stats.total(), 5);
+/// assert_eq!(stats.most_common(), Some((U4::n(0), 3)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NibbleStats {
+    counts: [u64; 16],
+}
+
+impl NibbleStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds both nibbles of every byte.
+    pub fn update(&mut self, bytes: &[u8]) {
+        for (c, n) in self.counts.iter_mut().zip(nibble_histogram(bytes)) {
+            *c += n;
+        }
+    }
+
+    pub fn push(&mut self, n: U4) {
+        self.counts[n.to_u8() as usize] += 1;
+    }
+
+    pub fn counts(&self) -> &[u64; 16] {
+        &self.counts
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// The most frequent nibble and its count, preferring the smallest on
+    /// ties, or `None` if nothing was counted.
+    pub fn most_common(&self) -> Option<(U4, u64)> {
+        let (n, &c) = self
+            .counts
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|&(_, c)| c)?;
+        (c > 0).then_some((U4::from_u8(n as u8), c))
+    }
+
+    /// The least frequent nibble and its count, preferring the smallest on
+    /// ties, or `None` if nothing was counted.
+    pub fn least_common(&self) -> Option<(U4, u64)> {
+        let (n, &c) = self.counts.iter().enumerate().min_by_key(|&(_, c)| c)?;
+        (self.total() > 0).then_some((U4::from_u8(n as u8), c))
+    }
+
+    /// Pearson's chi-squared statistic against a uniform distribution, with
+    /// 15 degrees of freedom; about 25.0 is the 5% critical value. Zero when
+    /// nothing was counted.
+    pub fn chi_squared(&self) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return 0.0;
+        }
+        let expected = total as f64 / 16.0;
+        self.counts
+            .iter()
+            .map(|&c| {
+                let d = c as f64 - expected;
+                d * d / expected
+            })
+            .sum()
+    }
+
+    /// Shannon entropy in bits per nibble, from 0 to 4.
+    #[cfg(feature = "std")]
+    pub fn entropy(&self) -> f64 {
+        let total = self.total() as f64;
+        self.counts
+            .iter()
+            .filter(|&&c| c > 0)
+            .map(|&c| {
+                let p = c as f64 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+}
+
+impl Extend<U4> for NibbleStats {
+    fn extend<I: IntoIterator<Item = U4>>(&mut self, iter: I) {
+        for n in iter {
+            self.push(n);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_naive() {
+        let bytes: Vec<u8> = (0..1003_u32).map(|i| (i * 37 % 251) as u8).collect();
+        for len in [0, 1, 7, 8, 9, 1003] {
+            let bytes = &bytes[..len];
+            let mut naive = [0; 16];
+            for &b in bytes {
+                naive[(b >> 4) as usize] += 1;
+                naive[(b & 0xf) as usize] += 1;
+            }
+            assert_eq!(nibble_histogram(bytes), naive, "len {len}");
+        }
+        assert_eq!(nibble_histogram(&[0xff; 16])[15], 32);
+        assert_eq!(nibble_histogram(&[0; 16])[0], 32);
+    }
+
+    #[test]
+    fn statistics() {
+        let mut stats = NibbleStats::new();
+        assert_eq!(stats.most_common(), None);
+        assert_eq!(stats.least_common(), None);
+        assert_eq!(stats.chi_squared(), 0.0);
+
+        let all: Vec<u8> = (0..=255).collect();
+        stats.update(&all);
+        assert_eq!(stats.total(), 512);
+        assert_eq!(stats.chi_squared(), 0.0);
+        #[cfg(feature = "std")]
+        assert_eq!(stats.entropy(), 4.0);
+
+        let mut skewed = NibbleStats::new();
+        skewed.update(&[0xaa; 8]);
+        skewed.extend([U4::n(3)]);
+        assert_eq!(skewed.most_common(), Some((U4::n(0xa), 16)));
+        assert_eq!(skewed.least_common(), Some((U4::n(0), 0)));
+        assert!(skewed.chi_squared() > 25.0);
+        #[cfg(feature = "std")]
+        assert!(skewed.entropy() < 0.5);
+    }
+}
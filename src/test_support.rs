@@ -0,0 +1,144 @@
+//! Exhaustive differential checks of nibble operations.
+//!
+//! With only 16 values and 256 pairs, every input can be tried. Each check
+//! runs a [`U4`] operation against a reference on `u8` whose result is
+//! reduced modulo 16, and reports the first input where they disagree.
+//!
+//! ```
+//! use u4::test_support::{check_binary, check_unary};
+//!
+//! check_binary("add", |a, b| a + b, |a, b| Some(a + b)).unwrap();
+//! check_unary("not", |a| !a, |a| !a).unwrap();
+//! ```
+
+use core::fmt::{Display, Formatter};
+
+use crate::U4;
+
+/// An input on which an operation and its reference disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mismatch {
+    pub op: &'static str,
+    pub lhs: U4,
+    /// `None` for unary operations.
+    pub rhs: Option<U4>,
+    pub got: U4,
+    pub expected: U4,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.rhs {
+            Some(rhs) => write!(f, "{}({}, {})", self.op, self.lhs, rhs)?,
+            None => write!(f, "{}({})", self.op, self.lhs)?,
+        }
+        write!(f, " gave {}, expected {}", self.got, self.expected)
+    }
+}
+
+impl core::error::Error for Mismatch {}
+
+/// Every nibble value in ascending order.
+pub fn all() -> impl Iterator<Item = U4> + Clone {
+    (0..16).map(U4::from_u8)
+}
+
+/// Compares `op` with `reference` on all 16 inputs.
+pub fn check_unary(
+    op: &'static str,
+    f: impl Fn(U4) -> U4,
+    reference: impl Fn(u8) -> u8,
+) -> Result<(), Mismatch> {
+    for a in all() {
+        let got = f(a);
+        let expected = U4::from_u8(reference(a.to_u8()));
+        if got != expected {
+            return Err(Mismatch {
+                op,
+                lhs: a,
+                rhs: None,
+                got,
+                expected,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Compares `op` with `reference` on all 256 input pairs. Pairs where the
+/// reference returns `None`, such as division by zero, are skipped.
+pub fn check_binary(
+    op: &'static str,
+    f: impl Fn(U4, U4) -> U4,
+    reference: impl Fn(u8, u8) -> Option<u8>,
+) -> Result<(), Mismatch> {
+    for a in all() {
+        for b in all() {
+            let Some(expected) = reference(a.to_u8(), b.to_u8()) else {
+                continue;
+            };
+            let got = f(a, b);
+            let expected = U4::from_u8(expected);
+            if got != expected {
+                return Err(Mismatch {
+                    op,
+                    lhs: a,
+                    rhs: Some(b),
+                    got,
+                    expected,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks the crate's own arithmetic, bitwise, shift and rotate operators.
+pub fn check_operators() -> Result<(), Mismatch> {
+    check_unary("not", |a| !a, |a| !a)?;
+    check_binary("add", |a, b| a + b, |a, b| Some(a + b))?;
+    check_binary("sub", |a, b| a - b, |a, b| Some(a.wrapping_sub(b)))?;
+    check_binary("mul", |a, b| a * b, |a, b| Some(a * b))?;
+    check_binary("div", |a, b| a / b, |a, b| a.checked_div(b))?;
+    check_binary("rem", |a, b| a % b, |a, b| a.checked_rem(b))?;
+    check_binary("and", |a, b| a & b, |a, b| Some(a & b))?;
+    check_binary("or", |a, b| a | b, |a, b| Some(a | b))?;
+    check_binary("xor", |a, b| a ^ b, |a, b| Some(a ^ b))?;
+    check_binary(
+        "shl",
+        |a, b| a << b.to_u8() as usize,
+        |a, b| Some(a.checked_shl(b as u32).unwrap_or(0)),
+    )?;
+    check_binary(
+        "shr",
+        |a, b| a >> b.to_u8() as usize,
+        |a, b| Some(a.checked_shr(b as u32).unwrap_or(0)),
+    )?;
+    check_binary(
+        "rotate_left",
+        |a, b| a.rotate_left(b.to_u8() as u32),
+        |a, b| Some((a * 0x11) << (b % 4) >> 4),
+    )?;
+    check_binary(
+        "rotate_right",
+        |a, b| a.rotate_right(b.to_u8() as u32),
+        |a, b| Some((a * 0x11) >> (b % 4)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        check_operators().unwrap();
+    }
+
+    #[test]
+    fn reports_mismatch() {
+        let err = check_binary("add", |a, b| a | b, |a, b| Some(a + b)).unwrap_err();
+        assert_eq!(err.to_string(), "add(1, 1) gave 1, expected 2");
+        assert_eq!(all().count(), 16);
+    }
+}
@@ -0,0 +1,244 @@
+//! A panic-free profile of the nibble API.
+//!
+//! [`total::U4`](U4) wraps the crate's [`U4`](crate::U4) but exposes only
+//! total operations: checked, wrapping and saturating arithmetic and
+//! fallible constructors. It has no `/`, `%` or other operator that can
+//! panic, so importing it in place of the crate root type rules out nibble
+//! panics at compile time.
+//!
+//! ```
+//! use u4::total::U4;
+//!
+//! let n = U4::new(9).unwrap();
+//! assert_eq!(n.checked_div(U4::MIN), None);
+//! assert_eq!(n.saturating_add(n), U4::MAX);
+//! ```
+//!
+//! ```compile_fail
+//! let _ = u4::total::U4::MAX / u4::total::U4::MIN;
+//! ```
+
+use core::fmt::{self, Display, Formatter};
+use core::ops::{BitAnd, BitOr, BitXor, Not};
+
+/// A nibble with only non-panicking operations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct U4(crate::U4);
+
+impl U4 {
+    pub const MIN: U4 = U4(crate::U4::MIN);
+    pub const MAX: U4 = U4(crate::U4::MAX);
+    pub const BITS: u32 = 4;
+
+    /// `n` as a nibble, or `None` if it is above 15.
+    pub const fn new(n: u8) -> Option<Self> {
+        if n > 15 {
+            None
+        } else {
+            Some(Self(crate::U4::from_u8(n)))
+        }
+    }
+
+    /// The low four bits of `n`.
+    pub const fn from_u8(n: u8) -> Self {
+        Self(crate::U4::from_u8(n))
+    }
+
+    pub const fn from_hex_digit(c: u8) -> Option<Self> {
+        match crate::U4::from_hex_digit(c) {
+            Some(n) => Some(Self(n)),
+            None => None,
+        }
+    }
+
+    pub const fn to_u8(self) -> u8 {
+        self.0.to_u8()
+    }
+
+    pub const fn get(self) -> crate::U4 {
+        self.0
+    }
+
+    const fn checked(n: Option<u8>) -> Option<Self> {
+        match n {
+            Some(n) => Self::new(n),
+            None => None,
+        }
+    }
+
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::checked(self.to_u8().checked_add(rhs.to_u8()))
+    }
+
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Self::checked(self.to_u8().checked_sub(rhs.to_u8()))
+    }
+
+    pub const fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Self::checked(self.to_u8().checked_mul(rhs.to_u8()))
+    }
+
+    /// `None` if `rhs` is zero.
+    pub const fn checked_div(self, rhs: Self) -> Option<Self> {
+        Self::checked(self.to_u8().checked_div(rhs.to_u8()))
+    }
+
+    /// `None` if `rhs` is zero.
+    pub const fn checked_rem(self, rhs: Self) -> Option<Self> {
+        Self::checked(self.to_u8().checked_rem(rhs.to_u8()))
+    }
+
+    /// `None` if `n` is 4 or more.
+    pub const fn checked_shl(self, n: u32) -> Option<Self> {
+        if n >= Self::BITS {
+            None
+        } else {
+            Some(Self::from_u8(self.to_u8() << n))
+        }
+    }
+
+    /// `None` if `n` is 4 or more.
+    pub const fn checked_shr(self, n: u32) -> Option<Self> {
+        if n >= Self::BITS {
+            None
+        } else {
+            Some(Self::from_u8(self.to_u8() >> n))
+        }
+    }
+
+    pub const fn wrapping_add(self, rhs: Self) -> Self {
+        Self::from_u8(self.to_u8() + rhs.to_u8())
+    }
+
+    pub const fn wrapping_sub(self, rhs: Self) -> Self {
+        Self::from_u8(self.to_u8().wrapping_sub(rhs.to_u8()))
+    }
+
+    pub const fn wrapping_mul(self, rhs: Self) -> Self {
+        Self::from_u8(self.to_u8() * rhs.to_u8())
+    }
+
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        match self.checked_add(rhs) {
+            Some(n) => n,
+            None => Self::MAX,
+        }
+    }
+
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        Self::from_u8(self.to_u8().saturating_sub(rhs.to_u8()))
+    }
+
+    pub const fn saturating_mul(self, rhs: Self) -> Self {
+        match self.checked_mul(rhs) {
+            Some(n) => n,
+            None => Self::MAX,
+        }
+    }
+
+    pub const fn rotate_left(self, n: u32) -> Self {
+        Self(self.0.rotate_left(n))
+    }
+
+    pub const fn rotate_right(self, n: u32) -> Self {
+        Self(self.0.rotate_right(n))
+    }
+}
+
+impl From<crate::U4> for U4 {
+    fn from(n: crate::U4) -> Self {
+        Self(n)
+    }
+}
+
+impl From<U4> for crate::U4 {
+    fn from(n: U4) -> Self {
+        n.0
+    }
+}
+
+impl From<U4> for u8 {
+    fn from(n: U4) -> Self {
+        n.to_u8()
+    }
+}
+
+impl Display for U4 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl BitAnd for U4 {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for U4 {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitXor for U4 {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl Not for U4 {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_u8() {
+        for a in 0..16 {
+            let x = U4::from_u8(a);
+            for b in 0..16 {
+                let y = U4::from_u8(b);
+                let expect = |r: Option<u8>| r.filter(|&r| r < 16).map(U4::from_u8);
+                assert_eq!(x.checked_add(y), expect(a.checked_add(b)));
+                assert_eq!(x.checked_sub(y), expect(a.checked_sub(b)));
+                assert_eq!(x.checked_mul(y), expect(a.checked_mul(b)));
+                assert_eq!(x.checked_div(y), expect(a.checked_div(b)));
+                assert_eq!(x.checked_rem(y), expect(a.checked_rem(b)));
+                assert_eq!(x.wrapping_add(y).get(), x.get() + y.get());
+                assert_eq!(x.wrapping_sub(y).get(), x.get() - y.get());
+                assert_eq!(x.wrapping_mul(y).get(), x.get() * y.get());
+                assert_eq!(x.saturating_add(y).to_u8(), (a + b).min(15));
+                assert_eq!(x.saturating_sub(y).to_u8(), a.saturating_sub(b));
+                assert_eq!(x.saturating_mul(y).to_u8(), (a * b).min(15));
+                assert_eq!((x ^ y).get(), x.get() ^ y.get());
+            }
+        }
+    }
+
+    #[test]
+    fn constructors_and_shifts() {
+        assert_eq!(U4::new(15), Some(U4::MAX));
+        assert_eq!(U4::new(16), None);
+        assert_eq!(U4::from_u8(0x1f), U4::MAX);
+        assert_eq!(U4::from_hex_digit(b'g'), None);
+        assert_eq!(U4::MAX.checked_shl(3), U4::new(8));
+        assert_eq!(U4::MAX.checked_shl(4), None);
+        assert_eq!(U4::MAX.checked_shr(u32::MAX), None);
+        assert_eq!(U4::new(1).unwrap().rotate_right(u32::MAX), U4::from_u8(2));
+        assert_eq!(crate::U4::from(!U4::MIN), crate::U4::MAX);
+    }
+}
@@ -0,0 +1,278 @@
+//! Nibble-oriented toy ciphers for teaching and cryptanalysis experiments.
+//!
+//! Blocks are nibble slices and keys are [`Key`]s, which are wiped on drop
+//! with the `zeroize` feature. Plug an [`SBox4`], a [`LinearLayer`] and a
+//! [`KeySchedule`] into [`Spn`] or [`Feistel`] to get a working cipher.
+
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::{bitmatrix::BitMatrix4, perm::Perm16, sbox::SBox4, U4};
+
+/// The diffusion layer of a round.
+pub trait LinearLayer {
+    fn apply(&self, block: &mut [U4]);
+    fn apply_inverse(&self, block: &mut [U4]);
+}
+
+/// No diffusion.
+impl LinearLayer for () {
+    fn apply(&self, _block: &mut [U4]) {}
+    fn apply_inverse(&self, _block: &mut [U4]) {}
+}
+
+/// Permutes the 16 nibbles of the block. Panics on blocks of another length.
+impl LinearLayer for Perm16 {
+    fn apply(&self, block: &mut [U4]) {
+        self.apply_to_slice(block);
+    }
+
+    fn apply_inverse(&self, block: &mut [U4]) {
+        self.inverse().apply_to_slice(block);
+    }
+}
+
+/// Multiplies every nibble by the matrix. `apply_inverse` panics if the
+/// matrix is singular.
+impl LinearLayer for BitMatrix4 {
+    fn apply(&self, block: &mut [U4]) {
+        for n in block {
+            *n = self.mul_vec(*n);
+        }
+    }
+
+    fn apply_inverse(&self, block: &mut [U4]) {
+        let inv = self.inverse().expect("singular linear layer");
+        inv.apply(block);
+    }
+}
+
+/// Derives the key for round `round`, `len` nibbles long.
+pub trait KeySchedule {
+    fn round_key(&self, key: &[U4], round: usize, len: usize) -> Vec<U4>;
+}
+
+impl<F: Fn(&[U4], usize, usize) -> Vec<U4>> KeySchedule for F {
+    fn round_key(&self, key: &[U4], round: usize, len: usize) -> Vec<U4> {
+        self(key, round, len)
+    }
+}
+
+/// Cycles the key to the block length, rotated by one nibble per round and
+/// xored with the round number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RotatingKey;
+
+impl KeySchedule for RotatingKey {
+    fn round_key(&self, key: &[U4], round: usize, len: usize) -> Vec<U4> {
+        assert!(!key.is_empty(), "empty key");
+        (0..len)
+            .map(|i| key[(i + round) % key.len()] ^ U4::from_u8(round as u8))
+            .collect()
+    }
+}
+
+/// A cipher key. With the `zeroize` feature it is wiped when dropped.
+///
+/// It derefs to `[U4]`, which is what [`KeySchedule`]s read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key(pub(crate) Vec<U4>);
+
+impl From<Vec<U4>> for Key {
+    fn from(nibbles: Vec<U4>) -> Self {
+        Self(nibbles)
+    }
+}
+
+impl Deref for Key {
+    type Target = [U4];
+
+    fn deref(&self) -> &[U4] {
+        &self.0
+    }
+}
+
+/// Adds a derived key into `block`, wiping the key afterwards when the
+/// `zeroize` feature is on.
+fn add_key(block: &mut [U4], key: Vec<U4>) {
+    for (n, &k) in block.iter_mut().zip(&key) {
+        *n = *n ^ k;
+    }
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut { key });
+}
+
+/// A substitution-permutation network.
+///
+/// Each of the `rounds` rounds adds the round key, substitutes every nibble
+/// and applies the linear layer (except in the last round). A final round
+/// key is added at the end.
+#[derive(Debug, Clone)]
+pub struct Spn<L, K> {
+    pub sbox: SBox4,
+    pub linear: L,
+    pub schedule: K,
+    pub rounds: usize,
+}
+
+impl<L: LinearLayer, K: KeySchedule> Spn<L, K> {
+    pub fn new(sbox: SBox4, linear: L, schedule: K, rounds: usize) -> Self {
+        Self {
+            sbox,
+            linear,
+            schedule,
+            rounds,
+        }
+    }
+
+    pub fn encrypt(&self, block: &[U4], key: &Key) -> Vec<U4> {
+        let mut state = block.to_vec();
+        for round in 0..self.rounds {
+            add_key(&mut state, self.schedule.round_key(key, round, block.len()));
+            for n in state.iter_mut() {
+                *n = self.sbox.apply(*n);
+            }
+            if round + 1 < self.rounds {
+                self.linear.apply(&mut state);
+            }
+        }
+        add_key(
+            &mut state,
+            self.schedule.round_key(key, self.rounds, block.len()),
+        );
+        state
+    }
+
+    pub fn decrypt(&self, block: &[U4], key: &Key) -> Vec<U4> {
+        let inv = self.sbox.inverse();
+        let mut state = block.to_vec();
+        add_key(
+            &mut state,
+            self.schedule.round_key(key, self.rounds, block.len()),
+        );
+        for round in (0..self.rounds).rev() {
+            if round + 1 < self.rounds {
+                self.linear.apply_inverse(&mut state);
+            }
+            for n in state.iter_mut() {
+                *n = inv.apply(*n);
+            }
+            add_key(&mut state, self.schedule.round_key(key, round, block.len()));
+        }
+        state
+    }
+}
+
+/// A balanced Feistel network whose round function adds the round key to
+/// the right half, substitutes every nibble and applies the linear layer.
+/// The round function need not be invertible.
+#[derive(Debug, Clone)]
+pub struct Feistel<L, K> {
+    pub sbox: SBox4,
+    pub linear: L,
+    pub schedule: K,
+    pub rounds: usize,
+}
+
+impl<L: LinearLayer, K: KeySchedule> Feistel<L, K> {
+    pub fn new(sbox: SBox4, linear: L, schedule: K, rounds: usize) -> Self {
+        Self {
+            sbox,
+            linear,
+            schedule,
+            rounds,
+        }
+    }
+
+    fn round_fn(&self, half: &[U4], key: &[U4], round: usize) -> Vec<U4> {
+        let mut out = half.to_vec();
+        add_key(&mut out, self.schedule.round_key(key, round, half.len()));
+        for n in out.iter_mut() {
+            *n = self.sbox.apply(*n);
+        }
+        self.linear.apply(&mut out);
+        out
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `block` has an odd number of nibbles.
+    pub fn encrypt(&self, block: &[U4], key: &Key) -> Vec<U4> {
+        assert!(
+            block.len().is_multiple_of(2),
+            "Feistel block must have even length"
+        );
+        let (l, r) = block.split_at(block.len() / 2);
+        let (mut l, mut r) = (l.to_vec(), r.to_vec());
+        for round in 0..self.rounds {
+            let f = self.round_fn(&r, key, round);
+            add_key(&mut l, f);
+            core::mem::swap(&mut l, &mut r);
+        }
+        [r, l].concat()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `block` has an odd number of nibbles.
+    pub fn decrypt(&self, block: &[U4], key: &Key) -> Vec<U4> {
+        assert!(
+            block.len().is_multiple_of(2),
+            "Feistel block must have even length"
+        );
+        let (r, l) = block.split_at(block.len() / 2);
+        let (mut l, mut r) = (l.to_vec(), r.to_vec());
+        for round in (0..self.rounds).rev() {
+            core::mem::swap(&mut l, &mut r);
+            let f = self.round_fn(&r, key, round);
+            add_key(&mut l, f);
+        }
+        [l, r].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nibbles(values: &[u8]) -> Vec<U4> {
+        values.iter().map(|&v| U4::from_u8(v)).collect()
+    }
+
+    #[test]
+    fn spn_round_trip() {
+        let perm = Perm16::from_u8_table([0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15]);
+        let spn = Spn::new(SBox4::PRESENT, perm, RotatingKey, 8);
+        let block = nibbles(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let key = Key::from(nibbles(&[3, 1, 4, 1, 5, 9, 2, 6]));
+
+        let ct = spn.encrypt(&block, &key);
+        assert_ne!(ct, block);
+        assert_eq!(spn.decrypt(&ct, &key), block);
+    }
+
+    #[test]
+    fn spn_matrix_layer() {
+        let m = BitMatrix4::new([U4::n(0b0011), U4::n(0b0110), U4::n(0b1100), U4::n(0b1000)]);
+        let schedule = |key: &[U4], round: usize, len: usize| {
+            (0..len).map(|i| key[(i + round) % key.len()]).collect()
+        };
+        let spn = Spn::new(SBox4::GIFT, m, schedule, 4);
+        let block = nibbles(&[9, 8, 7]);
+        let key = Key::from(nibbles(&[1, 2]));
+        assert_eq!(spn.decrypt(&spn.encrypt(&block, &key), &key), block);
+    }
+
+    #[test]
+    fn feistel_round_trip() {
+        // A singular layer still gives an invertible Feistel cipher.
+        let singular = BitMatrix4::new([U4::n(1), U4::n(1), U4::n(0), U4::n(0)]);
+        let feistel = Feistel::new(SBox4::PRINCE, singular, RotatingKey, 6);
+        let block = nibbles(&[1, 2, 3, 4, 5, 6]);
+        let key = Key::from(nibbles(&[0xa, 0xb]));
+
+        let ct = feistel.encrypt(&block, &key);
+        assert_ne!(ct, block);
+        assert_eq!(feistel.decrypt(&ct, &key), block);
+    }
+}
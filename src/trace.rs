@@ -0,0 +1,533 @@
+//! Step-by-step traces of nibble arithmetic for teaching.
+//!
+//! Each traced operation returns its result together with every
+//! intermediate step, and formats as a worked example.
+//!
+//! ```
+//! use u4::{trace, U4};
+//!
+//! let t = trace::add(U4::n(6), U4::n(3), false);
+//! assert_eq!(t.result, U4::n(9));
+//! println!("{t}");
+//! ```
+
+use core::fmt::{self, Display, Formatter};
+
+use crate::U4;
+
+/// Whether an [`AdderTrace`] adds or subtracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdderOp {
+    Add,
+    Sub,
+}
+
+/// One bit position of a ripple-carry addition or borrow subtraction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BitStep {
+    pub a: bool,
+    pub b: bool,
+    /// The carry, or borrow, coming in from the bit below.
+    pub carry_in: bool,
+    pub result: bool,
+    /// The carry, or borrow, passed to the bit above.
+    pub carry_out: bool,
+}
+
+/// A bit-by-bit addition or subtraction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdderTrace {
+    pub op: AdderOp,
+    pub a: U4,
+    pub b: U4,
+    /// Bit 0 first.
+    pub steps: [BitStep; 4],
+    pub result: U4,
+    /// Carry or borrow out of bit 3.
+    pub carry: bool,
+}
+
+fn bit(n: U4, i: usize) -> bool {
+    n.to_u8() >> i & 1 != 0
+}
+
+fn ripple(op: AdderOp, a: U4, b: U4, carry_in: bool) -> AdderTrace {
+    let mut steps = [BitStep::default(); 4];
+    let mut carry = carry_in;
+    let mut result = 0;
+    for (i, step) in steps.iter_mut().enumerate() {
+        let (x, y) = (bit(a, i), bit(b, i));
+        let r = x ^ y ^ carry;
+        let out = match op {
+            AdderOp::Add => (x && y) || (carry && (x ^ y)),
+            AdderOp::Sub => (!x && y) || (carry && !(x ^ y)),
+        };
+        *step = BitStep {
+            a: x,
+            b: y,
+            carry_in: carry,
+            result: r,
+            carry_out: out,
+        };
+        result |= (r as u8) << i;
+        carry = out;
+    }
+    AdderTrace {
+        op,
+        a,
+        b,
+        steps,
+        result: U4::from_u8(result),
+        carry,
+    }
+}
+
+/// Traces `a + b + carry_in` through a ripple-carry adder.
+pub fn add(a: U4, b: U4, carry_in: bool) -> AdderTrace {
+    ripple(AdderOp::Add, a, b, carry_in)
+}
+
+/// Traces `a - b - borrow_in` with a borrow rippling upwards.
+pub fn sub(a: U4, b: U4, borrow_in: bool) -> AdderTrace {
+    ripple(AdderOp::Sub, a, b, borrow_in)
+}
+
+impl Display for AdderTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (sign, carry) = match self.op {
+            AdderOp::Add => ('+', "carry"),
+            AdderOp::Sub => ('-', "borrow"),
+        };
+        writeln!(f, "  {:04b}  ({})", self.a.to_u8(), self.a)?;
+        writeln!(f, "{sign} {:04b}  ({})", self.b.to_u8(), self.b)?;
+        f.write_str("  ")?;
+        for s in self.steps.iter().rev() {
+            write!(f, "{}", s.carry_in as u8)?;
+        }
+        writeln!(f, "  {carry} in")?;
+        writeln!(
+            f,
+            "= {:04b}  ({}), {carry} out {}",
+            self.result.to_u8(),
+            self.result,
+            self.carry as u8
+        )?;
+        for (i, s) in self.steps.iter().enumerate() {
+            write!(
+                f,
+                "bit {i}: {} {sign} {} {sign} {carry} {} = {}, {carry} {}",
+                s.a as u8, s.b as u8, s.carry_in as u8, s.result as u8, s.carry_out as u8
+            )?;
+            if i < 3 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One step of [`mul_shift_add`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShiftAddStep {
+    /// Bit of the multiplier examined at this step.
+    pub multiplier_bit: bool,
+    /// The multiplicand shifted into place, or zero if the bit is clear.
+    pub partial_product: u8,
+    /// The running sum after adding the partial product.
+    pub accumulator: u8,
+}
+
+/// An unsigned 4×4-bit shift-and-add multiplication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShiftAddTrace {
+    pub a: U4,
+    pub b: U4,
+    /// One per bit of `b`, bit 0 first.
+    pub steps: [ShiftAddStep; 4],
+    pub product: u8,
+}
+
+/// Traces `a * b` by adding `a << i` for every set bit `i` of `b`.
+pub fn mul_shift_add(a: U4, b: U4) -> ShiftAddTrace {
+    let mut steps = [ShiftAddStep::default(); 4];
+    let mut acc = 0;
+    for (i, step) in steps.iter_mut().enumerate() {
+        let multiplier_bit = bit(b, i);
+        let partial_product = if multiplier_bit { a.to_u8() << i } else { 0 };
+        acc += partial_product;
+        *step = ShiftAddStep {
+            multiplier_bit,
+            partial_product,
+            accumulator: acc,
+        };
+    }
+    ShiftAddTrace {
+        a,
+        b,
+        steps,
+        product: acc,
+    }
+}
+
+impl Display for ShiftAddTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:04b} x {:04b}  ({} x {})",
+            self.a.to_u8(),
+            self.b.to_u8(),
+            self.a,
+            self.b
+        )?;
+        for (i, s) in self.steps.iter().enumerate() {
+            writeln!(
+                f,
+                "bit {i} = {}: + {:08b}  -> {:08b}",
+                s.multiplier_bit as u8, s.partial_product, s.accumulator
+            )?;
+        }
+        write!(f, "= {:08b}  ({})", self.product, self.product)
+    }
+}
+
+/// What a Booth step does before shifting, chosen by the multiplier bit and
+/// the bit shifted out before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoothAction {
+    /// `00` or `11`: inside a run of equal bits.
+    None,
+    /// `01`: end of a run of ones, add the multiplicand.
+    Add,
+    /// `10`: start of a run of ones, subtract the multiplicand.
+    Sub,
+}
+
+/// One step of [`mul_booth`], with the registers after the arithmetic
+/// shift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoothStep {
+    pub action: BoothAction,
+    /// The accumulator, one bit wider than a nibble so that subtracting
+    /// -8 cannot overflow.
+    pub a: i8,
+    pub q: U4,
+    /// The bit last shifted out of `q`.
+    pub q_1: bool,
+}
+
+/// A signed 4×4-bit multiplication by Booth's algorithm, reading both
+/// operands as two's complement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoothTrace {
+    pub multiplicand: U4,
+    pub multiplier: U4,
+    pub steps: [BoothStep; 4],
+    /// The 8-bit two's complement product.
+    pub product: u8,
+}
+
+impl BoothTrace {
+    pub fn signed_product(&self) -> i8 {
+        self.product as i8
+    }
+}
+
+fn signed(n: U4) -> i8 {
+    ((n.to_u8() << 4) as i8) >> 4
+}
+
+/// Traces the signed product of `multiplicand` and `multiplier` by Booth's
+/// algorithm.
+pub fn mul_booth(multiplicand: U4, multiplier: U4) -> BoothTrace {
+    let m = signed(multiplicand);
+    let (mut a, mut q, mut q_1) = (0_i8, multiplier.to_u8(), false);
+    let steps = [(); 4].map(|()| {
+        let action = match (q & 1 != 0, q_1) {
+            (false, true) => BoothAction::Add,
+            (true, false) => BoothAction::Sub,
+            _ => BoothAction::None,
+        };
+        match action {
+            BoothAction::Add => a += m,
+            BoothAction::Sub => a -= m,
+            BoothAction::None => {}
+        }
+        q_1 = q & 1 != 0;
+        q = (q >> 1) | ((a as u8 & 1) << 3);
+        a >>= 1;
+        BoothStep {
+            action,
+            a,
+            q: U4::from_u8(q),
+            q_1,
+        }
+    });
+    BoothTrace {
+        multiplicand,
+        multiplier,
+        steps,
+        product: ((a as u8) << 4) | q,
+    }
+}
+
+impl Display for BoothTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:04b} x {:04b}  ({} x {})",
+            self.multiplicand.to_u8(),
+            self.multiplier.to_u8(),
+            signed(self.multiplicand),
+            signed(self.multiplier)
+        )?;
+        for (i, s) in self.steps.iter().enumerate() {
+            let action = match s.action {
+                BoothAction::None => "shift",
+                BoothAction::Add => "add, shift",
+                BoothAction::Sub => "sub, shift",
+            };
+            writeln!(
+                f,
+                "step {i}: {action:<10}  A={:05b} Q={:04b} Q-1={}",
+                s.a as u8 & 0x1f,
+                s.q.to_u8(),
+                s.q_1 as u8
+            )?;
+        }
+        write!(f, "= {:08b}  ({})", self.product, self.signed_product())
+    }
+}
+
+/// Which algorithm produced a [`DivTrace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DivMethod {
+    /// Subtract, and add the divisor back whenever the remainder goes
+    /// negative.
+    Restoring,
+    /// Keep a negative remainder and add the divisor on the next step
+    /// instead, with one correction at the end.
+    NonRestoring,
+}
+
+/// One quotient bit of a traced division.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DivStep {
+    /// Whether the divisor was subtracted from or added to the shifted
+    /// remainder.
+    pub op: AdderOp,
+    /// The partial remainder after the trial operation.
+    pub trial: i8,
+    /// Whether the trial was undone; only ever set by
+    /// [`DivMethod::Restoring`].
+    pub restored: bool,
+    pub quotient_bit: bool,
+    /// The partial remainder at the end of the step.
+    pub remainder: i8,
+}
+
+/// A traced unsigned 4-bit division.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DivTrace {
+    pub method: DivMethod,
+    pub dividend: U4,
+    pub divisor: U4,
+    /// One per quotient bit, most significant first.
+    pub steps: [DivStep; 4],
+    /// Whether a negative final remainder had the divisor added back; only
+    /// ever set by [`DivMethod::NonRestoring`].
+    pub corrected: bool,
+    pub quotient: U4,
+    pub remainder: U4,
+}
+
+fn divide(method: DivMethod, dividend: U4, divisor: U4) -> Option<DivTrace> {
+    if divisor.to_u8() == 0 {
+        return None;
+    }
+    let m = divisor.to_u8() as i8;
+    let (mut a, mut q) = (0_i8, dividend.to_u8());
+    let steps = [(); 4].map(|()| {
+        let op = if a < 0 { AdderOp::Add } else { AdderOp::Sub };
+        a = (a << 1) | (q >> 3) as i8;
+        q = (q << 1) & 0xf;
+        a += if op == AdderOp::Add { m } else { -m };
+        let trial = a;
+        let quotient_bit = a >= 0;
+        let restored = method == DivMethod::Restoring && !quotient_bit;
+        if restored {
+            a += m;
+        }
+        q |= quotient_bit as u8;
+        DivStep {
+            op,
+            trial,
+            restored,
+            quotient_bit,
+            remainder: a,
+        }
+    });
+    let corrected = a < 0;
+    if corrected {
+        a += m;
+    }
+    Some(DivTrace {
+        method,
+        dividend,
+        divisor,
+        steps,
+        corrected,
+        quotient: U4::from_u8(q),
+        remainder: U4::from_u8(a as u8),
+    })
+}
+
+/// Traces `dividend / divisor` by restoring division, or returns `None`
+/// if `divisor` is zero.
+pub fn div_restoring(dividend: U4, divisor: U4) -> Option<DivTrace> {
+    divide(DivMethod::Restoring, dividend, divisor)
+}
+
+/// Traces `dividend / divisor` by non-restoring division, or returns
+/// `None` if `divisor` is zero.
+pub fn div_non_restoring(dividend: U4, divisor: U4) -> Option<DivTrace> {
+    divide(DivMethod::NonRestoring, dividend, divisor)
+}
+
+impl Display for DivTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:04b} / {:04b}  ({} / {})",
+            self.dividend.to_u8(),
+            self.divisor.to_u8(),
+            self.dividend,
+            self.divisor
+        )?;
+        for (i, s) in self.steps.iter().enumerate() {
+            let op = match s.op {
+                AdderOp::Add => '+',
+                AdderOp::Sub => '-',
+            };
+            write!(f, "bit {}: shift, {op} divisor = {:>3}", 3 - i, s.trial)?;
+            if s.restored {
+                write!(f, ", restore to {}", s.remainder)?;
+            }
+            writeln!(f, ", q = {}", s.quotient_bit as u8)?;
+        }
+        if self.corrected {
+            writeln!(f, "correct: + divisor = {}", self.remainder)?;
+        }
+        write!(
+            f,
+            "= {:04b} remainder {:04b}  ({} r {})",
+            self.quotient.to_u8(),
+            self.remainder.to_u8(),
+            self.quotient,
+            self.remainder
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_arithmetic() {
+        for a in (0..16).map(U4::n) {
+            for b in (0..16).map(U4::n) {
+                for c in [false, true] {
+                    let sum = a.to_u8() + b.to_u8() + c as u8;
+                    let t = add(a, b, c);
+                    assert_eq!((t.result, t.carry), (U4::from_u8(sum), sum > 15));
+
+                    let t = sub(a, b, c);
+                    let borrow = b.to_u8() + c as u8 > a.to_u8();
+                    let diff = a.to_u8().wrapping_sub(b.to_u8() + c as u8);
+                    assert_eq!((t.result, t.carry), (U4::from_u8(diff), borrow));
+                    assert!(t.steps.windows(2).all(|w| w[0].carry_out == w[1].carry_in));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn multiplication() {
+        for a in (0..16).map(U4::n) {
+            for b in (0..16).map(U4::n) {
+                let t = mul_shift_add(a, b);
+                assert_eq!(t.product, a.to_u8() * b.to_u8());
+                assert_eq!(t.steps[3].accumulator, t.product);
+
+                let t = mul_booth(a, b);
+                assert_eq!(t.signed_product(), signed(a) * signed(b), "{a} x {b}");
+            }
+        }
+        let actions = mul_booth(U4::n(3), U4::n(0b0110)).steps.map(|s| s.action);
+        assert_eq!(
+            actions,
+            [
+                BoothAction::None,
+                BoothAction::Sub,
+                BoothAction::None,
+                BoothAction::Add
+            ]
+        );
+        assert!(mul_shift_add(U4::n(5), U4::n(3))
+            .to_string()
+            .ends_with("= 00001111  (15)"));
+        assert!(mul_booth(U4::n(0x8), U4::n(0x8))
+            .to_string()
+            .ends_with("(64)"));
+    }
+
+    #[test]
+    fn division() {
+        for a in (0..16).map(U4::n) {
+            assert_eq!(div_restoring(a, U4::n(0)), None);
+            assert_eq!(div_non_restoring(a, U4::n(0)), None);
+            for b in (1..16).map(U4::n) {
+                for t in [div_restoring(a, b), div_non_restoring(a, b)] {
+                    let t = t.unwrap();
+                    assert_eq!(t.quotient.to_u8(), a.to_u8() / b.to_u8());
+                    assert_eq!(t.remainder.to_u8(), a.to_u8() % b.to_u8());
+                }
+                let t = div_restoring(a, b).unwrap();
+                assert!(!t.corrected);
+                assert!(t
+                    .steps
+                    .iter()
+                    .all(|s| s.op == AdderOp::Sub && s.remainder >= 0));
+                let t = div_non_restoring(a, b).unwrap();
+                assert!(t.steps.iter().all(|s| !s.restored));
+            }
+        }
+
+        let t = div_non_restoring(U4::n(7), U4::n(2)).unwrap();
+        assert_eq!(t.steps.map(|s| s.quotient_bit), [false, false, true, true]);
+        assert_eq!(t.steps.map(|s| s.op)[..2], [AdderOp::Sub, AdderOp::Add]);
+        assert!(div_restoring(U4::n(7), U4::n(2))
+            .unwrap()
+            .to_string()
+            .ends_with("= 0011 remainder 0001  (3 r 1)"));
+    }
+
+    #[test]
+    fn display() {
+        let text = sub(U4::n(4), U4::n(7), false).to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "  0100  (4)",
+                "- 0111  (7)",
+                "  1110  borrow in",
+                "= 1101  (13), borrow out 1",
+                "bit 0: 0 - 1 - borrow 0 = 1, borrow 1",
+                "bit 1: 0 - 1 - borrow 1 = 0, borrow 1",
+                "bit 2: 1 - 1 - borrow 1 = 1, borrow 1",
+                "bit 3: 0 - 0 - borrow 1 = 1, borrow 1",
+            ]
+        );
+    }
+}
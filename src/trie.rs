@@ -0,0 +1,262 @@
+//! A radix-16 trie keyed by nibble sequences.
+
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::U4;
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    value: Option<V>,
+    children: [Option<Box<Node<V>>>; 16],
+}
+
+impl<V> Default for Node<V> {
+    fn default() -> Self {
+        Self {
+            value: None,
+            children: Default::default(),
+        }
+    }
+}
+
+impl<V> Node<V> {
+    fn is_empty(&self) -> bool {
+        self.value.is_none() && self.children.iter().all(Option::is_none)
+    }
+
+    fn find(&self, key: &[U4]) -> Option<&Self> {
+        key.iter()
+            .try_fold(self, |node, n| node.children[n.to_u8() as usize].as_deref())
+    }
+
+    fn remove(&mut self, key: &[U4]) -> Option<V> {
+        let Some((first, rest)) = key.split_first() else {
+            return self.value.take();
+        };
+        let slot = &mut self.children[first.to_u8() as usize];
+        let child = slot.as_deref_mut()?;
+        let value = child.remove(rest);
+        if child.is_empty() {
+            *slot = None;
+        }
+        value
+    }
+}
+
+/// A map from nibble sequences to values, iterated in key order.
+///
+/// ```
+/// use u4::{trie::NibbleTrie, U4};
+///
+/// let mut routes = NibbleTrie::new();
+/// routes.insert(&[U4::n(0xa)], "a");
+/// routes.insert(&[U4::n(0xa), U4::n(0xb)], "ab");
+///
+/// let key = [0xa, 0xb, 0xc].map(U4::n);
+/// assert_eq!(routes.longest_prefix(&key), Some((2, &"ab")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct NibbleTrie<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+impl<V> Default for NibbleTrie<V> {
+    fn default() -> Self {
+        Self {
+            root: Node::default(),
+            len: 0,
+        }
+    }
+}
+
+impl<V> NibbleTrie<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `value` at `key`, returning the value it replaced.
+    pub fn insert(&mut self, key: &[U4], value: V) -> Option<V> {
+        let mut node = &mut self.root;
+        for n in key {
+            node = node.children[n.to_u8() as usize].get_or_insert_with(Box::default);
+        }
+        let old = node.value.replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn get(&self, key: &[U4]) -> Option<&V> {
+        self.root.find(key)?.value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, key: &[U4]) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        for n in key {
+            node = node.children[n.to_u8() as usize].as_deref_mut()?;
+        }
+        node.value.as_mut()
+    }
+
+    pub fn contains_key(&self, key: &[U4]) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key`, pruning branches left without values.
+    pub fn remove(&mut self, key: &[U4]) -> Option<V> {
+        let value = self.root.remove(key);
+        if value.is_some() {
+            self.len -= 1;
+        }
+        value
+    }
+
+    /// The longest prefix of `key` that has a value, as its length and the
+    /// value.
+    pub fn longest_prefix(&self, key: &[U4]) -> Option<(usize, &V)> {
+        let mut node = &self.root;
+        let mut best = node.value.as_ref().map(|v| (0, v));
+        for (i, n) in key.iter().enumerate() {
+            match node.children[n.to_u8() as usize].as_deref() {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(v) = &node.value {
+                best = Some((i + 1, v));
+            }
+        }
+        best
+    }
+
+    /// Entries in key order; a key comes before its extensions.
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter {
+            stack: Vec::from([(&self.root, 0)]),
+            path: Vec::new(),
+            remaining: self.len,
+        }
+    }
+}
+
+impl<K: AsRef<[U4]>, V> FromIterator<(K, V)> for NibbleTrie<V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        trie.extend(iter);
+        trie
+    }
+}
+
+impl<K: AsRef<[U4]>, V> Extend<(K, V)> for NibbleTrie<V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k.as_ref(), v);
+        }
+    }
+}
+
+impl<'a, V> IntoIterator for &'a NibbleTrie<V> {
+    type Item = (Vec<U4>, &'a V);
+    type IntoIter = Iter<'a, V>;
+
+    fn into_iter(self) -> Iter<'a, V> {
+        self.iter()
+    }
+}
+
+/// Iterator over the entries of a [`NibbleTrie`].
+#[derive(Debug, Clone)]
+pub struct Iter<'a, V> {
+    /// Each node on the current path with its next step: 0 to yield its
+    /// value, `i + 1` to descend into child `i`.
+    stack: Vec<(&'a Node<V>, usize)>,
+    path: Vec<U4>,
+    remaining: usize,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Vec<U4>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, step) = self.stack.last_mut()?;
+            let node: &'a Node<V> = node;
+            if *step == 0 {
+                *step = 1;
+                if let Some(v) = &node.value {
+                    self.remaining -= 1;
+                    return Some((self.path.clone(), v));
+                }
+            } else if *step > 16 {
+                self.stack.pop();
+                self.path.pop();
+            } else {
+                let i = *step - 1;
+                *step += 1;
+                if let Some(child) = node.children[i].as_deref() {
+                    self.path.push(U4::from_u8(i as u8));
+                    self.stack.push((child, 0));
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<V> ExactSizeIterator for Iter<'_, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> Vec<U4> {
+        s.bytes().map(|c| U4::from_hex_digit(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn insert_get_remove() {
+        let mut trie = NibbleTrie::new();
+        assert_eq!(trie.insert(&key("12"), 1), None);
+        assert_eq!(trie.insert(&key("12"), 2), Some(1));
+        trie.insert(&key(""), 0);
+        trie.insert(&key("123"), 3);
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.get(&key("12")), Some(&2));
+        assert_eq!(trie.get(&key("1")), None);
+        *trie.get_mut(&key("123")).unwrap() += 10;
+
+        assert_eq!(trie.remove(&key("12")), Some(2));
+        assert_eq!(trie.remove(&key("12")), None);
+        assert_eq!(trie.get(&key("123")), Some(&13));
+        assert_eq!(trie.remove(&key("123")), Some(13));
+        assert!(trie.root.children.iter().all(Option::is_none));
+        assert_eq!(trie.len(), 1);
+    }
+
+    #[test]
+    fn prefixes_and_order() {
+        let trie: NibbleTrie<&str> = ["f", "0a", "", "0", "0a1", "b"]
+            .into_iter()
+            .map(|k| (key(k), k))
+            .collect();
+        assert_eq!(trie.longest_prefix(&key("0a2")), Some((2, &"0a")));
+        assert_eq!(trie.longest_prefix(&key("c")), Some((0, &"")));
+
+        let keys: Vec<&str> = trie.iter().map(|(_, v)| *v).collect();
+        assert_eq!(keys, ["", "0", "0a", "0a1", "b", "f"]);
+        assert!(trie.iter().all(|(k, v)| k == key(v)));
+        assert_eq!(trie.iter().len(), 6);
+    }
+}
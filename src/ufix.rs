@@ -0,0 +1,211 @@
+use core::fmt::{self, Display, Formatter};
+
+use crate::U4;
+
+/// How to round a result that falls between two representable values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Toward zero.
+    Down,
+    /// Away from zero.
+    Up,
+    /// To the nearest value, ties away from zero.
+    #[default]
+    Nearest,
+    /// To the nearest value, ties to even.
+    NearestEven,
+}
+
+impl Rounding {
+    /// `n / d` rounded.
+    pub(crate) const fn div(self, n: u32, d: u32) -> u32 {
+        let (q, r) = (n / d, n % d);
+        let up = match self {
+            Rounding::Down => false,
+            Rounding::Up => r > 0,
+            Rounding::Nearest => 2 * r >= d,
+            Rounding::NearestEven => 2 * r > d || (2 * r == d && q % 2 == 1),
+        };
+        q + up as u32
+    }
+}
+
+/// An unsigned fraction `n / 16` for a nibble `n`, so `0..=15/16`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UFix4(U4);
+
+impl UFix4 {
+    pub const ZERO: UFix4 = UFix4(U4::MIN);
+    /// 15/16.
+    pub const MAX: UFix4 = UFix4(U4::MAX);
+    /// 1/16, the step between adjacent values.
+    pub const EPSILON: UFix4 = UFix4(U4::from_u8(1));
+
+    pub const fn from_bits(bits: U4) -> Self {
+        Self(bits)
+    }
+
+    pub const fn to_bits(self) -> U4 {
+        self.0
+    }
+
+    /// The nearest value to `v` under `rounding`, saturating outside
+    /// `0..=15/16`. NaN maps to zero.
+    pub fn from_f32(v: f32, rounding: Rounding) -> Self {
+        if v.is_nan() || v <= 0.0 {
+            return Self::ZERO;
+        }
+        if v >= 1.0 {
+            return Self::MAX;
+        }
+        // Scaling by a power of two is exact, as is the fractional part.
+        let scaled = v * 16.0;
+        let q = scaled as u32;
+        let frac = scaled - q as f32;
+        let up = match rounding {
+            Rounding::Down => false,
+            Rounding::Up => frac > 0.0,
+            Rounding::Nearest => frac >= 0.5,
+            Rounding::NearestEven => frac > 0.5 || (frac == 0.5 && q % 2 == 1),
+        };
+        Self(U4::from_u8((q + up as u32).min(15) as u8))
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0.to_u8() as f32 / 16.0
+    }
+
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        let sum = self.0.to_u8() + rhs.0.to_u8();
+        Self(U4::from_u8(if sum > 15 { 15 } else { sum }))
+    }
+
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        Self(U4::from_u8(self.0.to_u8().saturating_sub(rhs.0.to_u8())))
+    }
+
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        let sum = self.0.to_u8() + rhs.0.to_u8();
+        if sum > 15 {
+            None
+        } else {
+            Some(Self(U4::from_u8(sum)))
+        }
+    }
+
+    /// The product, which never overflows, rounded back to sixteenths.
+    pub const fn mul(self, rhs: Self, rounding: Rounding) -> Self {
+        let product = self.0.to_u8() as u32 * rhs.0.to_u8() as u32;
+        Self(U4::from_u8(rounding.div(product, 16) as u8))
+    }
+
+    /// `v * self`, as when applying a 4-bit alpha or coefficient to a byte.
+    pub const fn scale(self, v: u8, rounding: Rounding) -> u8 {
+        rounding.div(v as u32 * self.0.to_u8() as u32, 16) as u8
+    }
+}
+
+impl From<U4> for UFix4 {
+    fn from(bits: U4) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<UFix4> for f32 {
+    fn from(v: UFix4) -> Self {
+        v.to_f32()
+    }
+}
+
+impl From<UFix4> for f64 {
+    fn from(v: UFix4) -> Self {
+        v.0.to_u8() as f64 / 16.0
+    }
+}
+
+/// Formats the exact decimal value, such as `0.5625`.
+impl Display for UFix4 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut frac = self.0.to_u8() as u32 * 625;
+        if frac == 0 {
+            return write!(f, "0");
+        }
+        let mut digits = 4;
+        while frac.is_multiple_of(10) {
+            frac /= 10;
+            digits -= 1;
+        }
+        write!(f, "0.{frac:0digits$}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(n: u8) -> UFix4 {
+        UFix4::from_bits(U4::n(n))
+    }
+
+    #[test]
+    fn rounding() {
+        let cases = [
+            (Rounding::Down, [1, 1, 1, 1]),
+            (Rounding::Up, [1, 2, 2, 2]),
+            (Rounding::Nearest, [1, 1, 2, 2]),
+            (Rounding::NearestEven, [1, 1, 2, 2]),
+        ];
+        for (rounding, expected) in cases {
+            // 1, 1.25, 1.5 and 1.75.
+            assert_eq!([16, 20, 24, 28].map(|n| rounding.div(n, 16)), expected);
+        }
+        assert_eq!(Rounding::NearestEven.div(40, 16), 2);
+        assert_eq!(Rounding::Nearest.div(40, 16), 3);
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(fix(12).saturating_add(fix(6)), UFix4::MAX);
+        assert_eq!(fix(3).saturating_sub(fix(6)), UFix4::ZERO);
+        assert_eq!(fix(9).checked_add(fix(6)), Some(UFix4::MAX));
+        assert_eq!(fix(9).checked_add(fix(7)), None);
+
+        // 8/16 * 3/16 = 1.5/16.
+        assert_eq!(fix(8).mul(fix(3), Rounding::Down), fix(1));
+        assert_eq!(fix(8).mul(fix(3), Rounding::Nearest), fix(2));
+        assert_eq!(fix(8).mul(fix(3), Rounding::NearestEven), fix(2));
+        assert_eq!(fix(8).mul(fix(5), Rounding::NearestEven), fix(2));
+        assert_eq!(UFix4::MAX.mul(UFix4::MAX, Rounding::Up), fix(15));
+
+        assert_eq!(fix(8).scale(255, Rounding::Down), 127);
+        assert_eq!(fix(8).scale(255, Rounding::Nearest), 128);
+        assert_eq!(UFix4::MAX.scale(16, Rounding::Down), 15);
+    }
+
+    #[test]
+    fn conversions() {
+        for n in 0..16 {
+            let f = fix(n);
+            assert_eq!(UFix4::from_f32(f.to_f32(), Rounding::Down), f);
+            assert_eq!(UFix4::from_f32(f.to_f32(), Rounding::Up), f);
+        }
+        assert_eq!(UFix4::from_f32(0.49, Rounding::Nearest), fix(8));
+        assert_eq!(UFix4::from_f32(0.49, Rounding::Down), fix(7));
+        assert_eq!(UFix4::from_f32(2.0, Rounding::Down), UFix4::MAX);
+        assert_eq!(UFix4::from_f32(-1.0, Rounding::Up), UFix4::ZERO);
+        assert_eq!(UFix4::from_f32(0.0001, Rounding::Up), UFix4::EPSILON);
+        assert_eq!(UFix4::from_f32(1.5 / 16.0, Rounding::NearestEven), fix(2));
+        assert_eq!(UFix4::from_f32(2.5 / 16.0, Rounding::NearestEven), fix(2));
+        assert_eq!(UFix4::from_f32(f32::NAN, Rounding::Up), UFix4::ZERO);
+        assert_eq!(f64::from(fix(4)), 0.25);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(fix(0).to_string(), "0");
+        assert_eq!(fix(8).to_string(), "0.5");
+        assert_eq!(fix(9).to_string(), "0.5625");
+        assert_eq!(fix(1).to_string(), "0.0625");
+        assert_eq!(UFix4::MAX.to_string(), "0.9375");
+    }
+}
@@ -0,0 +1,183 @@
+//! Variable-length integers in nibbles.
+//!
+//! Each nibble carries three data bits in its low bits and sets bit 3 when
+//! more nibbles follow, least significant group first: values below 8 take
+//! one nibble, below 64 two, and a `u64` at most 22.
+//!
+//! ```
+//! use u4::{varint, U4};
+//!
+//! let nibbles: Vec<U4> = [5, 300].into_iter().flat_map(varint::encode).collect();
+//! assert_eq!(nibbles.len(), 1 + 3);
+//! let values: Result<Vec<u64>, _> = varint::decode_all(nibbles).collect();
+//! assert_eq!(values, Ok(vec![5, 300]));
+//! ```
+
+use core::fmt::{self, Display, Formatter};
+
+use crate::U4;
+
+/// The most nibbles a `u64` takes.
+pub const MAX_LEN: usize = 22;
+
+const MORE: u8 = 0b1000;
+
+/// The nibbles could not be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarintError {
+    /// The input ended after a nibble with the continuation bit set.
+    Truncated,
+    /// The value does not fit in a `u64`.
+    Overflow,
+}
+
+impl Display for VarintError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => f.write_str("truncated nibble varint"),
+            Self::Overflow => f.write_str("nibble varint overflows u64"),
+        }
+    }
+}
+
+impl core::error::Error for VarintError {}
+
+/// The number of nibbles [`encode`] produces for `value`.
+pub const fn encoded_len(value: u64) -> usize {
+    let bits = 64 - value.leading_zeros() as usize;
+    if bits == 0 {
+        1
+    } else {
+        bits.div_ceil(3)
+    }
+}
+
+/// The nibbles encoding `value`.
+pub fn encode(value: u64) -> Encode {
+    Encode { value, done: false }
+}
+
+/// Iterator returned by [`encode`].
+#[derive(Debug, Clone)]
+pub struct Encode {
+    value: u64,
+    done: bool,
+}
+
+impl Iterator for Encode {
+    type Item = U4;
+
+    fn next(&mut self) -> Option<U4> {
+        if self.done {
+            return None;
+        }
+        let group = (self.value & 0b111) as u8;
+        self.value >>= 3;
+        self.done = self.value == 0;
+        Some(U4::from_u8(if self.done { group } else { group | MORE }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if self.done {
+            0
+        } else {
+            encoded_len(self.value)
+        };
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Encode {}
+
+/// Reads one value from the front of `nibbles`, leaving the rest in place.
+pub fn decode(nibbles: &mut impl Iterator<Item = U4>) -> Result<u64, VarintError> {
+    let mut value = 0_u64;
+    for shift in (0..).step_by(3) {
+        let n = nibbles.next().ok_or(VarintError::Truncated)?.to_u8();
+        let group = (n & 0b111) as u64;
+        if shift >= 64 || (shift > 61 && group >> (64 - shift) != 0) {
+            return Err(VarintError::Overflow);
+        }
+        value |= group << shift;
+        if n & MORE == 0 {
+            return Ok(value);
+        }
+    }
+    unreachable!()
+}
+
+/// Decodes values until `nibbles` runs out.
+pub fn decode_all<I: IntoIterator<Item = U4>>(nibbles: I) -> DecodeAll<I::IntoIter> {
+    DecodeAll {
+        nibbles: nibbles.into_iter().peekable(),
+    }
+}
+
+/// Iterator returned by [`decode_all`].
+#[derive(Debug, Clone)]
+pub struct DecodeAll<I: Iterator<Item = U4>> {
+    nibbles: core::iter::Peekable<I>,
+}
+
+impl<I: Iterator<Item = U4>> Iterator for DecodeAll<I> {
+    type Item = Result<u64, VarintError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nibbles.peek()?;
+        Some(decode(&mut self.nibbles))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for value in [
+            0,
+            1,
+            7,
+            8,
+            63,
+            64,
+            511,
+            512,
+            u32::MAX as u64,
+            u64::MAX - 1,
+            u64::MAX,
+        ] {
+            let nibbles: Vec<U4> = encode(value).collect();
+            assert_eq!(nibbles.len(), encoded_len(value), "{value}");
+            assert_eq!(encode(value).len(), nibbles.len());
+            assert_eq!(decode(&mut nibbles.into_iter()), Ok(value));
+        }
+        assert_eq!(encoded_len(u64::MAX), MAX_LEN);
+        assert_eq!(
+            encode(100).collect::<Vec<_>>(),
+            [0b1100, 0b1100, 0b0001].map(U4::n)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            decode(&mut [U4::n(0b1000)].into_iter()),
+            Err(VarintError::Truncated)
+        );
+        assert_eq!(
+            decode(&mut core::iter::empty()),
+            Err(VarintError::Truncated)
+        );
+
+        // u64::MAX with one more bit in the last group.
+        let mut too_big: Vec<U4> = encode(u64::MAX).collect();
+        *too_big.last_mut().unwrap() = U4::n(0b011);
+        assert_eq!(decode(&mut too_big.into_iter()), Err(VarintError::Overflow));
+        let endless = core::iter::repeat(U4::n(0b1000));
+        assert_eq!(decode(&mut endless.take(30)), Err(VarintError::Overflow));
+
+        let values: Vec<_> = decode_all([1, 0b1000].map(U4::n)).collect();
+        assert_eq!(values, [Ok(1), Err(VarintError::Truncated)]);
+    }
+}
@@ -0,0 +1,92 @@
+//! zeroize support.
+//!
+//! [`U4`] is [`DefaultIsZeroes`], so nibble arrays, slices and (with
+//! `alloc`) `Vec<U4>` holding key material can be wiped with
+//! [`Zeroize::zeroize`](::zeroize::Zeroize::zeroize) or wrapped in
+//! `Zeroizing`. The toy ciphers also wipe each derived round key after use,
+//! and their key types are wiped on drop.
+
+use ::zeroize::{DefaultIsZeroes, Zeroize, ZeroizeOnDrop};
+
+use crate::{mini_aes::RoundKeys, U4};
+
+impl DefaultIsZeroes for U4 {}
+
+impl Zeroize for RoundKeys {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for RoundKeys {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for RoundKeys {}
+
+#[cfg(feature = "alloc")]
+impl Zeroize for crate::toy_cipher::Key {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Drop for crate::toy_cipher::Key {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ZeroizeOnDrop for crate::toy_cipher::Key {}
+
+#[cfg(test)]
+mod tests {
+    use ::zeroize::Zeroizing;
+
+    use super::*;
+
+    fn wiped_on_drop<T: ZeroizeOnDrop>(_: &T) {}
+
+    #[test]
+    fn wipes() {
+        let mut key = [U4::MAX; 8];
+        key.zeroize();
+        assert_eq!(key, [U4::MIN; 8]);
+
+        let mut slice = [U4::n(3), U4::n(9)];
+        slice[..].zeroize();
+        assert_eq!(slice, [U4::MIN; 2]);
+
+        let key = Zeroizing::new([U4::n(7); 4]);
+        assert_eq!(key[0], U4::n(7));
+    }
+
+    #[test]
+    fn wipes_round_keys() {
+        let mut keys = RoundKeys::new(0xc3f0);
+        wiped_on_drop(&keys);
+        keys.zeroize();
+        assert_eq!(keys.0, [[U4::MIN; 4]; 3]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn wipes_toy_key() {
+        let mut key = crate::toy_cipher::Key::from(vec![U4::MAX; 4]);
+        wiped_on_drop(&key);
+        key.zeroize();
+        assert!(key.is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn wipes_vec() {
+        let mut key = vec![U4::MAX; 5];
+        key.zeroize();
+        assert!(key.is_empty());
+    }
+}